@@ -1,358 +1,12627 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::{
+    get_stack_height, AccountMeta, Instruction, TRANSACTION_LEVEL_STACK_HEIGHT,
+};
+use anchor_lang::solana_program::program::invoke;
 use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata::types::{Creator, DataV2};
+use anchor_spl::metadata::{self, CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata};
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+use std::cell::Cell;
 
 declare_id!("AvTfTNzZfqg666MTy6N4MaeMwdZxa8rBGgdsgkdGoXPK");
 
+// Upper bound on invitations created by a single invite_many call
+pub const MAX_INVITES_PER_TX: usize = 10;
+// Applicants a private job's whitelist can hold
+pub const MAX_WHITELIST_LEN: usize = 32;
+// Wallets that can act for an organization
+pub const MAX_ORG_ADMINS: usize = 10;
+// Job posts a client's JobIndex can list at once
+pub const MAX_INDEXED_JOBS: usize = 64;
+// Keys that can jointly sign off a job's submissions
+pub const MAX_JOB_APPROVERS: usize = 5;
+
+// Dispute deadlines, measured from when the dispute is opened
+pub const DISPUTE_RESPONSE_WINDOW_SECS: i64 = 7 * 24 * 60 * 60;
+pub const DISPUTE_RULING_WINDOW_SECS: i64 = 14 * 24 * 60 * 60;
+// Arbitrators disputes are assigned from, in turn
+pub const MAX_ARBITRATORS: usize = 64;
+// Minimum time a replacement arbiter gets to rule
+pub const ARBITER_REASSIGNMENT_WINDOW_SECS: i64 = 7 * 24 * 60 * 60;
+
+// Delay between requesting and completing a wallet migration, giving the
+// original owner time to cancel a migration started with a stolen key
+pub const WALLET_MIGRATION_DELAY_SECS: i64 = 3 * 24 * 60 * 60;
+
+// Beneficiary contingency: the shortest inactivity horizon a user may set, and
+// the delay after a beneficiary claim during which the owner can still cancel
+pub const MIN_BENEFICIARY_INACTIVITY_SECS: i64 = 180 * 24 * 60 * 60;
+pub const BENEFICIARY_CLAIM_DELAY_SECS: i64 = 30 * 24 * 60 * 60;
+
+// How long a client has to review submitted work before anyone can release payment;
+// clients may choose a different window before hiring, but not below the minimum
+pub const DEFAULT_REVIEW_WINDOW_SECS: i64 = 14 * 24 * 60 * 60;
+pub const MIN_REVIEW_WINDOW_SECS: i64 = 24 * 60 * 60;
+
+// How long a freelancer has to answer a check-in nonce
+pub const CHECK_IN_WINDOW_SECS: i64 = 15 * 60;
+
+// Upper bound on tranches, and so on the periods in a job's payment schedule
+pub const MAX_SCHEDULE_PERIODS: usize = 24;
+
+// Oracle prices older than this cannot be used to value escrowed collateral
+pub const MAX_PRICE_AGE_SECS: i64 = 10 * 60;
+
+// Number of recent events kept in the global activity feed
+pub const ACTIVITY_FEED_CAPACITY: usize = 32;
+// Number of recent payouts kept in each freelancer's work history
+pub const WORK_HISTORY_CAPACITY: usize = 16;
+
+// Size caps for job and application extension accounts
+pub const MAX_EXTENSION_FIELDS: usize = 8;
+pub const MAX_EXTENSION_KEY_LEN: usize = 32;
+pub const MAX_EXTENSION_TEXT_LEN: usize = 64;
+
+// Unapproved entries an hourly contract's time log can hold, and their memo size
+pub const MAX_TIME_ENTRIES: usize = 16;
+pub const MAX_TIME_MEMO_LEN: usize = 100;
+
+// Recipients a freelancer's payout can be split between
+pub const MAX_PAYOUT_SPLITS: usize = 8;
+
+// Completion certificates are Metaplex NFTs named after the job and amount; their
+// uri is the configured base followed by a query naming the job, client and amount
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = metadata::ID;
+pub const CERTIFICATE_SYMBOL: &str = "LPWORK";
+pub const MAX_CERTIFICATE_NAME_LEN: usize = 32;
+pub const MAX_CERTIFICATE_URI_BASE_LEN: usize = 64;
+
+// Star ratings run from 1 to MAX_RATING; 0 means not yet rated
+pub const MAX_RATING: u8 = 5;
+
+pub const MAX_NAME_LEN: usize = 50;
+// Usernames are unique lowercase handles; 32 bytes is also the PDA seed limit
+pub const MIN_HANDLE_LEN: usize = 3;
+pub const MAX_HANDLE_LEN: usize = 32;
+// Skill tags on a freelancer profile and tags on a job post
+pub const MAX_SKILLS: usize = 10;
+pub const MAX_JOB_TAGS: usize = 5;
+pub const MAX_TAG_LEN: usize = 32;
+// JobPost text limits
+pub const MAX_TITLE_LEN: usize = 100;
+pub const MAX_DESCRIPTION_LEN: usize = 500;
+// Application text limits
+pub const MAX_SUBMISSION_LINK_LEN: usize = 200;
+pub const MAX_NARRATION_LEN: usize = 300;
+pub const MAX_REVIEW_LEN: usize = 300;
+// Capacity of a JobPostLarge description, written in chunks
+pub const MAX_LARGE_DESCRIPTION_LEN: usize = 8 * 1024;
+// Off-chain content references, e.g. "ar://<tx id>" or "ipfs://<cid>"
+pub const MAX_METADATA_URI_LEN: usize = 128;
+pub const METADATA_URI_SCHEMES: [&str; 3] = ["ar://", "ipfs://", "https://"];
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+// Late penalties accrue per started day past the end date
+pub const SECONDS_PER_DAY: i64 = 86_400;
+// Longest a single boost_job_post call can feature a job for
+pub const MAX_BOOST_SECS: i64 = 30 * SECONDS_PER_DAY;
+// Ceiling on the protocol fee, so a compromised authority cannot confiscate payouts
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+// Lamport payouts must never land in token accounts, which would strand them
+pub const TOKEN_PROGRAM_IDS: [Pubkey; 2] = [
+    pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+    pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PQ1FvXfcGDwfnc"),
+];
+// Reputation earned per paid job, and the weight of one strike for losing a dispute
+pub const REPUTATION_POINTS_PER_JOB: u64 = 100;
+pub const STRIKE_POINTS: u64 = 100;
+
 #[program]
 pub mod lp_program {
     use super::*;
 
-    pub fn register_user(ctx: Context<RegisterUser>, name: String, role: UserRole) -> Result<()> {
+    // Only the program's upgrade authority can create the registry, and it alone
+    // vets who arbitrates
+    pub fn initialize_arbitrator_registry(
+        ctx: Context<InitializeArbitratorRegistry>,
+    ) -> Result<()> {
+        require_top_level()?;
+        let registry = &mut ctx.accounts.arbitrator_registry;
+        registry.authority = ctx.accounts.signer.key();
+        registry.arbitrators = Vec::new();
+        registry.next_index = 0;
+        registry.bump = ctx.bumps.arbitrator_registry;
+
+        msg!(
+            "Arbitrator registry initialized with authority: {}",
+            registry.authority
+        );
+        Ok(())
+    }
+
+    // Vets a user as an arbitrator; they still need to stake before disputes
+    // are assigned to them
+    pub fn grant_arbitrator_role(ctx: Context<GrantArbitratorRole>) -> Result<()> {
+        require_top_level()?;
         let user = &mut ctx.accounts.user_account;
-        user.wallet = ctx.accounts.signer.key();
-        user.name = name;
-        user.role = role;
+        user.roles |= ROLE_ARBITRATOR;
 
-        msg!("User registered: {} as {:?}", user.name, user.role);
+        msg!("{} may now arbitrate", user.wallet);
         Ok(())
     }
 
-    // Note: start_date and end_date are i64 unix timestamps (seconds)
-    #[allow(clippy::too_many_arguments)]
-    pub fn initialize_job_post(
-        ctx: Context<InitializeJobPost>,
-        title: String,
-        description: String,
-        amount: u64,
-        start_date: i64,
-        end_date: i64,
+    // Only the program's upgrade authority can create the global config
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        dispute_window_secs: i64,
     ) -> Result<()> {
-        // Only clients can post jobs
-        require!(
-            ctx.accounts.user_account.role == UserRole::Client,
-            ErrorCode::Unauthorized
-        );
+        require_top_level()?;
+        require!(dispute_window_secs > 0, ErrorCode::InvalidConfig);
 
-        // Validation: start_date must be <= end_date, and start_date must not be in the past
-        require!(start_date <= end_date, ErrorCode::InvalidDates);
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.signer.key();
+        config.dispute_window_secs = dispute_window_secs;
+        config.bump = ctx.bumps.config;
 
-        let clock = Clock::get()?;
-        require!(start_date >= clock.unix_timestamp, ErrorCode::InvalidDates);
+        msg!("Config initialized with authority: {}", config.authority);
+        Ok(())
+    }
 
-        let job_post = &mut ctx.accounts.job_post;
-        job_post.client = ctx.accounts.user_account.wallet;
-        job_post.title = title;
-        job_post.description = description;
-        job_post.amount = amount;
-        job_post.is_filled = false;
-        job_post.escrow_bump = ctx.bumps.escrow;
-        job_post.start_date = start_date;
-        job_post.end_date = end_date;
+    pub fn set_dispute_window(ctx: Context<UpdateConfig>, dispute_window_secs: i64) -> Result<()> {
+        require_top_level()?;
+        require!(dispute_window_secs > 0, ErrorCode::InvalidConfig);
 
-        // Transfer funds to escrow
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.signer.to_account_info(),
-                to: ctx.accounts.escrow.to_account_info(),
-            },
+        let config = &mut ctx.accounts.config;
+        config.dispute_window_secs = dispute_window_secs;
+
+        msg!("Dispute window set to {} seconds", dispute_window_secs);
+        Ok(())
+    }
+
+    // Decay is applied per elapsed period; a period of 0 disables decay
+    pub fn set_reputation_decay(
+        ctx: Context<UpdateConfig>,
+        reputation_decay_bps: u16,
+        strike_decay_bps: u16,
+        decay_period_secs: i64,
+    ) -> Result<()> {
+        require_top_level()?;
+        require!(
+            u64::from(reputation_decay_bps) <= BPS_DENOMINATOR
+                && u64::from(strike_decay_bps) <= BPS_DENOMINATOR
+                && decay_period_secs >= 0,
+            ErrorCode::InvalidConfig
         );
-        system_program::transfer(cpi_context, amount)?;
+
+        let config = &mut ctx.accounts.config;
+        config.reputation_decay_bps = reputation_decay_bps;
+        config.strike_decay_bps = strike_decay_bps;
+        config.decay_period_secs = decay_period_secs;
 
         msg!(
-            "Job post created with amount: {} start: {} end: {}",
-            amount,
-            job_post.start_date,
-            job_post.end_date
+            "Reputation decay set to {} bps, strike decay {} bps, every {} seconds",
+            reputation_decay_bps,
+            strike_decay_bps,
+            decay_period_secs
         );
         Ok(())
     }
 
-    // Allow freelancer to include expected_end_date when applying
-    pub fn apply_to_job(
-        ctx: Context<ApplyToJob>,
-        resume_link: String,
-        expected_end_date: i64,
+    // USD-denominated token jobs must be funded with at least this share of the
+    // agreed value, e.g. 15_000 for 150%
+    pub fn set_collateral_ratio(
+        ctx: Context<UpdateConfig>,
+        collateral_ratio_bps: u16,
     ) -> Result<()> {
-        // Only freelancers can apply
+        require_top_level()?;
         require!(
-            ctx.accounts.user_account.role == UserRole::Freelancer,
-            ErrorCode::Unauthorized
+            u64::from(collateral_ratio_bps) >= BPS_DENOMINATOR,
+            ErrorCode::InvalidConfig
         );
 
-        // Validation: freelancer's expected_end_date must be a valid unix timestamp (non-negative)
-        require!(expected_end_date >= 0, ErrorCode::InvalidDates);
+        let config = &mut ctx.accounts.config;
+        config.collateral_ratio_bps = collateral_ratio_bps;
 
-        let application = &mut ctx.accounts.application;
-        application.applicant = ctx.accounts.user_account.wallet;
-        application.job_post = ctx.accounts.job_post.key();
-        application.resume_link = resume_link;
-        application.approved = false;
-        application.completed = false;
-        application.submission_link = String::new();
-        application.narration = String::new();
-        application.client_review = String::new();
-        application.expected_end_date = expected_end_date;
+        msg!("Collateral ratio set to {} bps", collateral_ratio_bps);
+        Ok(())
+    }
+
+    // Governance names the oracle allowed to publish a mint's USD price
+    pub fn register_price_feed(
+        ctx: Context<RegisterPriceFeed>,
+        mint: Pubkey,
+        oracle: Pubkey,
+    ) -> Result<()> {
+        require_top_level()?;
+
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.mint = mint;
+        price_feed.oracle = oracle;
+        price_feed.bump = ctx.bumps.price_feed;
+
+        msg!("Price feed for {} registered with oracle {}", mint, oracle);
+        Ok(())
+    }
+
+    // Users must accept the new version before opening new engagements
+    pub fn set_terms_version(ctx: Context<UpdateConfig>, terms_version: u32) -> Result<()> {
+        require_top_level()?;
+        let config = &mut ctx.accounts.config;
+        require!(
+            terms_version > config.terms_version,
+            ErrorCode::InvalidConfig
+        );
+        config.terms_version = terms_version;
+
+        msg!("Protocol terms bumped to version {}", terms_version);
+        Ok(())
+    }
+
+    // Extra time past a job's end date before an undelivered job can be reclaimed
+    pub fn set_delivery_grace_period(
+        ctx: Context<UpdateConfig>,
+        delivery_grace_secs: i64,
+    ) -> Result<()> {
+        require_top_level()?;
+        require!(delivery_grace_secs >= 0, ErrorCode::InvalidConfig);
+
+        let config = &mut ctx.accounts.config;
+        config.delivery_grace_secs = delivery_grace_secs;
 
         msg!(
-            "Application submitted with resume: {} expected_end_date: {}",
-            application.resume_link,
-            application.expected_end_date
+            "Delivery grace period set to {} seconds",
+            delivery_grace_secs
         );
         Ok(())
     }
 
-    pub fn approve_application(ctx: Context<ApproveApplication>) -> Result<()> {
+    // Floor decrease_job_budget must leave a lamport job's budget at or above
+    pub fn set_min_job_amount(ctx: Context<UpdateConfig>, min_job_amount: u64) -> Result<()> {
+        require_top_level()?;
+
+        let config = &mut ctx.accounts.config;
+        config.min_job_amount = min_job_amount;
+
+        msg!("Minimum job amount set to {} lamports", min_job_amount);
+        Ok(())
+    }
+
+    // Referrers receive share_bps of the protocol fees charged on each of a
+    // referred user's first job_limit lamport jobs; a limit of 0 disables sharing
+    pub fn set_referral_fee_share(
+        ctx: Context<UpdateConfig>,
+        share_bps: u16,
+        job_limit: u32,
+    ) -> Result<()> {
+        require_top_level()?;
         require!(
-            ctx.accounts.job_post.client == ctx.accounts.user_account.wallet,
-            ErrorCode::Unauthorized
+            u64::from(share_bps) <= BPS_DENOMINATOR,
+            ErrorCode::InvalidConfig
         );
-        require!(
-            ctx.accounts.user_account.role == UserRole::Client,
-            ErrorCode::Unauthorized
+
+        let config = &mut ctx.accounts.config;
+        config.referral_fee_share_bps = share_bps;
+        config.referral_job_limit = job_limit;
+
+        msg!(
+            "Referrers earn {} bps of fees on a user's first {} jobs",
+            share_bps,
+            job_limit
         );
+        Ok(())
+    }
+
+    // Lamports charged per started day of a featured listing; 0 disables boosting
+    pub fn set_boost_fee(ctx: Context<UpdateConfig>, boost_fee_per_day: u64) -> Result<()> {
+        require_top_level()?;
+
+        let config = &mut ctx.accounts.config;
+        config.boost_fee_per_day = boost_fee_per_day;
+
+        msg!("Boost fee set to {} lamports per day", boost_fee_per_day);
+        Ok(())
+    }
+
+    // Base of the off-chain uri completion certificates point to
+    pub fn set_certificate_base_uri(ctx: Context<UpdateConfig>, base_uri: String) -> Result<()> {
+        require_top_level()?;
         require!(
-            !ctx.accounts.job_post.is_filled,
-            ErrorCode::JobAlreadyFilled
+            base_uri.len() <= MAX_CERTIFICATE_URI_BASE_LEN,
+            ErrorCode::CertificateUriTooLong
         );
 
-        let application = &mut ctx.accounts.application;
-        application.approved = true;
-        ctx.accounts.job_post.is_filled = true;
+        let config = &mut ctx.accounts.config;
+        config.certificate_base_uri = base_uri;
 
         msg!(
-            "Application approved for job: {}",
-            ctx.accounts.job_post.title
+            "Certificate base uri set to {}",
+            config.certificate_base_uri
         );
         Ok(())
     }
 
-    pub fn submit_work(
-        ctx: Context<SubmitWork>,
-        submission_link: String,
-        narration: String,
+    // A threshold of 0 disables tranching
+    pub fn set_tranche_policy(
+        ctx: Context<UpdateConfig>,
+        tranche_threshold: u64,
+        tranche_count: u8,
     ) -> Result<()> {
+        require_top_level()?;
         require!(
-            ctx.accounts.user_account.role == UserRole::Freelancer,
-            ErrorCode::Unauthorized
+            tranche_threshold == 0
+                || (tranche_count >= 2 && usize::from(tranche_count) <= MAX_SCHEDULE_PERIODS),
+            ErrorCode::InvalidConfig
         );
-        require!(
-            ctx.accounts.application.applicant == ctx.accounts.user_account.wallet,
-            ErrorCode::Unauthorized
+
+        let config = &mut ctx.accounts.config;
+        config.tranche_threshold = tranche_threshold;
+        config.tranche_count = tranche_count;
+
+        msg!(
+            "Jobs above {} lamports are released in {} tranches",
+            tranche_threshold,
+            tranche_count
         );
+        Ok(())
+    }
+
+    // Once a client has used this many revisions, remaining disagreements go to a dispute
+    pub fn set_max_revisions(ctx: Context<UpdateConfig>, max_revisions: u8) -> Result<()> {
+        require_top_level()?;
+
+        let config = &mut ctx.accounts.config;
+        config.max_revisions = max_revisions;
+
+        msg!("Max revisions per application set to {}", max_revisions);
+        Ok(())
+    }
+
+    // Protocol fees sent to the treasury: the client side is charged on top of the
+    // budget at funding, the freelancer side is deducted from each approved payout
+    pub fn set_fee(
+        ctx: Context<UpdateConfig>,
+        client_fee_bps: u16,
+        freelancer_fee_bps: u16,
+        fee_treasury: Pubkey,
+    ) -> Result<()> {
+        require_top_level()?;
         require!(
-            ctx.accounts.application.approved,
-            ErrorCode::ApplicationNotApproved
+            client_fee_bps <= MAX_FEE_BPS && freelancer_fee_bps <= MAX_FEE_BPS,
+            ErrorCode::InvalidConfig
         );
 
-        let application = &mut ctx.accounts.application;
-        application.submission_link = submission_link;
-        application.narration = narration;
-        application.completed = true;
+        let config = &mut ctx.accounts.config;
+        config.client_fee_bps = client_fee_bps;
+        config.freelancer_fee_bps = freelancer_fee_bps;
+        config.fee_treasury = fee_treasury;
 
         msg!(
-            "Work submitted with link: {} and narration",
-            application.submission_link
+            "Protocol fee set to {} bps client side, {} bps freelancer side, treasury {}",
+            client_fee_bps,
+            freelancer_fee_bps,
+            fee_treasury
         );
         Ok(())
     }
 
-    pub fn approve_submission(
-        ctx: Context<ApproveSubmission>,
-        client_review: String,
+    // Freezes or resumes the marketplace; governance instructions keep working
+    pub fn set_pause(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        require_top_level()?;
+
+        let config = &mut ctx.accounts.config;
+        config.paused = paused;
+
+        msg!("Program paused: {}", paused);
+        Ok(())
+    }
+
+    pub fn set_arbitration_policy(
+        ctx: Context<UpdateConfig>,
+        min_arbitrator_stake: u64,
+        arbitrator_fee_bps: u16,
     ) -> Result<()> {
+        require_top_level()?;
+        require!(arbitrator_fee_bps <= MAX_FEE_BPS, ErrorCode::InvalidConfig);
+
+        let config = &mut ctx.accounts.config;
+        config.min_arbitrator_stake = min_arbitrator_stake;
+        config.arbitrator_fee_bps = arbitrator_fee_bps;
+
+        msg!(
+            "Arbitrators must stake {} lamports and earn {} bps per ruling",
+            min_arbitrator_stake,
+            arbitrator_fee_bps
+        );
+        Ok(())
+    }
+
+    pub fn set_dispute_bond(
+        ctx: Context<UpdateConfig>,
+        dispute_bond_bps: u16,
+        min_dispute_bond: u64,
+        max_dispute_bond: u64,
+    ) -> Result<()> {
+        require_top_level()?;
         require!(
-            ctx.accounts.job_post.client == ctx.accounts.user_account.wallet,
-            ErrorCode::Unauthorized
+            u64::from(dispute_bond_bps) <= BPS_DENOMINATOR
+                && (max_dispute_bond == 0 || min_dispute_bond <= max_dispute_bond),
+            ErrorCode::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.dispute_bond_bps = dispute_bond_bps;
+        config.min_dispute_bond = min_dispute_bond;
+        config.max_dispute_bond = max_dispute_bond;
+
+        msg!(
+            "Dispute bond set to {} bps, between {} and {} lamports",
+            dispute_bond_bps,
+            min_dispute_bond,
+            max_dispute_bond
         );
+        Ok(())
+    }
+
+    // Governance seizes part of an arbitrator's stake into the treasury for
+    // proven misbehavior; evidence_hash identifies the off-chain findings
+    pub fn slash_arbitrator(
+        ctx: Context<SlashArbitrator>,
+        amount: u64,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        require_top_level()?;
+        let stake = &mut ctx.accounts.arbitrator_stake;
         require!(
-            ctx.accounts.user_account.role == UserRole::Client,
-            ErrorCode::Unauthorized
+            amount > 0 && amount <= stake.staked,
+            ErrorCode::InvalidStakeAmount
         );
+
+        let stake_info = stake.to_account_info();
+        let treasury = ctx.accounts.fee_treasury.to_account_info();
+        transfer_from_escrow(&stake_info, &treasury, amount)?;
+        stake.staked -= amount;
+        stake.slashed_total += amount;
+
+        emit!(ArbitratorSlashed {
+            arbitrator: stake.arbitrator,
+            amount,
+            remaining_stake: stake.staked,
+            evidence_hash,
+        });
+        Ok(())
+    }
+
+    // Governance whitelists an external reputation issuer and its multiplier
+    pub fn register_reputation_issuer(
+        ctx: Context<RegisterReputationIssuer>,
+        issuer: Pubkey,
+        weight_bps: u16,
+    ) -> Result<()> {
+        require_top_level()?;
         require!(
-            ctx.accounts.application.completed,
-            ErrorCode::WorkNotCompleted
+            u64::from(weight_bps) <= BPS_DENOMINATOR,
+            ErrorCode::InvalidConfig
         );
 
-        // Save client review
-        let application = &mut ctx.accounts.application;
-        application.client_review = client_review;
+        let record = &mut ctx.accounts.issuer_record;
+        record.issuer = issuer;
+        record.weight_bps = weight_bps;
+        record.active = true;
+        record.bump = ctx.bumps.issuer_record;
 
-        // Transfer funds from escrow to freelancer
-        let job_post_key = ctx.accounts.job_post.key();
-        let seeds = &[
-            b"escrow",
-            job_post_key.as_ref(),
-            &[ctx.accounts.job_post.escrow_bump],
-        ];
-        let signer = &[&seeds[..]];
+        msg!(
+            "Reputation issuer {} registered at {} bps",
+            issuer,
+            weight_bps
+        );
+        Ok(())
+    }
 
-        let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.escrow.to_account_info(),
-                to: ctx.accounts.freelancer.to_account_info(),
-            },
-            signer,
+    // Existing attestations pick up the new weight through reweigh_attestation
+    pub fn update_reputation_issuer(
+        ctx: Context<UpdateReputationIssuer>,
+        weight_bps: u16,
+        active: bool,
+    ) -> Result<()> {
+        require_top_level()?;
+        require!(
+            u64::from(weight_bps) <= BPS_DENOMINATOR,
+            ErrorCode::InvalidConfig
         );
-        system_program::transfer(cpi_context, ctx.accounts.job_post.amount)?;
 
-        msg!("Submission approved, funds transferred, and review recorded");
+        let record = &mut ctx.accounts.issuer_record;
+        record.weight_bps = weight_bps;
+        record.active = active;
+
+        msg!(
+            "Reputation issuer {} updated: {} bps, active: {}",
+            record.issuer,
+            weight_bps,
+            active
+        );
         Ok(())
     }
-}
 
-#[account]
-#[derive(InitSpace)]
-pub struct UserAccount {
-    pub wallet: Pubkey,
-    #[max_len(50)]
-    pub name: String,
-    pub role: UserRole,
-}
+    // Governance whitelists a vesting program freelancers may route payouts into.
+    // deposit_discriminator prefixes the instruction data of the deposit CPI.
+    pub fn register_vesting_program(
+        ctx: Context<RegisterVestingProgram>,
+        program: Pubkey,
+        deposit_discriminator: [u8; 8],
+    ) -> Result<()> {
+        require_top_level()?;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
-pub enum UserRole {
-    Client,
-    Freelancer,
-}
+        let record = &mut ctx.accounts.vesting_program_record;
+        record.program = program;
+        record.deposit_discriminator = deposit_discriminator;
+        record.active = true;
+        record.bump = ctx.bumps.vesting_program_record;
 
-#[account]
-#[derive(InitSpace)]
-pub struct JobPost {
-    pub client: Pubkey,
-    #[max_len(100)]
-    pub title: String,
-    pub amount: u64,
-    #[max_len(500)]
-    pub description: String,
-    pub is_filled: bool,
-    pub escrow_bump: u8,
-    // New: start and end dates (unix timestamps, in seconds)
-    pub start_date: i64,
-    pub end_date: i64,
-}
+        msg!("Vesting program {} registered", program);
+        Ok(())
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct Application {
-    pub applicant: Pubkey,
-    pub job_post: Pubkey,
-    #[max_len(200)]
-    pub resume_link: String,
-    pub approved: bool,
-    pub completed: bool,
-    #[max_len(200)]
-    pub submission_link: String,
-    #[max_len(300)]
-    pub narration: String, // freelancer’s narration
-    #[max_len(300)]
-    pub client_review: String, // client’s review
-    // New: freelancer's expected end date for the job (unix timestamp, in seconds)
-    pub expected_end_date: i64,
-}
+    // Payouts routed to a deactivated program fall back to the freelancer's wallet
+    pub fn update_vesting_program(ctx: Context<UpdateVestingProgram>, active: bool) -> Result<()> {
+        require_top_level()?;
 
-#[derive(Accounts)]
-pub struct RegisterUser<'info> {
-    #[account(
+        let record = &mut ctx.accounts.vesting_program_record;
+        record.active = active;
+
+        msg!(
+            "Vesting program {} updated, active: {}",
+            record.program,
+            active
+        );
+        Ok(())
+    }
+
+    // price_e6 is micro-USD per whole token
+    pub fn update_price(ctx: Context<UpdatePrice>, price_e6: u64) -> Result<()> {
+        require!(price_e6 > 0, ErrorCode::InvalidPrice);
+
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.price_e6 = price_e6;
+        price_feed.updated_at = Clock::get()?.unix_timestamp;
+
+        msg!("Price of {} set to {} micro-USD", price_feed.mint, price_e6);
+        Ok(())
+    }
+
+    // One-time setup of the global activity feed; anyone may pay for it
+    pub fn initialize_activity_feed(ctx: Context<InitializeActivityFeed>) -> Result<()> {
+        let feed = &mut ctx.accounts.activity_feed;
+        feed.head = 0;
+        feed.total_events = 0;
+        feed.entries = Vec::new();
+        feed.bump = ctx.bumps.activity_feed;
+
+        msg!("Activity feed initialized");
+        Ok(())
+    }
+
+    // One-time setup of the global protocol stats; anyone may pay for it
+    pub fn initialize_protocol_stats(ctx: Context<InitializeProtocolStats>) -> Result<()> {
+        ctx.accounts.protocol_stats.bump = ctx.bumps.protocol_stats;
+
+        msg!("Protocol stats initialized");
+        Ok(())
+    }
+
+    // One-time setup of the global job ID counter; IDs start at 1
+    pub fn initialize_job_counter(ctx: Context<InitializeJobCounter>) -> Result<()> {
+        let job_counter = &mut ctx.accounts.job_counter;
+        job_counter.next_id = 1;
+        job_counter.bump = ctx.bumps.job_counter;
+
+        msg!("Job counter initialized");
+        Ok(())
+    }
+
+    // `handle` claims a unique username; the UsernameRecord PDA is seeded by it
+    pub fn register_user(
+        ctx: Context<RegisterUser>,
+        name: String,
+        role: UserRole,
+        handle: String,
+    ) -> Result<()> {
+        validate_user_name(&name)?;
+        validate_handle(&handle)?;
+        // Arbitrators are vetted by the authority, see grant_arbitrator_role
+        require!(
+            role != UserRole::Arbitrator,
+            ErrorCode::ArbitratorRoleRestricted
+        );
+
+        let user = &mut ctx.accounts.user_account;
+        user.wallet = ctx.accounts.signer.key();
+        user.name = name;
+        user.roles = role.flag();
+        user.last_active_at = Clock::get()?.unix_timestamp;
+        user.handle = handle;
+
+        let username = &mut ctx.accounts.username_record;
+        username.handle = user.handle.clone();
+        username.wallet = user.wallet;
+        username.bump = ctx.bumps.username_record;
+
+        let reputation = &mut ctx.accounts.reputation;
+        reputation.wallet = user.wallet;
+        reputation.last_decay_at = Clock::get()?.unix_timestamp;
+        reputation.bump = ctx.bumps.reputation;
+        let work_history = &mut ctx.accounts.work_history;
+        work_history.wallet = user.wallet;
+        work_history.bump = ctx.bumps.work_history;
+        ctx.accounts.protocol_stats.total_users += 1;
+
+        msg!(
+            "User registered: {} (@{}) as {:?}",
+            user.name,
+            user.handle,
+            role
+        );
+        emit!(UserRegistered {
+            wallet: user.wallet,
+            handle: user.handle.clone(),
+            role,
+            timestamp: user.last_active_at,
+        });
+        Ok(())
+    }
+
+    // Replaces the profile's roles with any non-empty combination of
+    // ROLE_CLIENT, ROLE_FREELANCER and ROLE_ARBITRATOR. ROLE_ARBITRATOR can be
+    // dropped here but only the authority grants it.
+    pub fn set_roles(ctx: Context<UpdateUserAccount>, roles: u8) -> Result<()> {
+        require!(
+            roles != 0 && roles & !ALL_ROLES == 0,
+            ErrorCode::InvalidRoles
+        );
+        let user = &mut ctx.accounts.user_account;
+        require!(
+            roles & ROLE_ARBITRATOR == 0 || user.has_role(UserRole::Arbitrator),
+            ErrorCode::ArbitratorRoleRestricted
+        );
+        user.roles = roles;
+
+        msg!("Roles of {} set to {:#05b}", user.wallet, roles);
+        Ok(())
+    }
+
+    // Records who referred this user; set once, and never to the user themselves
+    pub fn register_referral(ctx: Context<RegisterReferral>, referrer: Pubkey) -> Result<()> {
+        let user = &mut ctx.accounts.user_account;
+        require!(
+            user.referred_by.is_none(),
+            ErrorCode::ReferralAlreadyRegistered
+        );
+        require_keys_neq!(referrer, user.wallet, ErrorCode::SelfDealing);
+        user.referred_by = Some(referrer);
+
+        emit!(ReferralRegistered {
+            user: user.wallet,
+            referrer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("{} was referred by {}", user.wallet, referrer);
+        Ok(())
+    }
+
+    // Profile text can be corrected at any time; the role and wallet cannot change here
+    pub fn update_profile(ctx: Context<UpdateUserAccount>, name: String) -> Result<()> {
+        validate_user_name(&name)?;
+
+        let user = &mut ctx.accounts.user_account;
+        user.name = name;
+        user.last_active_at = Clock::get()?.unix_timestamp;
+
+        msg!("Profile updated: {}", user.name);
+        Ok(())
+    }
+
+    // Returns the profile's rent once nothing is in flight and releases the
+    // username. The Reputation record is kept so history, strikes included,
+    // cannot be shed, which also means the wallet cannot register again.
+    pub fn close_user_account(ctx: Context<CloseUserAccount>) -> Result<()> {
+        let user = &ctx.accounts.user_account;
+        require!(
+            user.open_job_posts == 0 && user.pending_applications == 0 && user.active_jobs == 0,
+            ErrorCode::UserAccountInUse
+        );
+        require!(
+            user.pending_wallet.is_none(),
+            ErrorCode::WalletMigrationPending
+        );
+
+        msg!(
+            "User account closed: {}, @{} released",
+            user.wallet,
+            user.handle
+        );
+        Ok(())
+    }
+
+    pub fn initiate_wallet_migration(
+        ctx: Context<UpdateUserAccount>,
+        new_wallet: Pubkey,
+    ) -> Result<()> {
+        require_keys_neq!(
+            new_wallet,
+            ctx.accounts.signer.key(),
+            ErrorCode::InvalidWalletMigration
+        );
+
+        let clock = Clock::get()?;
+        let user = &mut ctx.accounts.user_account;
+        user.pending_wallet = Some(new_wallet);
+        user.migration_available_at = clock.unix_timestamp + WALLET_MIGRATION_DELAY_SECS;
+
+        emit!(WalletMigrationInitiated {
+            old_wallet: user.wallet,
+            new_wallet,
+            available_at: user.migration_available_at,
+        });
+
+        msg!(
+            "Wallet migration to {} can be finalized after {}",
+            new_wallet,
+            user.migration_available_at
+        );
+        Ok(())
+    }
+
+    pub fn cancel_wallet_migration(ctx: Context<UpdateUserAccount>) -> Result<()> {
+        let user = &mut ctx.accounts.user_account;
+        require!(
+            user.pending_wallet.is_some(),
+            ErrorCode::InvalidWalletMigration
+        );
+        user.pending_wallet = None;
+        user.migration_available_at = 0;
+        user.last_active_at = Clock::get()?.unix_timestamp;
+
+        msg!("Wallet migration cancelled");
+        Ok(())
+    }
+
+    // Pass None to remove the beneficiary
+    pub fn set_beneficiary(
+        ctx: Context<UpdateUserAccount>,
+        beneficiary: Option<Pubkey>,
+        inactivity_horizon_secs: i64,
+    ) -> Result<()> {
+        if beneficiary.is_some() {
+            require!(
+                inactivity_horizon_secs >= MIN_BENEFICIARY_INACTIVITY_SECS,
+                ErrorCode::InvalidBeneficiary
+            );
+            require!(
+                beneficiary != Some(ctx.accounts.signer.key()),
+                ErrorCode::InvalidBeneficiary
+            );
+        }
+
+        let user = &mut ctx.accounts.user_account;
+        user.beneficiary = beneficiary;
+        user.inactivity_horizon_secs = inactivity_horizon_secs;
+        user.last_active_at = Clock::get()?.unix_timestamp;
+
+        msg!(
+            "Beneficiary set to {:?} after {} seconds of inactivity",
+            beneficiary,
+            inactivity_horizon_secs
+        );
+        Ok(())
+    }
+
+    // The version must match the current one, so users accept what they have read
+    pub fn accept_terms(ctx: Context<UpdateUserAccount>, version: u32) -> Result<()> {
+        require!(
+            version == ctx.accounts.config.terms_version,
+            ErrorCode::TermsVersionMismatch
+        );
+        let user = &mut ctx.accounts.user_account;
+        user.accepted_terms_version = version;
+
+        msg!("{} accepted terms version {}", user.wallet, version);
+        Ok(())
+    }
+
+    // Heartbeat that resets the beneficiary inactivity clock
+    pub fn record_activity(ctx: Context<UpdateUserAccount>) -> Result<()> {
+        let user = &mut ctx.accounts.user_account;
+        user.last_active_at = Clock::get()?.unix_timestamp;
+
+        msg!("Activity recorded at {}", user.last_active_at);
+        Ok(())
+    }
+
+    // After the owner's inactivity horizon, the beneficiary starts a delayed wallet
+    // migration to themselves; the owner can still cancel it during the delay.
+    // Once finalized, the beneficiary re-keys engagements to receive pending payouts.
+    pub fn claim_as_beneficiary(ctx: Context<ClaimAsBeneficiary>) -> Result<()> {
+        let beneficiary = ctx.accounts.signer.key();
+        let user = &mut ctx.accounts.user_account;
+        require!(
+            user.beneficiary == Some(beneficiary),
+            ErrorCode::Unauthorized
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp >= user.last_active_at + user.inactivity_horizon_secs,
+            ErrorCode::OwnerStillActive
+        );
+
+        user.pending_wallet = Some(beneficiary);
+        user.migration_available_at = clock.unix_timestamp + BENEFICIARY_CLAIM_DELAY_SECS;
+
+        emit!(WalletMigrationInitiated {
+            old_wallet: user.wallet,
+            new_wallet: beneficiary,
+            available_at: user.migration_available_at,
+        });
+
+        msg!(
+            "Beneficiary claim started, can be finalized after {}",
+            user.migration_available_at
+        );
+        Ok(())
+    }
+
+    // Signed by the new wallet: moves the profile and reputation to PDAs under it
+    pub fn finalize_wallet_migration(ctx: Context<FinalizeWalletMigration>) -> Result<()> {
+        let clock = Clock::get()?;
+        let old_user = &ctx.accounts.old_user_account;
+        require!(
+            clock.unix_timestamp >= old_user.migration_available_at,
+            ErrorCode::WalletMigrationPending
+        );
+
+        let old_wallet = old_user.wallet;
+        let new_wallet = ctx.accounts.new_wallet.key();
+        ctx.accounts.new_user_account.set_inner(UserAccount {
+            wallet: new_wallet,
+            pending_wallet: None,
+            migration_available_at: 0,
+            migrated_from: Some(old_wallet),
+            ..(**old_user).clone()
+        });
+        ctx.accounts.new_reputation.set_inner(Reputation {
+            wallet: new_wallet,
+            bump: ctx.bumps.new_reputation,
+            ..(*ctx.accounts.old_reputation).clone()
+        });
+        // The handle follows the profile to the new wallet
+        ctx.accounts.username_record.wallet = new_wallet;
+
+        emit!(WalletMigrated {
+            old_wallet,
+            new_wallet,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Wallet migrated from {} to {}", old_wallet, new_wallet);
+        Ok(())
+    }
+
+    // After finalizing, the new wallet re-keys each active engagement
+    pub fn migrate_application(ctx: Context<MigrateApplication>) -> Result<()> {
+        let application = &mut ctx.accounts.application;
+        let old_wallet = application.applicant;
+        application.applicant = ctx.accounts.signer.key();
+
+        emit!(EngagementMigrated {
+            engagement: application.key(),
+            job_post: application.job_post,
+            old_wallet,
+            new_wallet: application.applicant,
+        });
+
+        msg!("Application re-keyed to {}", application.applicant);
+        Ok(())
+    }
+
+    pub fn migrate_job_post(ctx: Context<MigrateJobPost>) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        let old_wallet = job_post.client;
+        job_post.client = ctx.accounts.signer.key();
+
+        emit!(EngagementMigrated {
+            engagement: job_post.key(),
+            job_post: job_post.key(),
+            old_wallet,
+            new_wallet: job_post.client,
+        });
+
+        msg!("Job post re-keyed to {}", job_post.client);
+        Ok(())
+    }
+
+    // A client needs their job index before posting; every job they post is
+    // listed in it until the post is cancelled
+    pub fn initialize_job_index(ctx: Context<InitializeJobIndex>) -> Result<()> {
+        let job_index = &mut ctx.accounts.job_index;
+        job_index.client = ctx.accounts.signer.key();
+        job_index.jobs = Vec::new();
+        job_index.bump = ctx.bumps.job_index;
+
+        msg!("Job index created for {}", job_index.client);
+        Ok(())
+    }
+
+    // max_active_jobs of 0 means the freelancer has no capacity limit
+    pub fn set_work_capacity(ctx: Context<SetWorkCapacity>, max_active_jobs: u16) -> Result<()> {
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Freelancer),
+            ErrorCode::Unauthorized
+        );
+
+        let user = &mut ctx.accounts.user_account;
+        user.max_active_jobs = max_active_jobs;
+
+        msg!(
+            "Work capacity set to {} (currently active: {})",
+            user.max_active_jobs,
+            user.active_jobs
+        );
+        Ok(())
+    }
+
+    pub fn initialize_freelancer_profile(ctx: Context<InitializeFreelancerProfile>) -> Result<()> {
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Freelancer),
+            ErrorCode::Unauthorized
+        );
+
+        let profile = &mut ctx.accounts.profile;
+        profile.wallet = ctx.accounts.signer.key();
+        profile.skills = Vec::new();
+        profile.available = true;
+        profile.bump = ctx.bumps.profile;
+
+        msg!("Freelancer profile created for {}", profile.wallet);
+        Ok(())
+    }
+
+    // Replaces the profile's skill tags; see validate_tags for the format
+    pub fn set_skills(ctx: Context<UpdateFreelancerProfile>, skills: Vec<String>) -> Result<()> {
+        validate_tags(&skills, MAX_SKILLS)?;
+
+        let profile = &mut ctx.accounts.profile;
+        profile.skills = skills;
+
+        msg!("Skills of {} set to {:?}", profile.wallet, profile.skills);
+        Ok(())
+    }
+
+    pub fn set_availability(
+        ctx: Context<UpdateFreelancerProfile>,
+        available: bool,
+        hourly_rate: u64,
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.profile;
+        profile.available = available;
+        profile.hourly_rate = hourly_rate;
+
+        emit!(AvailabilityChanged {
+            wallet: profile.wallet,
+            available,
+            hourly_rate,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "{} is {} at {} lamports per hour",
+            profile.wallet,
+            if available {
+                "available"
+            } else {
+                "unavailable"
+            },
+            hourly_rate
+        );
+        Ok(())
+    }
+
+    // Pre-flight for initialize_job_post: runs the same checks without creating
+    // anything and returns a JobParamsReport as return data. Simulate it.
+    pub fn validate_job_params(
+        ctx: Context<ValidateJobParams>,
+        title: String,
+        description: String,
+        amount: u64,
+        start_date: i64,
+        end_date: i64,
+    ) -> Result<JobParamsReport> {
+        let config = &ctx.accounts.config;
+        let client = &ctx.accounts.client;
+        let now = Clock::get()?.unix_timestamp;
+
+        let title_error = validate_job_title(&title).err().map(error_number);
+        let description_error = validate_job_description(&description)
+            .err()
+            .map(error_number);
+        let dates_error = validate_job_dates(start_date, end_date, now)
+            .err()
+            .map(error_number);
+        let amount_error = (amount == 0)
+            .then_some(ErrorCode::InvalidJobAmount.into())
+            .map(error_number);
+
+        // The job PDA is keyed by client and their next job index
+        let job_index = ctx.accounts.client_account.job_counter;
+        let (job_post_address, _) = Pubkey::find_program_address(
+            &[b"job_post", client.key().as_ref(), &job_index.to_le_bytes()],
+            ctx.program_id,
+        );
+        let seed_available = match &ctx.accounts.job_post {
+            Some(job_post) => {
+                require_keys_eq!(
+                    job_post.key(),
+                    job_post_address,
+                    ErrorCode::InvalidJobAddress
+                );
+                job_post.lamports() == 0
+            }
+            None => false,
+        };
+
+        let client_fee = bps_of(amount, config.client_fee_bps);
+        let total_deposit = amount.saturating_add(client_fee);
+        let tranche_count = if config.tranche_threshold > 0 && amount > config.tranche_threshold {
+            config.tranche_count
+        } else {
+            1
+        };
+
+        Ok(JobParamsReport {
+            title_error,
+            description_error,
+            dates_error,
+            amount_error,
+            job_post_address,
+            seed_available,
+            client_fee,
+            total_deposit,
+            tranche_count,
+            can_fund: client.lamports() >= total_deposit,
+        })
+    }
+
+    // Note: start_date and end_date are i64 unix timestamps (seconds)
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_job_post(
+        ctx: Context<InitializeJobPost>,
+        title: String,
+        description: String,
+        amount: u64,
+        start_date: i64,
+        end_date: i64,
+        yield_recipient: YieldRecipient,
+        max_applicants: u32,
+        category: JobCategory,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        ctx.accounts.create(
+            ctx.bumps.escrow,
+            title,
+            description,
+            amount,
+            start_date,
+            end_date,
+            yield_recipient,
+            max_applicants,
+            category,
+            tags,
+        )?;
+
+        // Transfer funds to escrow
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.signer.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, amount + ctx.accounts.job_post.client_fee)?;
+        ctx.accounts
+            .protocol_stats
+            .record_escrow(amount + ctx.accounts.job_post.client_fee);
+
+        let job_post = &ctx.accounts.job_post;
+        emit!(JobPosted {
+            job_post: job_post.key(),
+            client: job_post.client,
+            job_id: job_post.job_id,
+            amount,
+            mint: None,
+            category: job_post.category,
+            start_date: job_post.start_date,
+            end_date: job_post.end_date,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Job #{} created with amount: {} start: {} end: {}",
+            job_post.job_id,
+            amount,
+            job_post.start_date,
+            job_post.end_date
+        );
+        Ok(())
+    }
+
+    // Same as initialize_job_post, but the budget is escrowed in an SPL token
+    // (e.g. USDC) held by a token account the escrow PDA controls
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_job_post_spl(
+        ctx: Context<InitializeJobPostSpl>,
+        title: String,
+        description: String,
+        amount: u64,
+        start_date: i64,
+        end_date: i64,
+        yield_recipient: YieldRecipient,
+        max_applicants: u32,
+        category: JobCategory,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        // Transfer-fee mints deliver less than was sent, and the budget is
+        // what actually lands in the vault once the client-side fee is set aside
+        let mint = ctx.accounts.mint.to_account_info();
+        let client_fee = bps_of(amount, ctx.accounts.job.config.client_fee_bps);
+        let deposit = amount + client_fee;
+        let budget = deposit
+            .checked_sub(transfer_fee(&mint, deposit)?)
+            .and_then(|received| received.checked_sub(client_fee))
+            .ok_or(ErrorCode::InsufficientEscrowFunds)?;
+        ctx.accounts.job.create(
+            ctx.bumps.job.escrow,
+            title,
+            description,
+            budget,
+            start_date,
+            end_date,
+            yield_recipient,
+            max_applicants,
+            category,
+            tags,
+        )?;
+        let job_post = &mut ctx.accounts.job.job_post;
+        job_post.mint = Some(ctx.accounts.mint.key());
+        job_post.client_fee = client_fee;
+        let escrow = &mut ctx.accounts.job.escrow;
+        escrow.mint = job_post.mint;
+        escrow.expected_amount = budget + client_fee;
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.client_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.escrow_vault.to_account_info(),
+                authority: ctx.accounts.job.signer.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(cpi_context, deposit, ctx.accounts.mint.decimals)?;
+
+        let job_post = &ctx.accounts.job.job_post;
+        emit!(JobPosted {
+            job_post: job_post.key(),
+            client: job_post.client,
+            job_id: job_post.job_id,
+            amount: job_post.amount,
+            mint: job_post.mint,
+            category: job_post.category,
+            start_date: job_post.start_date,
+            end_date: job_post.end_date,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Job #{} created with {} of mint {} start: {} end: {}",
+            job_post.job_id,
+            job_post.amount,
+            ctx.accounts.mint.key(),
+            job_post.start_date,
+            job_post.end_date
+        );
+        Ok(())
+    }
+
+    // Refunds the escrowed budget to the client and stops new applications
+    pub fn cancel_job_post(ctx: Context<CancelJobPost>) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        require!(job_post.mint.is_none(), ErrorCode::TokenEscrowedJob);
+        job_post.require_open()?;
+        job_post.transition(JobStatus::Cancelled)?;
+        let client_account = &mut ctx.accounts.client_account;
+        client_account.open_job_posts = client_account.open_job_posts.saturating_sub(1);
+        ctx.accounts.job_index.remove(&job_post.key());
+
+        // The escrow keeps its rent, and with it the Refunded status, until
+        // close_completed_job sweeps it along with anything sent beyond the budget
+        ctx.accounts.escrow.status = EscrowStatus::Refunded;
+        let refund = ctx.accounts.escrow.outstanding(0) + job_post.referral_bounty;
+        let escrow = ctx.accounts.escrow.to_account_info();
+        let client = ctx.accounts.client.to_account_info();
+        transfer_from_escrow(&escrow, &client, refund)?;
+
+        emit!(JobCancelled {
+            job_post: job_post.key(),
+            client: job_post.client,
+            refund,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Job post cancelled: {}, refunded {} lamports",
+            job_post.title,
+            refund
+        );
+        Ok(())
+    }
+
+    // Refunds the escrowed tokens and closes the vault, returning its rent
+    pub fn cancel_job_post_spl(ctx: Context<CancelJobPostSpl>) -> Result<()> {
+        let job_post = &mut ctx.accounts.job.job_post;
+        job_post.require_open()?;
+        job_post.transition(JobStatus::Cancelled)?;
+        let client_account = &mut ctx.accounts.job.client_account;
+        client_account.open_job_posts = client_account.open_job_posts.saturating_sub(1);
+        ctx.accounts.job.job_index.remove(&job_post.key());
+
+        let job_key = job_post.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"escrow", job_key.as_ref(), &[job_post.escrow_bump]]];
+        ctx.accounts.job.escrow.status = EscrowStatus::Refunded;
+        // The vault is closed, so it is emptied whatever else was sent to it
+        let refund = ctx.accounts.escrow_vault.amount;
+        require!(
+            refund >= ctx.accounts.job.escrow.outstanding(0),
+            ErrorCode::InsufficientEscrowFunds
+        );
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.client_token_account.to_account_info(),
+                authority: ctx.accounts.job.escrow.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_context, refund, ctx.accounts.mint.decimals)?;
+
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow_vault.to_account_info(),
+                destination: ctx.accounts.job.client.to_account_info(),
+                authority: ctx.accounts.job.escrow.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::close_account(cpi_context)?;
+
+        // The referral bounty is held in lamports; the rent stays until the job is closed
+        let escrow = ctx.accounts.job.escrow.to_account_info();
+        let client = ctx.accounts.job.client.to_account_info();
+        transfer_from_escrow(&escrow, &client, job_post.referral_bounty)?;
+
+        emit!(JobCancelled {
+            job_post: job_post.key(),
+            client: job_post.client,
+            refund,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Job post cancelled: {}, refunded {} tokens",
+            job_post.title,
+            refund
+        );
+        Ok(())
+    }
+
+    // Archives a finished job once every application on it is closed, returning
+    // the rent to the client along with anything left in the escrow. Token jobs
+    // close their emptied vault too, so accrued fees must be claimed first.
+    pub fn close_completed_job(ctx: Context<CloseCompletedJob>) -> Result<()> {
+        let job_post = &ctx.accounts.job_post;
+        require!(
+            matches!(job_post.status, JobStatus::Completed | JobStatus::Cancelled),
+            ErrorCode::JobNotFinished
+        );
+        require!(job_post.applicant_count == 0, ErrorCode::JobHasApplications);
+
+        let client = ctx.accounts.client.to_account_info();
+        let escrow = ctx.accounts.escrow.to_account_info();
+        if job_post.mint.is_some() {
+            require!(
+                job_post.accrued_token_fees == 0,
+                ErrorCode::TokenFeesUnclaimed
+            );
+            // A cancelled job's vault is already gone
+            if let Some(escrow_vault) = &ctx.accounts.escrow_vault {
+                let token_program = ctx
+                    .accounts
+                    .token_program
+                    .as_ref()
+                    .ok_or(ErrorCode::TokenAccountsRequired)?;
+                let job_key = job_post.key();
+                let signer_seeds: &[&[&[u8]]] =
+                    &[&[b"escrow", job_key.as_ref(), &[job_post.escrow_bump]]];
+                let cpi_context = CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    CloseAccount {
+                        account: escrow_vault.to_account_info(),
+                        destination: client.clone(),
+                        authority: escrow.clone(),
+                    },
+                    signer_seeds,
+                );
+                token_interface::close_account(cpi_context)?;
+            }
+        }
+        let leftover = escrow.lamports();
+        close_escrow(job_post, &escrow, &client)?;
+        ctx.accounts.job_index.remove(&job_post.key());
+
+        emit!(JobArchived {
+            job_post: job_post.key(),
+            client: job_post.client,
+            job_id: job_post.job_id,
+            status: job_post.status,
+            amount: job_post.amount,
+            escrow_swept: leftover,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Job post closed: {}", job_post.title);
+        Ok(())
+    }
+
+    // Escrows more lamports for a job whose scope grew. The client-side fee is
+    // charged on the addition too; a contested job cannot be topped up.
+    pub fn increase_job_budget(ctx: Context<AdjustJobBudget>, additional: u64) -> Result<()> {
+        require!(additional > 0, ErrorCode::InvalidJobAmount);
+        let job_post = &mut ctx.accounts.job_post;
+        require!(job_post.mint.is_none(), ErrorCode::TokenEscrowedJob);
+        job_post.require_not_closed()?;
+        require!(
+            job_post.status != JobStatus::Disputed && job_post.open_disputes == 0,
+            ErrorCode::ApplicationDisputed
+        );
+
+        let fee = bps_of(additional, ctx.accounts.config.client_fee_bps);
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, additional + fee)?;
+        ctx.accounts.protocol_stats.record_escrow(additional + fee);
+        job_post.amount += additional;
+        job_post.client_fee += fee;
+        ctx.accounts.escrow.expected_amount = job_post.amount + job_post.client_fee;
+
+        emit!(JobBudgetIncreased {
+            job_post: job_post.key(),
+            additional,
+            amount: job_post.amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Budget for job #{} raised by {} to {} lamports",
+            job_post.job_id,
+            additional,
+            job_post.amount
+        );
+        Ok(())
+    }
+
+    // Returns part of an over-funded lamport escrow before anyone is hired, along
+    // with the client-side fee charged on it
+    pub fn decrease_job_budget(ctx: Context<AdjustJobBudget>, reduction: u64) -> Result<()> {
+        require!(reduction > 0, ErrorCode::InvalidJobAmount);
+        let job_post = &mut ctx.accounts.job_post;
+        require!(job_post.mint.is_none(), ErrorCode::TokenEscrowedJob);
+        job_post.require_open()?;
+        let remaining = job_post
+            .amount
+            .checked_sub(reduction)
+            .ok_or(ErrorCode::InvalidJobAmount)?;
+        require!(
+            remaining > 0 && remaining >= ctx.accounts.config.min_job_amount,
+            ErrorCode::BudgetBelowMinimum
+        );
+        require!(
+            remaining >= job_post.milestone_total,
+            ErrorCode::InvalidMilestone
+        );
+        require!(
+            remaining >= job_post.hourly_cap(),
+            ErrorCode::InsufficientEscrowFunds
+        );
+
+        let fee = bps_of(remaining, ctx.accounts.config.client_fee_bps);
+        let refund = reduction + job_post.client_fee.saturating_sub(fee);
+        let escrow = ctx.accounts.escrow.to_account_info();
+        let client = ctx.accounts.client.to_account_info();
+        transfer_from_escrow(&escrow, &client, refund)?;
+        job_post.amount = remaining;
+        job_post.client_fee = fee;
+        ctx.accounts.escrow.expected_amount = remaining + fee;
+
+        emit!(JobBudgetDecreased {
+            job_post: job_post.key(),
+            refund,
+            amount: remaining,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Budget for job #{} lowered to {} lamports, refunded {}",
+            job_post.job_id,
+            remaining,
+            refund
+        );
+        Ok(())
+    }
+
+    // Lamports each new applicant must deposit; refunded unless the client rejects
+    // the application as spam. Deposits already made keep their amount.
+    pub fn set_application_fee(ctx: Context<EditJobPost>, application_fee: u64) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        job_post.application_fee = application_fee;
+
+        msg!(
+            "Application fee for job #{} set to {} lamports",
+            job_post.job_id,
+            application_fee
+        );
+        Ok(())
+    }
+
+    // Lamports the hired freelancer must lock as a completion bond; returned with
+    // the final payout, sent to the client if the freelancer defaults
+    pub fn set_completion_bond(ctx: Context<EditJobPost>, bond_amount: u64) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        job_post.bond_amount = bond_amount;
+
+        msg!(
+            "Completion bond for job #{} set to {} lamports",
+            job_post.job_id,
+            bond_amount
+        );
+        Ok(())
+    }
+
+    // Switches a lamport job to hourly billing; the budget must cover every
+    // billable hour. A rate of 0 makes it fixed-price again.
+    pub fn set_hourly_terms(
+        ctx: Context<EditJobPost>,
+        hourly_rate: u64,
+        max_hours: u32,
+    ) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        require!(job_post.mint.is_none(), ErrorCode::TokenEscrowedJob);
+        require!(!job_post.streaming, ErrorCode::StreamingJob);
+        require!(!job_post.is_team(), ErrorCode::TeamJob);
+        require!(
+            (hourly_rate == 0) == (max_hours == 0),
+            ErrorCode::InvalidHourlyTerms
+        );
+        hourly_rate
+            .checked_mul(u64::from(max_hours))
+            .ok_or(ErrorCode::InvalidHourlyTerms)?;
+        job_post.hourly_rate = hourly_rate;
+        job_post.max_hours = max_hours;
+        require!(
+            job_post.hourly_cap() <= job_post.amount,
+            ErrorCode::InsufficientEscrowFunds
+        );
+
+        msg!(
+            "Job #{} billed at {} lamports an hour, up to {} hours",
+            job_post.job_id,
+            hourly_rate,
+            max_hours
+        );
+        Ok(())
+    }
+
+    // Lets approve_application hire up to max_hires freelancers, each taking an
+    // equal share of the budget. Team jobs are lamport-escrowed and fixed-price,
+    // and settle without disputes.
+    pub fn set_max_hires(ctx: Context<EditJobPost>, max_hires: u8) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        require!(max_hires > 0, ErrorCode::InvalidMaxHires);
+        if max_hires > 1 {
+            require!(job_post.mint.is_none(), ErrorCode::TokenEscrowedJob);
+            require!(
+                job_post.hourly_rate == 0 && !job_post.streaming && job_post.milestone_count == 0,
+                ErrorCode::TeamJob
+            );
+        }
+        job_post.max_hires = max_hires;
+
+        msg!(
+            "Job #{} hires up to {} freelancers",
+            job_post.job_id,
+            max_hires
+        );
+        Ok(())
+    }
+
+    // Makes approve_submission release funds only once `threshold` of the listed
+    // keys have approved, each in its own transaction. An empty list goes back to
+    // the client approving alone. Fixed before hiring so no one can lower the bar
+    // on work already underway.
+    pub fn set_job_approvers(
+        ctx: Context<EditJobPost>,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        require!(
+            approvers.len() <= MAX_JOB_APPROVERS
+                && usize::from(threshold) <= approvers.len()
+                && (threshold > 0 || approvers.is_empty()),
+            ErrorCode::InvalidApprovers
+        );
+        for (index, approver) in approvers.iter().enumerate() {
+            require!(
+                !approvers[..index].contains(approver),
+                ErrorCode::InvalidApprovers
+            );
+        }
+        job_post.approvers = approvers;
+        job_post.approval_threshold = threshold;
+
+        emit!(ApproversSet {
+            job_post: job_post.key(),
+            approvers: job_post.approvers.clone(),
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Job #{} needs {} of {} approvals",
+            job_post.job_id,
+            threshold,
+            job_post.approvers.len()
+        );
+        Ok(())
+    }
+
+    // Deducted from each release for every day the submission came in after
+    // end_date, and refunded to the client
+    pub fn set_late_penalty(
+        ctx: Context<EditJobPost>,
+        late_penalty_bps_per_day: u16,
+    ) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        require!(
+            u64::from(late_penalty_bps_per_day) <= BPS_DENOMINATOR,
+            ErrorCode::InvalidLatePenalty
+        );
+        job_post.late_penalty_bps_per_day = late_penalty_bps_per_day;
+
+        msg!(
+            "Job #{} late penalty set to {} bps per day",
+            job_post.job_id,
+            late_penalty_bps_per_day
+        );
+        Ok(())
+    }
+
+    // Share of the budget paid to the freelancer as a deposit the moment they are hired
+    pub fn set_upfront_bps(ctx: Context<EditJobPost>, upfront_bps: u16) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        require!(
+            u64::from(upfront_bps) <= BPS_DENOMINATOR,
+            ErrorCode::InvalidUpfrontBps
+        );
+        if upfront_bps > 0 {
+            require!(job_post.mint.is_none(), ErrorCode::TokenEscrowedJob);
+        }
+        job_post.upfront_bps = upfront_bps;
+
+        msg!(
+            "Job #{} pays {} bps upfront on hire",
+            job_post.job_id,
+            upfront_bps
+        );
+        Ok(())
+    }
+
+    // Streaming jobs vest the budget linearly from start_date to end_date; the
+    // freelancer pulls the vested part with claim_vested as the work goes on
+    pub fn set_streaming(ctx: Context<EditJobPost>, streaming: bool) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        if streaming {
+            require!(job_post.mint.is_none(), ErrorCode::TokenEscrowedJob);
+            require!(job_post.hourly_rate == 0, ErrorCode::StreamingJob);
+            require!(job_post.milestone_count == 0, ErrorCode::StreamingJob);
+            require!(!job_post.is_team(), ErrorCode::TeamJob);
+        }
+        job_post.streaming = streaming;
+
+        msg!("Job #{} streaming set to {}", job_post.job_id, streaming);
+        Ok(())
+    }
+
+    // Private jobs only take applications from wallets on the job's whitelist
+    pub fn set_job_visibility(ctx: Context<EditJobPost>, visibility: JobVisibility) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        job_post.visibility = visibility;
+
+        msg!(
+            "Job #{} visibility set to {:?}",
+            job_post.job_id,
+            visibility
+        );
+        Ok(())
+    }
+
+    // Features an open job for `duration_secs`, on top of any boost still running.
+    // The fee accrues to the protocol treasury like other protocol fees.
+    pub fn boost_job_post(ctx: Context<BoostJobPost>, duration_secs: i64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(config.boost_fee_per_day > 0, ErrorCode::BoostingDisabled);
+        require!(
+            (1..=MAX_BOOST_SECS).contains(&duration_secs),
+            ErrorCode::InvalidBoostDuration
+        );
+        ctx.accounts.job_post.require_accepting_applications()?;
+
+        let days = (duration_secs + SECONDS_PER_DAY - 1) / SECONDS_PER_DAY;
+        let fee = config.boost_fee_per_day.saturating_mul(days as u64);
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.config.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, fee)?;
+        ctx.accounts.config.accrued_fees += fee;
+
+        let now = Clock::get()?.unix_timestamp;
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.featured_until = job_post.featured_until.max(now) + duration_secs;
+
+        emit!(JobBoosted {
+            job_post: job_post.key(),
+            fee,
+            featured_until: job_post.featured_until,
+            timestamp: now,
+        });
+        msg!(
+            "Job #{} featured until {} for {} lamports",
+            job_post.job_id,
+            job_post.featured_until,
+            fee
+        );
+        Ok(())
+    }
+
+    pub fn initialize_job_whitelist(ctx: Context<InitializeJobWhitelist>) -> Result<()> {
+        let whitelist = &mut ctx.accounts.whitelist;
+        whitelist.job_post = ctx.accounts.job_post.key();
+        whitelist.applicants = Vec::new();
+        whitelist.bump = ctx.bumps.whitelist;
+
+        msg!(
+            "Whitelist created for job #{}",
+            ctx.accounts.job_post.job_id
+        );
+        Ok(())
+    }
+
+    pub fn add_whitelisted_applicant(
+        ctx: Context<UpdateJobWhitelist>,
+        applicant: Pubkey,
+    ) -> Result<()> {
+        let applicants = &mut ctx.accounts.whitelist.applicants;
+        if !applicants.contains(&applicant) {
+            require!(
+                applicants.len() < MAX_WHITELIST_LEN,
+                ErrorCode::WhitelistFull
+            );
+            applicants.push(applicant);
+        }
+
+        msg!(
+            "{} may apply to job #{}",
+            applicant,
+            ctx.accounts.job_post.job_id
+        );
+        Ok(())
+    }
+
+    // Existing applications are unaffected; only new ones are blocked
+    pub fn remove_whitelisted_applicant(
+        ctx: Context<UpdateJobWhitelist>,
+        applicant: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts
+            .whitelist
+            .applicants
+            .retain(|key| *key != applicant);
+
+        msg!(
+            "{} removed from the whitelist of job #{}",
+            applicant,
+            ctx.accounts.job_post.job_id
+        );
+        Ok(())
+    }
+
+    // Authorizes session_key to take the `scope` actions (DELEGATE_* flags) for
+    // the signer until expires_at. None of them move funds, so a hot frontend key
+    // can hire and ask for revisions without the treasury key signing each time.
+    pub fn create_delegate(ctx: Context<CreateDelegate>, scope: u8, expires_at: i64) -> Result<()> {
+        require!(
+            scope != 0 && scope & !DELEGATE_SCOPES == 0,
+            ErrorCode::InvalidDelegateScope
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(expires_at > now, ErrorCode::DelegateExpired);
+        let principal = ctx.accounts.principal.key();
+        let session_key = ctx.accounts.session_key.key();
+        require_keys_neq!(principal, session_key, ErrorCode::InvalidDelegateScope);
+
+        let delegate = &mut ctx.accounts.delegate;
+        delegate.principal = principal;
+        delegate.session_key = session_key;
+        delegate.scope = scope;
+        delegate.expires_at = expires_at;
+        delegate.bump = ctx.bumps.delegate;
+
+        emit!(DelegateCreated {
+            principal,
+            session_key,
+            scope,
+            expires_at,
+            timestamp: now,
+        });
+        msg!("Delegate {} may act for {}", session_key, principal);
+        Ok(())
+    }
+
+    // Works while the program is paused, so a leaked key can always be cut off
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        let delegate = &ctx.accounts.delegate;
+        emit!(DelegateRevoked {
+            principal: delegate.principal,
+            session_key: delegate.session_key,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Delegate {} revoked", delegate.session_key);
+        Ok(())
+    }
+
+    // A shared client identity for a company; the creator is its first admin
+    pub fn create_organization(
+        ctx: Context<CreateOrganization>,
+        name: String,
+        description: String,
+    ) -> Result<()> {
+        validate_user_name(&name)?;
+        validate_job_description(&description)?;
+
+        let organization = &mut ctx.accounts.organization;
+        organization.creator = ctx.accounts.admin.key();
+        organization.name = name;
+        organization.description = description;
+        organization.admins = vec![ctx.accounts.admin.key()];
+        organization.bump = ctx.bumps.organization;
+
+        emit!(OrganizationCreated {
+            organization: organization.key(),
+            creator: organization.creator,
+            name: organization.name.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Organization created: {}", organization.name);
+        Ok(())
+    }
+
+    pub fn update_organization(
+        ctx: Context<UpdateOrganization>,
+        name: String,
+        description: String,
+    ) -> Result<()> {
+        validate_user_name(&name)?;
+        validate_job_description(&description)?;
+
+        let organization = &mut ctx.accounts.organization;
+        organization.name = name;
+        organization.description = description;
+
+        msg!("Organization updated: {}", organization.name);
+        Ok(())
+    }
+
+    pub fn add_organization_admin(ctx: Context<UpdateOrganization>, admin: Pubkey) -> Result<()> {
+        let admins = &mut ctx.accounts.organization.admins;
+        if !admins.contains(&admin) {
+            require!(
+                admins.len() < MAX_ORG_ADMINS,
+                ErrorCode::OrganizationAdminsFull
+            );
+            admins.push(admin);
+        }
+
+        msg!(
+            "{} is now an admin of {}",
+            admin,
+            ctx.accounts.organization.name
+        );
+        Ok(())
+    }
+
+    // Any admin may remove any other, but the organization always keeps one
+    pub fn remove_organization_admin(
+        ctx: Context<UpdateOrganization>,
+        admin: Pubkey,
+    ) -> Result<()> {
+        let admins = &mut ctx.accounts.organization.admins;
+        admins.retain(|key| *key != admin);
+        require!(!admins.is_empty(), ErrorCode::LastOrganizationAdmin);
+
+        msg!(
+            "{} removed as an admin of {}",
+            admin,
+            ctx.accounts.organization.name
+        );
+        Ok(())
+    }
+
+    // Puts a job under an organization the client administers, so every admin can
+    // hire and approve work on it. Funds still move to and from the client's
+    // wallet. Omitting the organization makes the job personal again.
+    pub fn set_job_organization(ctx: Context<SetJobOrganization>) -> Result<()> {
+        let organization = match &ctx.accounts.organization {
+            Some(organization) => {
+                require!(
+                    organization.is_admin(&ctx.accounts.client.key()),
+                    ErrorCode::NotOrganizationAdmin
+                );
+                Some(organization.key())
+            }
+            None => None,
+        };
+        let job_post = &mut ctx.accounts.job_post;
+        require!(
+            !matches!(job_post.status, JobStatus::Completed | JobStatus::Cancelled),
+            ErrorCode::JobCompleted
+        );
+        job_post.organization = organization;
+
+        msg!(
+            "Job #{} organization set to {:?}",
+            job_post.job_id,
+            organization
+        );
+        Ok(())
+    }
+
+    // Fixes the listing in place before anyone has applied. Dates that change must
+    // pass the same checks as at posting; a payment schedule pins them.
+    pub fn edit_job_post(
+        ctx: Context<EditJobPostText>,
+        title: String,
+        description: String,
+        start_date: i64,
+        end_date: i64,
+    ) -> Result<()> {
+        validate_job_title(&title)?;
+        validate_job_description(&description)?;
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        require!(job_post.applicant_count == 0, ErrorCode::JobHasApplications);
+        if (start_date, end_date) != (job_post.start_date, job_post.end_date) {
+            require!(!job_post.has_schedule, ErrorCode::DatesLockedBySchedule);
+            validate_job_dates(start_date, end_date, Clock::get()?.unix_timestamp)?;
+        }
+
+        job_post.title = title;
+        job_post.description = description;
+        job_post.start_date = start_date;
+        job_post.end_date = end_date;
+        ctx.accounts.fit_text()?;
+        let job_post = &ctx.accounts.job_post;
+
+        emit!(JobEdited {
+            job_post: job_post.key(),
+            client: job_post.client,
+            start_date,
+            end_date,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Job #{} edited", job_post.job_id);
+        Ok(())
+    }
+
+    // Moves the description off-chain: the job points at a document whose
+    // SHA-256 is content_hash, and the on-chain description is dropped. Same
+    // editing rules as edit_job_post.
+    pub fn set_job_metadata(
+        ctx: Context<EditJobPostText>,
+        uri: String,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        validate_metadata_uri(&uri)?;
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        require!(job_post.applicant_count == 0, ErrorCode::JobHasApplications);
+        job_post.description = String::new();
+        job_post.metadata = Some(ContentRef { uri, content_hash });
+        ctx.accounts.fit_text()?;
+        let job_post = &ctx.accounts.job_post;
+
+        emit!(MetadataUpdated {
+            account: job_post.key(),
+            content_hash,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Job #{} description moved off-chain", job_post.job_id);
+        Ok(())
+    }
+
+    // Zero-copy companion holding a description of up to 8 KiB, which would not
+    // fit JobPost's Borsh-deserialized layout
+    pub fn initialize_job_post_large(ctx: Context<InitializeJobPostLarge>) -> Result<()> {
+        let mut large = ctx.accounts.job_post_large.load_init()?;
+        large.job_post = ctx.accounts.job_post.key();
+
+        msg!(
+            "Large description account created for job #{}",
+            ctx.accounts.job_post.job_id
+        );
+        Ok(())
+    }
+
+    // Writes `chunk` at `offset` and ends the description after it, so a long
+    // text is uploaded as a series of writes with increasing offsets. Same
+    // editing rules as edit_job_post.
+    pub fn write_job_post_large(
+        ctx: Context<WriteJobPostLarge>,
+        offset: u32,
+        chunk: Vec<u8>,
+    ) -> Result<()> {
+        let job_post = &ctx.accounts.job_post;
+        job_post.require_open()?;
+        require!(job_post.applicant_count == 0, ErrorCode::JobHasApplications);
+
+        let mut large = ctx.accounts.job_post_large.load_mut()?;
+        large.write(offset as usize, &chunk)?;
+
+        msg!(
+            "Job #{} large description is {} bytes",
+            job_post.job_id,
+            large.description_len
+        );
+        Ok(())
+    }
+
+    // Fails unless the job's off-chain document hashes to content_hash, so
+    // clients can check what they fetched against the chain in one simulation
+    pub fn verify_job_metadata(
+        ctx: Context<VerifyJobMetadata>,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .job_post
+            .metadata
+            .as_ref()
+            .ok_or(ErrorCode::NoMetadata)?
+            .verify(&content_hash)
+    }
+
+    // Fixed once the job is filled, since the freelancer accepted it as a term
+    pub fn set_review_window(ctx: Context<SetReviewWindow>, review_window_secs: i64) -> Result<()> {
+        require!(
+            review_window_secs >= MIN_REVIEW_WINDOW_SECS,
+            ErrorCode::InvalidReviewWindow
+        );
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        job_post.review_window_secs = review_window_secs;
+
+        msg!(
+            "Review window for job #{} set to {} seconds",
+            job_post.job_id,
+            review_window_secs
+        );
+        Ok(())
+    }
+
+    // Denominates a token job in USD before hiring. The escrowed budget must be
+    // worth at least the agreed value times the configured collateral ratio.
+    pub fn set_usd_terms(ctx: Context<SetUsdTerms>, agreed_usd_value: u64) -> Result<()> {
+        require!(agreed_usd_value > 0, ErrorCode::InvalidPrice);
+        ctx.accounts.job.job_post.require_open()?;
+
+        let job_post = &mut ctx.accounts.job.job_post;
+        job_post.agreed_usd_value = agreed_usd_value;
+        require!(
+            ctx.accounts.job.meets_collateral_ratio()?,
+            ErrorCode::InsufficientCollateral
+        );
+
+        msg!(
+            "Job #{} denominated at {} micro-USD",
+            ctx.accounts.job.job_post.job_id,
+            agreed_usd_value
+        );
+        Ok(())
+    }
+
+    // Permissionless: flags a USD-denominated job whose escrow has fallen below
+    // the agreed value, blocking hires until the client tops it up
+    pub fn top_up_call(ctx: Context<PriceCheckedJob>) -> Result<()> {
+        let agreed_usd_value = ctx.accounts.job_post.agreed_usd_value;
+        require!(agreed_usd_value > 0, ErrorCode::NotUsdDenominated);
+        let collateral_value = ctx.accounts.collateral_value()?;
+        require!(
+            collateral_value < agreed_usd_value,
+            ErrorCode::CollateralSufficient
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let job_post = &mut ctx.accounts.job_post;
+        if job_post.collateral_call_at == 0 {
+            job_post.collateral_call_at = now;
+        }
+
+        emit!(CollateralCalled {
+            job_post: job_post.key(),
+            collateral_value,
+            agreed_usd_value,
+            timestamp: now,
+        });
+
+        msg!(
+            "Job #{} collateral worth {} of {} micro-USD agreed",
+            job_post.job_id,
+            collateral_value,
+            agreed_usd_value
+        );
+        Ok(())
+    }
+
+    // Adds tokens to the budget; restoring the collateral ratio clears any call
+    pub fn top_up_collateral(ctx: Context<TopUpCollateral>, amount: u64) -> Result<()> {
+        let mint = ctx.accounts.job.mint.to_account_info();
+        let received = amount
+            .checked_sub(transfer_fee(&mint, amount)?)
+            .ok_or(ErrorCode::InsufficientEscrowFunds)?;
+        require!(received > 0, ErrorCode::InsufficientEscrowFunds);
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.client_token_account.to_account_info(),
+                mint,
+                to: ctx.accounts.escrow_vault.to_account_info(),
+                authority: ctx.accounts.client.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(cpi_context, amount, ctx.accounts.job.mint.decimals)?;
+
+        ctx.accounts.job.job_post.amount += received;
+        ctx.accounts.escrow.expected_amount += received;
+        if ctx.accounts.job.meets_collateral_ratio()? {
+            ctx.accounts.job.job_post.collateral_call_at = 0;
+        }
+
+        msg!(
+            "Job #{} topped up by {} tokens",
+            ctx.accounts.job.job_post.job_id,
+            received
+        );
+        Ok(())
+    }
+
+    // Materializes the job's installments as evenly spaced periods between its start
+    // and end dates. Create it alongside the job; once it exists every approval must
+    // pass it so its statuses stay current.
+    pub fn initialize_schedule(ctx: Context<InitializeSchedule>) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+
+        let count = job_post.tranche_count.max(1);
+        let period_secs = (job_post.end_date - job_post.start_date) / i64::from(count);
+        let installment = job_post.amount / u64::from(count);
+        let schedule = &mut ctx.accounts.schedule;
+        schedule.job_post = job_post.key();
+        schedule.periods = (0..count)
+            .map(|index| {
+                let last = index + 1 == count;
+                let starts_at = job_post.start_date + period_secs * i64::from(index);
+                SchedulePeriod {
+                    starts_at,
+                    ends_at: if last {
+                        job_post.end_date
+                    } else {
+                        starts_at + period_secs
+                    },
+                    amount: if last {
+                        job_post.amount - installment * u64::from(count - 1)
+                    } else {
+                        installment
+                    },
+                    status: PeriodStatus::Pending,
+                    released_at: 0,
+                }
+            })
+            .collect();
+        schedule.bump = ctx.bumps.schedule;
+        job_post.has_schedule = true;
+
+        msg!(
+            "Payment schedule for job #{} created with {} periods",
+            job_post.job_id,
+            count
+        );
+        Ok(())
+    }
+
+    // The hired freelancer never delivered: once the end date and grace period have
+    // passed, the client takes back the whole escrow and the application defaults
+    pub fn reclaim_expired_escrow(ctx: Context<ReclaimExpiredEscrow>) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.mint.is_none(),
+            ErrorCode::TokenEscrowedJob
+        );
+        let job_closed = ctx.accounts.mark_defaulted()?;
+
+        // While the rest of a team is still working, only the defaulted hire's
+        // share comes back. Otherwise the client takes everything above the rent,
+        // which keeps the escrow and its Refunded status until close_completed_job.
+        let application = &ctx.accounts.application;
+        let share = application.unreleased_amount(&ctx.accounts.job_post);
+        ctx.accounts
+            .escrow
+            .require_covers(application.released_amount, share)?;
+        let escrow = ctx.accounts.escrow.to_account_info();
+        let client = ctx.accounts.client.to_account_info();
+        let refund = if job_closed {
+            let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+            escrow.lamports().saturating_sub(rent_floor)
+        } else {
+            share
+        };
+        transfer_from_escrow(&escrow, &client, refund)?;
+
+        emit!(EscrowReclaimed {
+            job_post: ctx.accounts.job_post.key(),
+            application: ctx.accounts.application.key(),
+            freelancer: ctx.accounts.application.applicant,
+            refund,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Job #{} defaulted, refunded {} lamports",
+            ctx.accounts.job_post.job_id,
+            refund
+        );
+        Ok(())
+    }
+
+    // Token-escrowed counterpart; fees already accrued to the treasury stay in the vault
+    pub fn reclaim_expired_escrow_spl(ctx: Context<ReclaimExpiredEscrowSpl>) -> Result<()> {
+        // Team jobs are lamport-escrowed, so this always closes the job
+        ctx.accounts.base.mark_defaulted()?;
+
+        let job_post = &ctx.accounts.base.job_post;
+        let job_key = job_post.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"escrow", job_key.as_ref(), &[job_post.escrow_bump]]];
+        let refund = ctx
+            .accounts
+            .escrow_vault
+            .amount
+            .saturating_sub(job_post.accrued_token_fees);
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.client_token_account.to_account_info(),
+                authority: ctx.accounts.base.escrow.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_context, refund, ctx.accounts.mint.decimals)?;
+
+        // The referral bounty is held in lamports on the escrow PDA; its rent stays
+        // until the job is closed
+        let escrow = ctx.accounts.base.escrow.to_account_info();
+        let client = ctx.accounts.base.client.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+        transfer_from_escrow(
+            &escrow,
+            &client,
+            escrow.lamports().saturating_sub(rent_floor),
+        )?;
+
+        emit!(EscrowReclaimed {
+            job_post: job_post.key(),
+            application: ctx.accounts.base.application.key(),
+            freelancer: ctx.accounts.base.application.applicant,
+            refund,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Job #{} defaulted, refunded {} tokens",
+            job_post.job_id,
+            refund
+        );
+        Ok(())
+    }
+
+    pub fn initialize_job_extension(ctx: Context<InitializeJobExtension>) -> Result<()> {
+        let extension = &mut ctx.accounts.extension;
+        extension.job_post = ctx.accounts.job_post.key();
+        extension.fields = Vec::new();
+        extension.bump = ctx.bumps.extension;
+
+        msg!("Extension created for job: {}", ctx.accounts.job_post.title);
+        Ok(())
+    }
+
+    // Inserts the field, or replaces the value of an existing field with the same key
+    pub fn set_job_extension_field(
+        ctx: Context<UpdateJobExtension>,
+        key: String,
+        value: ExtensionValue,
+    ) -> Result<()> {
+        upsert_extension_field(&mut ctx.accounts.extension.fields, key, value)
+    }
+
+    pub fn remove_job_extension_field(ctx: Context<UpdateJobExtension>, key: String) -> Result<()> {
+        remove_extension_field(&mut ctx.accounts.extension.fields, &key)
+    }
+
+    pub fn initialize_application_extension(
+        ctx: Context<InitializeApplicationExtension>,
+    ) -> Result<()> {
+        let extension = &mut ctx.accounts.extension;
+        extension.application = ctx.accounts.application.key();
+        extension.fields = Vec::new();
+        extension.bump = ctx.bumps.extension;
+
+        msg!("Extension created for application");
+        Ok(())
+    }
+
+    pub fn set_application_extension_field(
+        ctx: Context<UpdateApplicationExtension>,
+        key: String,
+        value: ExtensionValue,
+    ) -> Result<()> {
+        upsert_extension_field(&mut ctx.accounts.extension.fields, key, value)
+    }
+
+    pub fn remove_application_extension_field(
+        ctx: Context<UpdateApplicationExtension>,
+        key: String,
+    ) -> Result<()> {
+        remove_extension_field(&mut ctx.accounts.extension.fields, &key)
+    }
+
+    // Allow freelancer to include expected_end_date when applying
+    pub fn apply_to_job(
+        ctx: Context<ApplyToJob>,
+        resume_link: String,
+        expected_end_date: i64,
+    ) -> Result<()> {
+        // Only freelancers can apply
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Freelancer),
+            ErrorCode::Unauthorized
+        );
+        ctx.accounts.job_post.require_accepting_applications()?;
+        let job_post = &ctx.accounts.job_post;
+        require!(
+            job_post.max_applicants == 0 || job_post.applicant_count < job_post.max_applicants,
+            ErrorCode::ApplicationsClosed
+        );
+        if ctx.accounts.job_post.visibility == JobVisibility::Private {
+            let whitelist = ctx
+                .accounts
+                .whitelist
+                .as_ref()
+                .ok_or(ErrorCode::NotWhitelisted)?;
+            require!(
+                whitelist.applicants.contains(&ctx.accounts.signer.key()),
+                ErrorCode::NotWhitelisted
+            );
+        }
+        // A wallet holding both roles still cannot work for itself
+        require_keys_neq!(
+            ctx.accounts.job_post.client,
+            ctx.accounts.signer.key(),
+            ErrorCode::SelfDealing
+        );
+        if let Some(profile) = &ctx.accounts.freelancer_profile {
+            require!(profile.available, ErrorCode::FreelancerUnavailable);
+        }
+
+        // Validation: freelancer's expected_end_date must be a valid unix timestamp (non-negative)
+        require!(expected_end_date >= 0, ErrorCode::InvalidDates);
+
+        let application = &mut ctx.accounts.application;
+        application.applicant = ctx.accounts.user_account.wallet;
+        application.job_post = ctx.accounts.job_post.key();
+        application.resume_link = resume_link;
+        application.status = ApplicationStatus::Pending;
+        application.submission_link = String::new();
+        application.narration = String::new();
+        application.client_review = String::new();
+        application.expected_end_date = expected_end_date;
+        application.stage = ApplicationStage::Applied;
+        ctx.accounts.user_account.pending_applications += 1;
+
+        let fee = ctx.accounts.job_post.application_fee;
+        if fee > 0 {
+            let deposit = ctx
+                .accounts
+                .deposit
+                .as_mut()
+                .ok_or(ErrorCode::ApplicationFeeRequired)?;
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.signer.to_account_info(),
+                    to: deposit.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_context, fee)?;
+            deposit.application = application.key();
+            deposit.amount = fee;
+            deposit.bump = ctx.bumps.deposit.ok_or(ErrorCode::ApplicationFeeRequired)?;
+            application.application_fee = fee;
+        }
+        ctx.accounts.job_post.applicant_count += 1;
+
+        emit!(ApplicationSubmitted {
+            application: application.key(),
+            job_post: application.job_post,
+            applicant: application.applicant,
+            expected_end_date,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Application submitted with resume: {} expected_end_date: {}",
+            application.resume_link,
+            application.expected_end_date
+        );
+        Ok(())
+    }
+
+    // A freelancer pitches a client directly; the quote is keyed by a hash of the
+    // agreed scope, which the client can check against what was discussed
+    pub fn create_quote(
+        ctx: Context<CreateQuote>,
+        client: Pubkey,
+        scope_hash: [u8; 32],
+        amount: u64,
+        start_date: i64,
+        end_date: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Freelancer),
+            ErrorCode::Unauthorized
+        );
+        require!(amount > 0, ErrorCode::InvalidQuote);
+        require_keys_neq!(client, ctx.accounts.signer.key(), ErrorCode::SelfDealing);
+        require!(start_date <= end_date, ErrorCode::InvalidDates);
+
+        let quote = &mut ctx.accounts.quote;
+        quote.freelancer = ctx.accounts.signer.key();
+        quote.client = client;
+        quote.scope_hash = scope_hash;
+        quote.amount = amount;
+        quote.start_date = start_date;
+        quote.end_date = end_date;
+        quote.created_at = Clock::get()?.unix_timestamp;
+        quote.bump = ctx.bumps.quote;
+
+        emit!(QuoteCreated {
+            quote: quote.key(),
+            freelancer: quote.freelancer,
+            client,
+            amount,
+            timestamp: quote.created_at,
+        });
+        msg!("Quote of {} lamports sent to {}", amount, client);
+        Ok(())
+    }
+
+    // Withdraws an unaccepted quote and returns its rent to the freelancer
+    pub fn cancel_quote(ctx: Context<CancelQuote>) -> Result<()> {
+        let quote = &ctx.accounts.quote;
+        emit!(QuoteCancelled {
+            quote: quote.key(),
+            freelancer: quote.freelancer,
+            client: quote.client,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Quote to {} withdrawn", ctx.accounts.quote.client);
+        Ok(())
+    }
+
+    // Creates a funded job from the quoted terms with the quoting freelancer
+    // already hired, in one step
+    pub fn accept_quote(
+        ctx: Context<AcceptQuote>,
+        title: String,
+        description: String,
+        yield_recipient: YieldRecipient,
+    ) -> Result<()> {
+        let quote = &ctx.accounts.quote;
+        let (amount, end_date) = (quote.amount, quote.end_date);
+        ctx.accounts.job.create(
+            ctx.bumps.job.escrow,
+            title,
+            description,
+            amount,
+            quote.start_date,
+            end_date,
+            yield_recipient,
+            0,
+            JobCategory::Other,
+            Vec::new(),
+        )?;
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.job.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.job.signer.to_account_info(),
+                to: ctx.accounts.job.escrow.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, amount + ctx.accounts.job.job_post.client_fee)?;
+        ctx.accounts
+            .job
+            .protocol_stats
+            .record_escrow(amount + ctx.accounts.job.job_post.client_fee);
+
+        record_relationship(
+            &ctx.accounts.relationship,
+            &ctx.accounts.job.signer.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.accounts.job.signer.key(),
+            ctx.accounts.freelancer.key(),
+        )?;
+        let freelancer_account = &mut ctx.accounts.freelancer_account;
+        freelancer_account.reserve_job_slot()?;
+        freelancer_account.pending_applications += 1;
+
+        let job_post = &mut ctx.accounts.job.job_post;
+        job_post.transition(JobStatus::InProgress)?;
+        let application = &mut ctx.accounts.application;
+        application.applicant = ctx.accounts.freelancer.key();
+        application.job_post = job_post.key();
+        application.expected_end_date = end_date;
+        application.status = ApplicationStatus::Approved;
+        application.stage = ApplicationStage::Hired;
+
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.job.activity_feed.record(
+            ActivityKind::JobFilled,
+            job_post.key(),
+            job_post.job_id,
+            amount,
+            now,
+        );
+
+        emit!(JobPosted {
+            job_post: job_post.key(),
+            client: job_post.client,
+            job_id: job_post.job_id,
+            amount,
+            mint: None,
+            category: job_post.category,
+            start_date: job_post.start_date,
+            end_date,
+            timestamp: now,
+        });
+        emit!(ApplicationApproved {
+            application: application.key(),
+            job_post: job_post.key(),
+            freelancer: application.applicant,
+            amount,
+            timestamp: now,
+        });
+        msg!(
+            "Quote accepted: job #{} created with {} hired",
+            job_post.job_id,
+            application.applicant
+        );
+        Ok(())
+    }
+
+    // A standing engagement paid per period: the client escrows `periods` payments
+    // of period_amount up front, one unlocking every period_secs from now
+    pub fn create_retainer(
+        ctx: Context<CreateRetainer>,
+        freelancer: Pubkey,
+        period_amount: u64,
+        period_secs: i64,
+        periods: u16,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Client),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts
+                .freelancer_account
+                .has_role(UserRole::Freelancer),
+            ErrorCode::Unauthorized
+        );
+        require_keys_neq!(
+            freelancer,
+            ctx.accounts.client.key(),
+            ErrorCode::SelfDealing
+        );
+        require!(
+            period_amount > 0 && period_secs > 0 && periods > 0,
+            ErrorCode::InvalidRetainer
+        );
+        let total = period_amount
+            .checked_mul(u64::from(periods))
+            .ok_or(ErrorCode::InvalidRetainer)?;
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.retainer.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, total)?;
+
+        let retainer = &mut ctx.accounts.retainer;
+        retainer.client = ctx.accounts.client.key();
+        retainer.freelancer = freelancer;
+        retainer.period_amount = period_amount;
+        retainer.period_secs = period_secs;
+        retainer.periods_funded = periods;
+        retainer.periods_claimed = 0;
+        retainer.started_at = Clock::get()?.unix_timestamp;
+        retainer.bump = ctx.bumps.retainer;
+
+        emit!(RetainerCreated {
+            retainer: retainer.key(),
+            client: retainer.client,
+            freelancer,
+            period_amount,
+            period_secs,
+            periods,
+            timestamp: retainer.started_at,
+        });
+        msg!(
+            "Retainer of {} periods at {} lamports created for {}",
+            periods,
+            period_amount,
+            freelancer
+        );
+        Ok(())
+    }
+
+    // Pays the freelancer every period that has elapsed since their last claim
+    pub fn claim_period_payment(ctx: Context<ClaimPeriodPayment>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let retainer = &mut ctx.accounts.retainer;
+        let periods = retainer.claimable_periods(now);
+        require!(periods > 0, ErrorCode::NothingToClaim);
+
+        let amount = retainer.period_amount * u64::from(periods);
+        retainer.periods_claimed += periods;
+        let retainer_info = retainer.to_account_info();
+        let freelancer = ctx.accounts.freelancer.to_account_info();
+        transfer_from_escrow(&retainer_info, &freelancer, amount)?;
+
+        emit!(RetainerPaymentClaimed {
+            retainer: retainer.key(),
+            periods,
+            amount,
+            periods_claimed: retainer.periods_claimed,
+            timestamp: now,
+        });
+        msg!(
+            "Claimed {} retainer periods, {} of {} paid",
+            periods,
+            retainer.periods_claimed,
+            retainer.periods_funded
+        );
+        Ok(())
+    }
+
+    // Ends the retainer: periods already elapsed are still paid to the freelancer,
+    // and the rest returns to the client along with the rent
+    pub fn cancel_retainer(ctx: Context<CancelRetainer>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let retainer = &mut ctx.accounts.retainer;
+        let owed = retainer.period_amount * u64::from(retainer.claimable_periods(now));
+        let retainer_info = retainer.to_account_info();
+        let freelancer = ctx.accounts.freelancer.to_account_info();
+        transfer_from_escrow(&retainer_info, &freelancer, owed)?;
+
+        let refunded = retainer.period_amount
+            * u64::from(retainer.periods_funded - retainer.periods_claimed)
+            - owed;
+        emit!(RetainerCancelled {
+            retainer: retainer.key(),
+            paid_to_freelancer: owed,
+            refunded,
+            timestamp: now,
+        });
+        msg!(
+            "Retainer cancelled, {} lamports to the freelancer, {} refunded",
+            owed,
+            refunded
+        );
+        Ok(())
+    }
+
+    // Closes an unapproved application and returns its rent to the freelancer
+    pub fn withdraw_application(ctx: Context<WithdrawApplication>) -> Result<()> {
+        let application = &mut ctx.accounts.application;
+        require!(
+            !application.is_hired(),
+            ErrorCode::ApplicationAlreadyApproved
+        );
+        application.set_status(ApplicationStatus::Withdrawn)?;
+
+        // The deposit closes back to the applicant; after the start date the fee
+        // itself goes to the client
+        if application.application_fee > 0 {
+            let deposit = ctx
+                .accounts
+                .deposit
+                .as_ref()
+                .ok_or(ErrorCode::ApplicationFeeRequired)?;
+            if Clock::get()?.unix_timestamp >= ctx.accounts.job_post.start_date {
+                let deposit_info = deposit.to_account_info();
+                let client = ctx.accounts.client.to_account_info();
+                transfer_from_escrow(&deposit_info, &client, deposit.amount)?;
+            }
+        }
+
+        // Walking away from a pending offer frees the job again
+        if application.stage == ApplicationStage::Offered {
+            ctx.accounts.job_post.transition(JobStatus::Open)?;
+        }
+        let user = &mut ctx.accounts.user_account;
+        user.pending_applications = user.pending_applications.saturating_sub(1);
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.applicant_count = job_post.applicant_count.saturating_sub(1);
+
+        emit!(ApplicationWithdrawn {
+            application: ctx.accounts.application.key(),
+            job_post: ctx.accounts.job_post.key(),
+            applicant: ctx.accounts.application.applicant,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Application withdrawn from job: {}",
+            ctx.accounts.job_post.title
+        );
+        Ok(())
+    }
+
+    // Archives a paid application, returning its rent to the freelancer. The
+    // deposit and any referral bounty have to be settled first, since both are
+    // looked up through the application.
+    pub fn close_paid_application(ctx: Context<ClosePaidApplication>) -> Result<()> {
+        let application = &ctx.accounts.application;
+        require!(
+            application.status == ApplicationStatus::Paid,
+            ErrorCode::ApplicationNotPaid
+        );
+        require!(
+            application.application_fee == 0,
+            ErrorCode::ApplicationFeeUnsettled
+        );
+        require!(
+            application.referral_claimable == 0,
+            ErrorCode::ReferralBountyUnclaimed
+        );
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.applicant_count = job_post.applicant_count.saturating_sub(1);
+
+        emit!(ApplicationArchived {
+            application: application.key(),
+            job_post: application.job_post,
+            applicant: application.applicant,
+            released_amount: application.released_amount,
+            freelancer_rating: application.freelancer_rating,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Application closed for job: {}", job_post.title);
+        Ok(())
+    }
+
+    // Tops the applicant's bond up to what the job requires. The lamports sit on the
+    // application account until payout or default, or return when it is withdrawn.
+    pub fn post_completion_bond(ctx: Context<PostCompletionBond>) -> Result<()> {
+        let application = &ctx.accounts.application;
+        require!(
+            application.status == ApplicationStatus::Pending,
+            ErrorCode::ApplicationAlreadyApproved
+        );
+        let amount = ctx
+            .accounts
+            .job_post
+            .bond_amount
+            .saturating_sub(application.bond_posted);
+        require!(amount > 0, ErrorCode::BondAlreadyPosted);
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.freelancer.to_account_info(),
+                to: application.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, amount)?;
+        ctx.accounts.application.bond_posted += amount;
+
+        msg!(
+            "Completion bond of {} lamports posted for job #{}",
+            ctx.accounts.application.bond_posted,
+            ctx.accounts.job_post.job_id
+        );
+        Ok(())
+    }
+
+    // Returns the deposit once the applicant was hired or the job was cancelled, or
+    // earlier if the client signs off on it after a rejection
+    pub fn refund_application_fee(ctx: Context<SettleApplicationFee>) -> Result<()> {
+        let application = &ctx.accounts.application;
+        let job_post = &ctx.accounts.job_post;
+        require!(
+            application.is_hired()
+                || job_post.status == JobStatus::Cancelled
+                || (application.stage == ApplicationStage::Rejected
+                    && ctx.accounts.signer.key() == job_post.client),
+            ErrorCode::ApplicationFeeLocked
+        );
+        ctx.accounts.application.application_fee = 0;
+
+        msg!(
+            "Application fee of {} lamports refunded",
+            ctx.accounts.deposit.amount
+        );
+        Ok(())
+    }
+
+    // The client keeps the fee of an application rejected as spam; the deposit's
+    // rent still goes back to the applicant
+    pub fn forfeit_application_fee(ctx: Context<SettleApplicationFee>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.signer.key(),
+            ctx.accounts.job_post.client,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.application.stage == ApplicationStage::Rejected,
+            ErrorCode::ApplicationNotRejected
+        );
+
+        let amount = ctx.accounts.deposit.amount;
+        let deposit = ctx.accounts.deposit.to_account_info();
+        let client = ctx.accounts.signer.to_account_info();
+        transfer_from_escrow(&deposit, &client, amount)?;
+        ctx.accounts.application.application_fee = 0;
+
+        msg!("Application fee of {} lamports forfeited", amount);
+        Ok(())
+    }
+
+    // Names the registered user who referred the applicant; they collect the
+    // job's referral bounty if this application is hired and paid
+    pub fn set_referrer(ctx: Context<SetReferrer>) -> Result<()> {
+        let referrer = ctx.accounts.referrer_account.wallet;
+        let application = &mut ctx.accounts.application;
+        require!(
+            application.status == ApplicationStatus::Pending,
+            ErrorCode::ApplicationAlreadyApproved
+        );
+        require!(
+            referrer != application.applicant && referrer != ctx.accounts.job_post.client,
+            ErrorCode::InvalidReferrer
+        );
+        application.referrer = Some(referrer);
+
+        msg!("Referrer {} named on application", referrer);
+        Ok(())
+    }
+
+    // Routes this application's payouts into a whitelisted vesting program, or
+    // back to the freelancer's wallet when no program is passed
+    pub fn set_payout_vesting(ctx: Context<SetPayoutVesting>) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.mint.is_none(),
+            ErrorCode::TokenEscrowedJob
+        );
+        require!(
+            ctx.accounts.application.status != ApplicationStatus::Paid,
+            ErrorCode::AlreadyPaid
+        );
+
+        let program = match &ctx.accounts.vesting_program_record {
+            Some(record) => {
+                require!(record.active, ErrorCode::VestingProgramInactive);
+                require!(
+                    ctx.accounts.application.payout_splits.is_empty(),
+                    ErrorCode::SplitsWithVesting
+                );
+                Some(record.program)
+            }
+            None => None,
+        };
+        ctx.accounts.application.payout_vesting = program;
+
+        msg!("Payout vesting program set to {:?}", program);
+        Ok(())
+    }
+
+    // Splits the freelancer's share of each release between recipients, e.g. an
+    // agency and its subcontractors. Shares must sum to exactly 10000 bps; an
+    // empty list pays the freelancer's wallet again.
+    pub fn set_payout_splits(
+        ctx: Context<SetPayoutSplits>,
+        splits: Vec<PayoutSplit>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.mint.is_none(),
+            ErrorCode::TokenEscrowedJob
+        );
+        let application = &mut ctx.accounts.application;
+        require!(
+            application.status != ApplicationStatus::Paid,
+            ErrorCode::AlreadyPaid
+        );
+        if !splits.is_empty() {
+            require!(
+                application.payout_vesting.is_none(),
+                ErrorCode::SplitsWithVesting
+            );
+            require!(
+                splits.len() <= MAX_PAYOUT_SPLITS,
+                ErrorCode::InvalidPayoutSplits
+            );
+            let mut total = 0u64;
+            for (i, split) in splits.iter().enumerate() {
+                require!(split.share_bps > 0, ErrorCode::InvalidPayoutSplits);
+                require!(
+                    !splits[..i]
+                        .iter()
+                        .any(|other| other.recipient == split.recipient),
+                    ErrorCode::InvalidPayoutSplits
+                );
+                total += u64::from(split.share_bps);
+            }
+            require!(total == BPS_DENOMINATOR, ErrorCode::InvalidPayoutSplits);
+        }
+        application.payout_splits = splits;
+
+        emit!(PayoutSplitsSet {
+            application: application.key(),
+            splits: application.payout_splits.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Payouts split between {} recipients",
+            application.payout_splits.len()
+        );
+        Ok(())
+    }
+
+    // The applicant's counterpart of set_job_metadata: the work narration lives
+    // off-chain and the on-chain one is dropped. Can be updated until payout.
+    pub fn set_application_metadata(
+        ctx: Context<SetApplicationMetadata>,
+        uri: String,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        validate_metadata_uri(&uri)?;
+        let application = &mut ctx.accounts.application;
+        require!(
+            application.status != ApplicationStatus::Paid,
+            ErrorCode::AlreadyPaid
+        );
+        application.narration = String::new();
+        application.metadata = Some(ContentRef { uri, content_hash });
+
+        emit!(MetadataUpdated {
+            account: application.key(),
+            content_hash,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Application narration moved off-chain");
+        Ok(())
+    }
+
+    pub fn verify_application_metadata(
+        ctx: Context<VerifyApplicationMetadata>,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .application
+            .metadata
+            .as_ref()
+            .ok_or(ErrorCode::NoMetadata)?
+            .verify(&content_hash)
+    }
+
+    // Adds lamports to the job's referral bounty, escrowed alongside the budget
+    pub fn fund_referral_bounty(ctx: Context<FundReferralBounty>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidBounty);
+        ctx.accounts.job_post.require_open()?;
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, amount)?;
+
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.referral_bounty += amount;
+
+        msg!(
+            "Referral bounty for job #{} is now {} lamports",
+            job_post.job_id,
+            job_post.referral_bounty
+        );
+        Ok(())
+    }
+
+    // remaining_accounts: the wallet of every payout split recipient, when the
+    // job pays upfront
+    pub fn approve_application<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveApplication<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.hire(ctx.remaining_accounts)?;
+
+        msg!(
+            "Application approved for job: {}",
+            ctx.accounts.job_post.title
+        );
+        Ok(())
+    }
+
+    // The applicant names the price they would take the job for instead of the
+    // posted amount; 0 withdraws the proposal
+    pub fn propose_counter_offer(
+        ctx: Context<ProposeCounterOffer>,
+        proposed_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.job_post.require_accepting_applications()?;
+        let application = &mut ctx.accounts.application;
+        require!(
+            application.status == ApplicationStatus::Pending,
+            ErrorCode::ApplicationAlreadyApproved
+        );
+        application.proposed_amount = proposed_amount;
+
+        emit!(CounterOfferProposed {
+            application: application.key(),
+            job_post: application.job_post,
+            posted_amount: ctx.accounts.job_post.amount,
+            proposed_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Counter-offer of {} lamports on job #{}",
+            proposed_amount,
+            ctx.accounts.job_post.job_id
+        );
+        Ok(())
+    }
+
+    // Hires the applicant at their proposed price: the budget and client-side fee
+    // are re-derived, and the escrow is topped up from or refunded to the client.
+    // remaining_accounts: the wallet of every payout split recipient, when the
+    // job pays upfront
+    pub fn accept_counter_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptCounterOffer<'info>>,
+    ) -> Result<()> {
+        let proposed = ctx.accounts.hire.application.proposed_amount;
+        require!(proposed > 0, ErrorCode::NoCounterOffer);
+        require!(
+            ctx.accounts.hire.delegate.is_none(),
+            ErrorCode::DelegateCannotMoveFunds
+        );
+        let job_post = &mut ctx.accounts.hire.job_post;
+        require!(job_post.mint.is_none(), ErrorCode::TokenEscrowedJob);
+        require!(!job_post.is_team(), ErrorCode::TeamJob);
+        let config = &ctx.accounts.hire.config;
+        if proposed < job_post.amount {
+            require!(
+                proposed >= config.min_job_amount,
+                ErrorCode::BudgetBelowMinimum
+            );
+            require!(
+                proposed >= job_post.milestone_total,
+                ErrorCode::InvalidMilestone
+            );
+            require!(
+                proposed >= job_post.hourly_cap(),
+                ErrorCode::InsufficientEscrowFunds
+            );
+        }
+
+        let previous_amount = job_post.amount;
+        let held = previous_amount + job_post.client_fee;
+        let fee = bps_of(proposed, config.client_fee_bps);
+        let owed = proposed + fee;
+        if owed > held {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.hire.signer.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_context, owed - held)?;
+            ctx.accounts.hire.protocol_stats.record_escrow(owed - held);
+        } else {
+            let escrow = ctx.accounts.escrow.to_account_info();
+            let client = ctx.accounts.hire.signer.to_account_info();
+            transfer_from_escrow(&escrow, &client, held - owed)?;
+        }
+        job_post.amount = proposed;
+        job_post.client_fee = fee;
+        ctx.accounts.escrow.expected_amount = owed;
+        ctx.accounts.hire.application.proposed_amount = 0;
+
+        ctx.accounts.hire.hire(ctx.remaining_accounts)?;
+
+        msg!(
+            "Counter-offer accepted for job #{}: {} lamports, was {}",
+            ctx.accounts.hire.job_post.job_id,
+            proposed,
+            previous_amount
+        );
+        Ok(())
+    }
+
+    // Client-driven pipeline moves; Hired is only reachable through approve_application
+    pub fn set_application_stage(
+        ctx: Context<SetApplicationStage>,
+        stage: ApplicationStage,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.client == ctx.accounts.user_account.wallet,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Client),
+            ErrorCode::Unauthorized
+        );
+
+        // Offers carry an expiry and rejections a reason, so both have their own instructions
+        require!(
+            stage != ApplicationStage::Offered && stage != ApplicationStage::Rejected,
+            ErrorCode::InvalidStageTransition
+        );
+
+        let application = &mut ctx.accounts.application;
+        let previous_stage = application.stage;
+        require!(
+            previous_stage.can_transition_to(stage),
+            ErrorCode::InvalidStageTransition
+        );
+        application.stage = stage;
+
+        emit!(ApplicationStageChanged {
+            application: application.key(),
+            job_post: application.job_post,
+            job_id: ctx.accounts.job_post.job_id,
+            from: previous_stage,
+            to: stage,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Application stage changed from {:?} to {:?}",
+            previous_stage,
+            stage
+        );
+        Ok(())
+    }
+
+    // reason may be left empty
+    pub fn reject_application(ctx: Context<SetApplicationStage>, reason: String) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.client == ctx.accounts.user_account.wallet,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Client),
+            ErrorCode::Unauthorized
+        );
+
+        let clock = Clock::get()?;
+        let application = &mut ctx.accounts.application;
+        let previous_stage = application.stage;
+        require!(
+            previous_stage.can_transition_to(ApplicationStage::Rejected),
+            ErrorCode::InvalidStageTransition
+        );
+        application.stage = ApplicationStage::Rejected;
+        application.set_status(ApplicationStatus::Rejected)?;
+        application.rejection_reason = reason;
+        application.rejected_at = clock.unix_timestamp;
+
+        // Withdrawing a pending offer frees the job again
+        if previous_stage == ApplicationStage::Offered {
+            ctx.accounts.job_post.transition(JobStatus::Open)?;
+        }
+
+        emit!(ApplicationStageChanged {
+            application: application.key(),
+            job_post: application.job_post,
+            job_id: ctx.accounts.job_post.job_id,
+            from: previous_stage,
+            to: ApplicationStage::Rejected,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Application rejected: {}", application.rejection_reason);
+        Ok(())
+    }
+
+    // The job is held for the chosen candidate until they accept or the offer expires
+    pub fn make_offer(ctx: Context<MakeOffer>, expires_at: i64) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.client == ctx.accounts.user_account.wallet,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Client),
+            ErrorCode::Unauthorized
+        );
+        ctx.accounts.job_post.require_open()?;
+
+        let clock = Clock::get()?;
+        require!(expires_at > clock.unix_timestamp, ErrorCode::InvalidDates);
+
+        let application = &mut ctx.accounts.application;
+        let previous_stage = application.stage;
+        require!(
+            previous_stage.can_transition_to(ApplicationStage::Offered),
+            ErrorCode::InvalidStageTransition
+        );
+        application.stage = ApplicationStage::Offered;
+        application.offer_expires_at = expires_at;
+        ctx.accounts.job_post.transition(JobStatus::Filled)?;
+
+        emit!(ApplicationStageChanged {
+            application: application.key(),
+            job_post: application.job_post,
+            job_id: ctx.accounts.job_post.job_id,
+            from: previous_stage,
+            to: ApplicationStage::Offered,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Offer made, expires at {}", expires_at);
+        Ok(())
+    }
+
+    // remaining_accounts: the wallet of every payout split recipient, when the
+    // job pays upfront
+    pub fn accept_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptOffer<'info>>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.job_post.is_team(), ErrorCode::TeamJob);
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Freelancer),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.application.applicant == ctx.accounts.user_account.wallet,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.application.stage == ApplicationStage::Offered,
+            ErrorCode::NoPendingOffer
+        );
+        require!(
+            ctx.accounts.job_post.collateral_call_at == 0,
+            ErrorCode::CollateralCallOutstanding
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp <= ctx.accounts.application.offer_expires_at,
+            ErrorCode::OfferExpired
+        );
+
+        ctx.accounts
+            .application
+            .require_bond(&ctx.accounts.job_post)?;
+        record_relationship(
+            &ctx.accounts.relationship,
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.accounts.job_post.client,
+            ctx.accounts.application.applicant,
+        )?;
+        ctx.accounts.user_account.reserve_job_slot()?;
+        ctx.accounts.job_post.transition(JobStatus::InProgress)?;
+
+        let application = &mut ctx.accounts.application;
+        application.set_status(ApplicationStatus::Approved)?;
+        application.stage = ApplicationStage::Hired;
+        release_upfront(
+            &ctx.accounts.job_post,
+            application,
+            ctx.accounts.escrow.as_ref().map(|escrow| escrow.as_ref()),
+            Some(ctx.accounts.signer.as_ref()),
+            &ctx.accounts.user_account,
+            &ctx.accounts.payout,
+            ctx.remaining_accounts,
+            &mut ctx.accounts.config,
+            &mut ctx.accounts.protocol_stats,
+        )?;
+
+        emit!(ApplicationStageChanged {
+            application: application.key(),
+            job_post: application.job_post,
+            job_id: ctx.accounts.job_post.job_id,
+            from: ApplicationStage::Offered,
+            to: ApplicationStage::Hired,
+            timestamp: clock.unix_timestamp,
+        });
+
+        ctx.accounts.activity_feed.record(
+            ActivityKind::JobFilled,
+            ctx.accounts.job_post.key(),
+            ctx.accounts.job_post.job_id,
+            ctx.accounts.job_post.amount,
+            clock.unix_timestamp,
+        );
+
+        emit!(ApplicationApproved {
+            application: ctx.accounts.application.key(),
+            job_post: ctx.accounts.job_post.key(),
+            freelancer: ctx.accounts.application.applicant,
+            amount: ctx.accounts.job_post.amount,
+            timestamp: clock.unix_timestamp,
+        });
+        msg!("Offer accepted for job: {}", ctx.accounts.job_post.title);
+        Ok(())
+    }
+
+    // Permissionless: anyone can release a job held by an unanswered offer
+    pub fn expire_offer(ctx: Context<ExpireOffer>) -> Result<()> {
+        require!(
+            ctx.accounts.application.stage == ApplicationStage::Offered,
+            ErrorCode::NoPendingOffer
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp > ctx.accounts.application.offer_expires_at,
+            ErrorCode::OfferNotExpired
+        );
+
+        let application = &mut ctx.accounts.application;
+        application.stage = ApplicationStage::Rejected;
+        application.set_status(ApplicationStatus::Rejected)?;
+        ctx.accounts.job_post.transition(JobStatus::Open)?;
+
+        emit!(ApplicationStageChanged {
+            application: application.key(),
+            job_post: application.job_post,
+            job_id: ctx.accounts.job_post.job_id,
+            from: ApplicationStage::Offered,
+            to: ApplicationStage::Rejected,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!(
+            "Offer expired, job reopened: {}",
+            ctx.accounts.job_post.title
+        );
+        Ok(())
+    }
+
+    // A paid tryout for an applicant, escrowed in the trial account itself. With
+    // convert_on_approval, approving the trial hires the applicant and the trial
+    // payment counts towards the job budget.
+    pub fn fund_trial(
+        ctx: Context<FundTrial>,
+        amount: u64,
+        deadline: i64,
+        convert_on_approval: bool,
+    ) -> Result<()> {
+        let job_post = &ctx.accounts.job_post;
+        require!(amount > 0, ErrorCode::InvalidTrial);
+        require!(
+            deadline > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidDates
+        );
+        job_post.require_open()?;
+        require!(
+            !ctx.accounts.application.stage.is_terminal(),
+            ErrorCode::InvalidStageTransition
+        );
+        if convert_on_approval {
+            // The trial payment is refunded from the job escrow on conversion
+            require!(job_post.mint.is_none(), ErrorCode::TokenEscrowedJob);
+            require!(amount <= job_post.amount, ErrorCode::InvalidTrial);
+        }
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.trial.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, amount)?;
+
+        let trial = &mut ctx.accounts.trial;
+        trial.application = ctx.accounts.application.key();
+        trial.amount = amount;
+        trial.deadline = deadline;
+        trial.convert_on_approval = convert_on_approval;
+        trial.status = TrialStatus::Funded;
+        trial.bump = ctx.bumps.trial;
+
+        emit!(TrialFunded {
+            trial: trial.key(),
+            application: trial.application,
+            amount,
+            deadline,
+            convert_on_approval,
+        });
+        msg!(
+            "Trial of {} lamports funded for job #{}",
+            amount,
+            job_post.job_id
+        );
+        Ok(())
+    }
+
+    pub fn submit_trial(ctx: Context<SubmitTrial>, submission_link: String) -> Result<()> {
+        let trial = &mut ctx.accounts.trial;
+        require!(
+            trial.status == TrialStatus::Funded,
+            ErrorCode::InvalidTrialStatus
+        );
+        require!(
+            Clock::get()?.unix_timestamp <= trial.deadline,
+            ErrorCode::TrialExpired
+        );
+
+        trial.submission_link = submission_link;
+        trial.submitted_at = Clock::get()?.unix_timestamp;
+        trial.status = TrialStatus::Submitted;
+
+        emit!(TrialSubmitted {
+            trial: trial.key(),
+            application: trial.application,
+            submitted_at: trial.submitted_at,
+        });
+        msg!("Trial submitted: {}", trial.submission_link);
+        Ok(())
+    }
+
+    // Pays the trial. The client can approve at any time; anyone can once the job's
+    // review window has passed since submission.
+    // remaining_accounts: the wallet of every payout split recipient, when the
+    // trial converts on a job that pays upfront
+    pub fn approve_trial<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveTrial<'info>>,
+    ) -> Result<()> {
+        let trial = &ctx.accounts.trial;
+        require!(
+            trial.status == TrialStatus::Submitted,
+            ErrorCode::InvalidTrialStatus
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.signer.key() == ctx.accounts.job_post.client
+                || now >= trial.submitted_at + ctx.accounts.job_post.review_window_secs,
+            ErrorCode::ReviewWindowOpen
+        );
+
+        let amount = trial.amount;
+        let trial_info = ctx.accounts.trial.to_account_info();
+        let freelancer = ctx.accounts.freelancer.to_account_info();
+        transfer_from_escrow(&trial_info, &freelancer, amount)?;
+        ctx.accounts.trial.status = TrialStatus::Approved;
+
+        // Conversion needs the job to still be open; otherwise the trial just pays out
+        let job_post = &ctx.accounts.job_post;
+        let converted = ctx.accounts.trial.convert_on_approval
+            && job_post.status == JobStatus::Open
+            && !job_post.is_team()
+            && !ctx.accounts.application.stage.is_terminal()
+            && job_post.milestone_total + amount <= job_post.amount
+            && ctx.accounts.application.bond_posted >= job_post.bond_amount;
+        emit!(TrialApproved {
+            trial: ctx.accounts.trial.key(),
+            application: ctx.accounts.application.key(),
+            amount,
+            converted,
+            timestamp: now,
+        });
+        if converted {
+            // The client already paid this much, so the escrowed budget shrinks by it
+            let escrow = ctx.accounts.escrow.to_account_info();
+            let client = ctx.accounts.client.to_account_info();
+            transfer_from_escrow(&escrow, &client, amount)?;
+
+            record_relationship(
+                &ctx.accounts.relationship,
+                &ctx.accounts.signer.to_account_info(),
+                &ctx.accounts.system_program,
+                ctx.accounts.job_post.client,
+                ctx.accounts.application.applicant,
+            )?;
+            ctx.accounts.freelancer_account.reserve_job_slot()?;
+            let job_post = &mut ctx.accounts.job_post;
+            job_post.amount -= amount;
+            ctx.accounts.escrow.expected_amount -= amount;
+            job_post.transition(JobStatus::InProgress)?;
+            let application = &mut ctx.accounts.application;
+            let previous_stage = application.stage;
+            application.set_status(ApplicationStatus::Approved)?;
+            application.stage = ApplicationStage::Hired;
+            release_upfront(
+                job_post,
+                application,
+                Some(ctx.accounts.escrow.as_ref()),
+                Some(ctx.accounts.freelancer.as_ref()),
+                &ctx.accounts.freelancer_account,
+                &ctx.accounts.payout,
+                ctx.remaining_accounts,
+                &mut ctx.accounts.config,
+                &mut ctx.accounts.protocol_stats,
+            )?;
+
+            emit!(ApplicationStageChanged {
+                application: application.key(),
+                job_post: application.job_post,
+                job_id: job_post.job_id,
+                from: previous_stage,
+                to: ApplicationStage::Hired,
+                timestamp: now,
+            });
+            ctx.accounts.activity_feed.record(
+                ActivityKind::JobFilled,
+                job_post.key(),
+                job_post.job_id,
+                job_post.amount,
+                now,
+            );
+            emit!(ApplicationApproved {
+                application: application.key(),
+                job_post: job_post.key(),
+                freelancer: application.applicant,
+                amount: job_post.amount,
+                timestamp: now,
+            });
+            msg!(
+                "Trial approved and applicant hired for job #{}",
+                job_post.job_id
+            );
+        } else {
+            msg!("Trial approved, {} lamports paid", amount);
+        }
+        Ok(())
+    }
+
+    // The client takes the trial funds back once the deadline passes with no submission
+    pub fn cancel_trial(ctx: Context<CancelTrial>) -> Result<()> {
+        let trial = &ctx.accounts.trial;
+        require!(
+            trial.status == TrialStatus::Funded,
+            ErrorCode::InvalidTrialStatus
+        );
+        require!(
+            Clock::get()?.unix_timestamp > trial.deadline,
+            ErrorCode::TrialNotExpired
+        );
+
+        emit!(TrialCancelled {
+            trial: trial.key(),
+            application: trial.application,
+            refund: trial.amount,
+        });
+        msg!("Trial cancelled, {} lamports refunded", trial.amount);
+        Ok(())
+    }
+
+    // remaining_accounts: [freelancer_user_account, invitation_pda] pairs, one per invitee
+    pub fn invite_many<'info>(ctx: Context<'_, '_, 'info, 'info, InviteMany<'info>>) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.client == ctx.accounts.user_account.wallet,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Client),
+            ErrorCode::Unauthorized
+        );
+        ctx.accounts.job_post.require_open()?;
+
+        let remaining = ctx.remaining_accounts;
+        let invitees = validate_remaining_accounts(
+            remaining,
+            &[
+                AccountRule::owned_by(ctx.program_id),
+                AccountRule::new_account(),
+            ],
+            MAX_INVITES_PER_TX,
+        )?;
+
+        let job_post_key = ctx.accounts.job_post.key();
+        let clock = Clock::get()?;
+
+        let invite_count = invitees.len();
+        for pair in invitees {
+            let freelancer_account = Account::<UserAccount>::try_from(&pair[0])?;
+            require!(
+                freelancer_account.has_role(UserRole::Freelancer),
+                ErrorCode::Unauthorized
+            );
+
+            let invitation_info = &pair[1];
+            let freelancer = freelancer_account.wallet;
+            require_keys_neq!(
+                freelancer,
+                ctx.accounts.job_post.client,
+                ErrorCode::SelfDealing
+            );
+            let (expected, bump) = Pubkey::find_program_address(
+                &[b"invitation", job_post_key.as_ref(), freelancer.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                invitation_info.key(),
+                expected,
+                ErrorCode::InvalidInvitationAccounts
+            );
+            require!(
+                invitation_info.data_is_empty(),
+                ErrorCode::InvitationAlreadyExists
+            );
+
+            create_pda(
+                invitation_info,
+                &ctx.accounts.signer.to_account_info(),
+                &ctx.accounts.system_program,
+                &[
+                    b"invitation",
+                    job_post_key.as_ref(),
+                    freelancer.as_ref(),
+                    &[bump],
+                ],
+                8 + Invitation::INIT_SPACE,
+            )?;
+
+            let invitation = Invitation {
+                job_post: job_post_key,
+                client: ctx.accounts.user_account.wallet,
+                freelancer,
+                created_at: clock.unix_timestamp,
+                bump,
+            };
+            let mut data = invitation_info.try_borrow_mut_data()?;
+            invitation.try_serialize(&mut &mut data[..])?;
+
+            emit!(FreelancerInvited {
+                invitation: expected,
+                job_post: job_post_key,
+                freelancer,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        msg!(
+            "Sent {} invitations for job: {}",
+            invite_count,
+            ctx.accounts.job_post.title
+        );
+        Ok(())
+    }
+
+    // Invites one freelancer the client already knows; accept_invitation hires them
+    pub fn invite_freelancer(ctx: Context<InviteFreelancer>, freelancer: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.client == ctx.accounts.user_account.wallet,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Client),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts
+                .freelancer_account
+                .has_role(UserRole::Freelancer),
+            ErrorCode::Unauthorized
+        );
+        require_keys_neq!(
+            freelancer,
+            ctx.accounts.job_post.client,
+            ErrorCode::SelfDealing
+        );
+        ctx.accounts.job_post.require_open()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let invitation = &mut ctx.accounts.invitation;
+        invitation.job_post = ctx.accounts.job_post.key();
+        invitation.client = ctx.accounts.user_account.wallet;
+        invitation.freelancer = freelancer;
+        invitation.created_at = now;
+        invitation.bump = ctx.bumps.invitation;
+
+        emit!(FreelancerInvited {
+            invitation: invitation.key(),
+            job_post: invitation.job_post,
+            freelancer,
+            timestamp: now,
+        });
+        msg!(
+            "Invited {} to job: {}",
+            freelancer,
+            ctx.accounts.job_post.title
+        );
+        Ok(())
+    }
+
+    // The invited freelancer takes the job: an approved application is created and
+    // the job fills in one step. The invitation's rent goes back to the client.
+    // remaining_accounts: the wallet of every payout split recipient, when the
+    // job pays upfront
+    pub fn accept_invitation<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptInvitation<'info>>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts
+                .freelancer_account
+                .has_role(UserRole::Freelancer),
+            ErrorCode::Unauthorized
+        );
+        let job_post = &mut ctx.accounts.job_post;
+        job_post.require_open()?;
+        require!(
+            job_post.collateral_call_at == 0,
+            ErrorCode::CollateralCallOutstanding
+        );
+        require!(!job_post.is_team(), ErrorCode::TeamJob);
+
+        record_relationship(
+            &ctx.accounts.relationship,
+            &ctx.accounts.freelancer.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.accounts.client.key(),
+            ctx.accounts.freelancer.key(),
+        )?;
+        let freelancer_account = &mut ctx.accounts.freelancer_account;
+        freelancer_account.reserve_job_slot()?;
+        freelancer_account.pending_applications += 1;
+
+        job_post.transition(JobStatus::InProgress)?;
+        job_post.applicant_count += 1;
+        // Accepting posts the bond in the same step
+        if job_post.bond_amount > 0 {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.freelancer.to_account_info(),
+                    to: ctx.accounts.application.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_context, job_post.bond_amount)?;
+        }
+        let application = &mut ctx.accounts.application;
+        application.bond_posted = job_post.bond_amount;
+        application.applicant = ctx.accounts.freelancer.key();
+        application.job_post = job_post.key();
+        application.expected_end_date = job_post.end_date;
+        application.status = ApplicationStatus::Approved;
+        application.stage = ApplicationStage::Hired;
+        release_upfront(
+            job_post,
+            application,
+            ctx.accounts.escrow.as_ref().map(|escrow| escrow.as_ref()),
+            Some(ctx.accounts.freelancer.as_ref()),
+            &ctx.accounts.freelancer_account,
+            &ctx.accounts.payout,
+            ctx.remaining_accounts,
+            &mut ctx.accounts.config,
+            &mut ctx.accounts.protocol_stats,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        emit!(ApplicationStageChanged {
+            application: application.key(),
+            job_post: job_post.key(),
+            job_id: job_post.job_id,
+            from: ApplicationStage::Applied,
+            to: ApplicationStage::Hired,
+            timestamp: now,
+        });
+        ctx.accounts.activity_feed.record(
+            ActivityKind::JobFilled,
+            job_post.key(),
+            job_post.job_id,
+            job_post.amount,
+            now,
+        );
+        emit!(ApplicationApproved {
+            application: application.key(),
+            job_post: job_post.key(),
+            freelancer: application.applicant,
+            amount: job_post.amount,
+            timestamp: now,
+        });
+
+        msg!("Invitation accepted for job: {}", job_post.title);
+        Ok(())
+    }
+
+    // Permissionless: brings a reputation's decay up to date so readers see current values
+    pub fn refresh_reputation(ctx: Context<RefreshReputation>) -> Result<()> {
+        let reputation = &mut ctx.accounts.reputation;
+        reputation.apply_decay(&ctx.accounts.config, Clock::get()?.unix_timestamp);
+
+        msg!(
+            "Reputation refreshed: score {} strikes {}",
+            reputation.score,
+            reputation.strike_points
+        );
+        Ok(())
+    }
+
+    // A non-transferable credential mirroring the wallet's reputation. It is kept
+    // current at each payout and can be refreshed by anyone with sync_credential.
+    pub fn issue_credential(ctx: Context<IssueCredential>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let reputation = &mut ctx.accounts.reputation;
+        reputation.apply_decay(&ctx.accounts.config, now);
+
+        let credential = &mut ctx.accounts.credential;
+        credential.wallet = reputation.wallet;
+        credential.issued_at = now;
+        credential.bump = ctx.bumps.credential;
+        credential.sync(reputation, now);
+
+        emit!(CredentialUpdated {
+            wallet: credential.wallet,
+            completed_jobs: credential.completed_jobs,
+            average_rating_x100: credential.average_rating_x100,
+            score: credential.score,
+            timestamp: now,
+        });
+        msg!("Reputation credential issued to {}", credential.wallet);
+        Ok(())
+    }
+
+    // Permissionless: brings a credential in line with its reputation, e.g. after
+    // a dispute or decay
+    pub fn sync_credential(ctx: Context<SyncCredential>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let reputation = &mut ctx.accounts.reputation;
+        reputation.apply_decay(&ctx.accounts.config, now);
+        let credential = &mut ctx.accounts.credential;
+        credential.sync(reputation, now);
+
+        emit!(CredentialUpdated {
+            wallet: credential.wallet,
+            completed_jobs: credential.completed_jobs,
+            average_rating_x100: credential.average_rating_x100,
+            score: credential.score,
+            timestamp: now,
+        });
+        msg!(
+            "Credential synced: {} jobs, rating {}",
+            credential.completed_jobs,
+            credential.average_rating_x100
+        );
+        Ok(())
+    }
+
+    // Each snapshot is a new immutable PDA that external programs can reference
+    pub fn snapshot_reputation(ctx: Context<SnapshotReputation>) -> Result<()> {
+        let clock = Clock::get()?;
+        let reputation = &mut ctx.accounts.reputation;
+        reputation.apply_decay(&ctx.accounts.config, clock.unix_timestamp);
+
+        let snapshot = &mut ctx.accounts.snapshot;
+        snapshot.wallet = reputation.wallet;
+        snapshot.sequence = reputation.snapshot_count;
+        snapshot.score = reputation.score;
+        snapshot.strike_points = reputation.strike_points;
+        snapshot.imported_score = reputation.imported_score;
+        snapshot.taken_at = clock.unix_timestamp;
+        snapshot.bump = ctx.bumps.snapshot;
+
+        reputation.snapshot_count += 1;
+
+        msg!(
+            "Reputation snapshot {} taken: score {} strikes {}",
+            snapshot.sequence,
+            snapshot.score,
+            snapshot.strike_points
+        );
+        Ok(())
+    }
+
+    // Called by a whitelisted issuer to vouch for a user
+    pub fn attest_reputation(ctx: Context<AttestReputation>, score: u64) -> Result<()> {
+        require!(
+            ctx.accounts.issuer_record.active,
+            ErrorCode::InactiveReputationIssuer
+        );
+
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.issuer = ctx.accounts.signer.key();
+        attestation.subject = ctx.accounts.reputation.wallet;
+        attestation.score = score;
+        attestation.weighted_score = 0;
+        attestation.attested_at = Clock::get()?.unix_timestamp;
+        attestation.bump = ctx.bumps.attestation;
+        attestation.reweigh(
+            &mut ctx.accounts.reputation,
+            ctx.accounts.issuer_record.effective_weight_bps(),
+        );
+
+        msg!(
+            "Reputation attested by {}: {} (weighted {})",
+            attestation.issuer,
+            score,
+            attestation.weighted_score
+        );
+        Ok(())
+    }
+
+    pub fn update_attestation(ctx: Context<UpdateAttestation>, score: u64) -> Result<()> {
+        let refresh = &mut ctx.accounts.refresh;
+        require!(
+            refresh.attestation.issuer == ctx.accounts.signer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            refresh.issuer_record.active,
+            ErrorCode::InactiveReputationIssuer
+        );
+
+        refresh.attestation.score = score;
+        refresh.attestation.attested_at = Clock::get()?.unix_timestamp;
+        refresh.reweigh()
+    }
+
+    // Permissionless: re-applies the issuer's current weight to one attestation
+    pub fn reweigh_attestation(ctx: Context<RefreshAttestation>) -> Result<()> {
+        ctx.accounts.reweigh()
+    }
+
+    pub fn submit_work(
+        ctx: Context<SubmitWork>,
+        submission_link: String,
+        narration: String,
+    ) -> Result<()> {
+        require!(
+            submission_link.len() <= MAX_SUBMISSION_LINK_LEN
+                && narration.len() <= MAX_NARRATION_LEN,
+            ErrorCode::SubmissionTooLong
+        );
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Freelancer),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.application.applicant == ctx.accounts.user_account.wallet,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.application.is_hired(),
+            ErrorCode::ApplicationNotApproved
+        );
+        require!(
+            !ctx.accounts.application.defaulted,
+            ErrorCode::ApplicationDefaulted
+        );
+        require!(
+            ctx.accounts.job_post.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+
+        let application = &mut ctx.accounts.application;
+        application.submission_link = submission_link;
+        application.narration = narration;
+        let signer = ctx.accounts.signer.to_account_info();
+        resize_account(
+            &application.to_account_info(),
+            application.space(),
+            &signer,
+            &signer,
+            &ctx.accounts.system_program,
+        )?;
+        // Approvals were for the work being replaced
+        application.approvals.clear();
+        // Resubmitting before the client responds just replaces the link
+        if application.status != ApplicationStatus::Submitted {
+            application.set_status(ApplicationStatus::Submitted)?;
+        }
+        application.submitted_at = Clock::get()?.unix_timestamp;
+
+        emit!(WorkSubmitted {
+            application: application.key(),
+            job_post: application.job_post,
+            freelancer: application.applicant,
+            submitted_at: application.submitted_at,
+        });
+        msg!(
+            "Work submitted with link: {} and narration",
+            application.submission_link
+        );
+        Ok(())
+    }
+
+    // Either party can create the check-in log for a hired application
+    pub fn initialize_work_session_log(ctx: Context<InitializeWorkSessionLog>) -> Result<()> {
+        let signer = ctx.accounts.signer.key();
+        require!(
+            signer == ctx.accounts.application.applicant || signer == ctx.accounts.job_post.client,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.application.is_hired(),
+            ErrorCode::ApplicationNotApproved
+        );
+
+        let log = &mut ctx.accounts.log;
+        log.application = ctx.accounts.application.key();
+        log.check_in_count = 0;
+        log.pending_nonce = None;
+        log.nonce_issued_at = 0;
+        log.last_check_in_at = 0;
+        log.evidence_hash = [0; 32];
+        log.bump = ctx.bumps.log;
+
+        msg!("Work session log created");
+        Ok(())
+    }
+
+    pub fn initialize_time_log(ctx: Context<InitializeTimeLog>) -> Result<()> {
+        require!(ctx.accounts.job_post.hourly_rate > 0, ErrorCode::NotHourly);
+        require!(
+            ctx.accounts.application.is_hired(),
+            ErrorCode::ApplicationNotApproved
+        );
+
+        let log = &mut ctx.accounts.log;
+        log.application = ctx.accounts.application.key();
+        log.entries = Vec::new();
+        log.logged_hours = 0;
+        log.approved_hours = 0;
+        log.bump = ctx.bumps.log;
+
+        msg!("Time log created");
+        Ok(())
+    }
+
+    // The hired freelancer records hours worked; they are paid once the client
+    // approves them
+    pub fn log_time(ctx: Context<LogTime>, hours: u16, memo: String) -> Result<()> {
+        require!(hours > 0, ErrorCode::InvalidHourlyTerms);
+        require!(memo.len() <= MAX_TIME_MEMO_LEN, ErrorCode::MemoTooLong);
+        require!(
+            ctx.accounts.job_post.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            matches!(
+                ctx.accounts.application.status,
+                ApplicationStatus::Approved | ApplicationStatus::Submitted
+            ),
+            ErrorCode::ApplicationNotApproved
+        );
+        let log = &mut ctx.accounts.log;
+        require!(log.entries.len() < MAX_TIME_ENTRIES, ErrorCode::TimeLogFull);
+        let logged_hours = log.logged_hours + u32::from(hours);
+        require!(
+            logged_hours <= ctx.accounts.job_post.max_hours,
+            ErrorCode::HoursExceedMax
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        log.entries.push(TimeEntry {
+            hours,
+            memo,
+            logged_at: now,
+        });
+        log.logged_hours = logged_hours;
+
+        emit!(TimeLogged {
+            log: log.key(),
+            application: log.application,
+            hours,
+            logged_hours,
+            timestamp: now,
+        });
+        msg!("{} hours logged, {} in total", hours, logged_hours);
+        Ok(())
+    }
+
+    // Pays rate x hours for every unapproved entry and clears them. Like milestones,
+    // approve_submission later settles whatever remains of the budget.
+    // remaining_accounts: the wallet of every payout split recipient, if any
+    pub fn approve_time<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveTime<'info>>,
+    ) -> Result<()> {
+        require_top_level()?;
+        require!(
+            ctx.accounts.job_post.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            matches!(
+                ctx.accounts.application.status,
+                ApplicationStatus::Approved | ApplicationStatus::Submitted
+            ),
+            ErrorCode::ApplicationNotApproved
+        );
+        let log = &mut ctx.accounts.log;
+        let hours: u32 = log.entries.iter().map(|entry| u32::from(entry.hours)).sum();
+        require!(hours > 0, ErrorCode::NothingToClaim);
+        let amount = ctx.accounts.job_post.hourly_rate * u64::from(hours);
+        require!(
+            amount
+                <= ctx
+                    .accounts
+                    .application
+                    .unreleased_amount(&ctx.accounts.job_post),
+            ErrorCode::InsufficientEscrowFunds
+        );
+
+        log.entries.clear();
+        log.approved_hours += hours;
+
+        let escrow = ctx.accounts.escrow.to_account_info();
+        let payee = ctx.accounts.payout.payee(
+            &ctx.accounts.application,
+            &ctx.accounts.freelancer_account,
+            ctx.accounts.freelancer.to_account_info(),
+            ctx.remaining_accounts,
+        )?;
+        let paid = payee.pay_earnings(
+            &escrow,
+            amount,
+            &mut ctx.accounts.application,
+            &ctx.accounts.freelancer_account,
+            &mut ctx.accounts.config,
+        )?;
+        ctx.accounts.protocol_stats.record_payout(paid);
+        ctx.accounts.application.released_amount += amount;
+
+        emit!(TimeApproved {
+            log: ctx.accounts.log.key(),
+            application: ctx.accounts.log.application,
+            hours,
+            amount,
+            fee: amount - paid,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("{} hours approved, {} lamports released", hours, amount);
+        Ok(())
+    }
+
+    // Pays out whatever has vested and not yet been released, net of the
+    // freelancer fee. Releases by approval count against the vested amount, so
+    // nothing is paid twice.
+    // remaining_accounts: the wallet of every payout split recipient, if any
+    pub fn claim_vested<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimVested<'info>>,
+    ) -> Result<()> {
+        let job_post = &ctx.accounts.job_post;
+        require!(job_post.streaming, ErrorCode::NotStreaming);
+        require!(
+            job_post.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            matches!(
+                ctx.accounts.application.status,
+                ApplicationStatus::Approved | ApplicationStatus::Submitted
+            ),
+            ErrorCode::ApplicationNotApproved
+        );
+        require!(
+            !ctx.accounts.application.defaulted,
+            ErrorCode::ApplicationDefaulted
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let amount = job_post
+            .vested_amount(now)
+            .saturating_sub(ctx.accounts.application.released_amount);
+        require!(amount > 0, ErrorCode::NothingToClaim);
+
+        let escrow = ctx.accounts.escrow.to_account_info();
+        let payee = ctx.accounts.payout.payee(
+            &ctx.accounts.application,
+            &ctx.accounts.freelancer_account,
+            ctx.accounts.freelancer.to_account_info(),
+            ctx.remaining_accounts,
+        )?;
+        let paid = payee.pay_earnings(
+            &escrow,
+            amount,
+            &mut ctx.accounts.application,
+            &ctx.accounts.freelancer_account,
+            &mut ctx.accounts.config,
+        )?;
+        ctx.accounts.protocol_stats.record_payout(paid);
+        ctx.accounts.application.released_amount += amount;
+
+        let job_post = &ctx.accounts.job_post;
+        emit!(VestedClaimed {
+            job_post: job_post.key(),
+            application: ctx.accounts.application.key(),
+            amount,
+            fee: amount - paid,
+            total_released: ctx.accounts.application.released_amount,
+            timestamp: now,
+        });
+        msg!(
+            "Claimed {} vested lamports, {} of {} released",
+            amount,
+            ctx.accounts.application.released_amount,
+            job_post.amount
+        );
+        Ok(())
+    }
+
+    // The client challenges the freelancer with a fresh nonce, unpredictable
+    // before this slot, that must be answered within CHECK_IN_WINDOW_SECS
+    pub fn issue_check_in_nonce(ctx: Context<IssueCheckInNonce>) -> Result<()> {
+        require!(
+            ctx.accounts.application.status != ApplicationStatus::Paid,
+            ErrorCode::AlreadyPaid
+        );
+
+        let clock = Clock::get()?;
+        let log = &mut ctx.accounts.log;
+        let nonce = hashv(&[
+            log.evidence_hash.as_ref(),
+            log.application.as_ref(),
+            &log.check_in_count.to_le_bytes(),
+            &clock.slot.to_le_bytes(),
+            &clock.unix_timestamp.to_le_bytes(),
+        ])
+        .to_bytes();
+        log.pending_nonce = Some(nonce);
+        log.nonce_issued_at = clock.unix_timestamp;
+
+        emit!(CheckInNonceIssued {
+            application: log.application,
+            nonce,
+            expires_at: clock.unix_timestamp + CHECK_IN_WINDOW_SECS,
+        });
+        Ok(())
+    }
+
+    // The freelancer's signature over a transaction carrying the nonce proves they
+    // were live in the window. Each check-in is chained into evidence_hash, so
+    // the history cannot be rewritten without changing the final hash.
+    pub fn check_in(ctx: Context<CheckIn>, nonce: [u8; 32]) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let log = &mut ctx.accounts.log;
+        require!(
+            log.pending_nonce == Some(nonce),
+            ErrorCode::InvalidCheckInNonce
+        );
+        require!(
+            now <= log.nonce_issued_at + CHECK_IN_WINDOW_SECS,
+            ErrorCode::CheckInNonceExpired
+        );
+
+        log.evidence_hash = hashv(&[
+            log.evidence_hash.as_ref(),
+            nonce.as_ref(),
+            ctx.accounts.signer.key().as_ref(),
+            &now.to_le_bytes(),
+        ])
+        .to_bytes();
+        log.check_in_count += 1;
+        log.last_check_in_at = now;
+        log.pending_nonce = None;
+
+        emit!(CheckInRecorded {
+            application: log.application,
+            sequence: log.check_in_count,
+            evidence_hash: log.evidence_hash,
+            checked_in_at: now,
+        });
+        Ok(())
+    }
+
+    // Milestones draw on the existing escrow first; any shortfall is funded by the
+    // client now and added to the job amount
+    pub fn add_milestone(
+        ctx: Context<AddMilestone>,
+        title: String,
+        amount: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidMilestone);
+        require!(!ctx.accounts.job_post.streaming, ErrorCode::StreamingJob);
+        require!(!ctx.accounts.job_post.is_team(), ErrorCode::TeamJob);
+        ctx.accounts.job_post.require_not_closed()?;
+        require!(
+            ctx.accounts.job_post.mint.is_none(),
+            ErrorCode::TokenEscrowedJob
+        );
+
+        let job_post = &mut ctx.accounts.job_post;
+        let allocated = job_post.milestone_total + amount;
+        let shortfall = allocated.saturating_sub(job_post.amount);
+        if shortfall > 0 {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.client.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_context, shortfall)?;
+            ctx.accounts.protocol_stats.record_escrow(shortfall);
+            job_post.amount += shortfall;
+            ctx.accounts.escrow.expected_amount += shortfall;
+        }
+
+        let milestone = &mut ctx.accounts.milestone;
+        milestone.job_post = job_post.key();
+        milestone.index = job_post.milestone_count;
+        milestone.title = title;
+        milestone.amount = amount;
+        milestone.deadline = deadline;
+        milestone.status = MilestoneStatus::Pending;
+        milestone.bump = ctx.bumps.milestone;
+
+        job_post.milestone_count += 1;
+        job_post.milestone_total = allocated;
+
+        msg!(
+            "Milestone {} added: {} for {} lamports",
+            milestone.index,
+            milestone.title,
+            milestone.amount
+        );
+        Ok(())
+    }
+
+    // Releases one milestone, net of the freelancer fee, along with its share of
+    // the client-side fee; approve_submission later settles whatever remains.
+    // remaining_accounts: the wallet of every payout split recipient, if any
+    pub fn approve_milestone<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveMilestone<'info>>,
+    ) -> Result<()> {
+        require_top_level()?;
+        require!(
+            ctx.accounts.application.is_hired(),
+            ErrorCode::ApplicationNotApproved
+        );
+        require!(
+            ctx.accounts.application.status != ApplicationStatus::Paid,
+            ErrorCode::AlreadyPaid
+        );
+        require!(
+            ctx.accounts.application.status != ApplicationStatus::Disputed,
+            ErrorCode::ApplicationDisputed
+        );
+        require!(
+            ctx.accounts.milestone.status == MilestoneStatus::Pending,
+            ErrorCode::InvalidMilestone
+        );
+        require!(
+            ctx.accounts.job_post.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+
+        let amount = ctx.accounts.milestone.amount;
+        let unreleased = ctx
+            .accounts
+            .application
+            .unreleased_amount(&ctx.accounts.job_post);
+        require!(amount <= unreleased, ErrorCode::InsufficientEscrowFunds);
+        let client_fee = ctx.accounts.job_post.client_fee_share(amount, unreleased);
+        ctx.accounts.escrow.require_covers(
+            ctx.accounts.application.released_amount,
+            amount + client_fee,
+        )?;
+
+        let escrow = ctx.accounts.escrow.to_account_info();
+        let payee = ctx.accounts.payout.payee(
+            &ctx.accounts.application,
+            &ctx.accounts.freelancer_account,
+            ctx.accounts.freelancer.to_account_info(),
+            ctx.remaining_accounts,
+        )?;
+        let paid = payee.pay_earnings(
+            &escrow,
+            amount,
+            &mut ctx.accounts.application,
+            &ctx.accounts.freelancer_account,
+            &mut ctx.accounts.config,
+        )?;
+        ctx.accounts.protocol_stats.record_payout(paid);
+        ctx.accounts.application.released_amount += amount;
+
+        // The released share of the client-side fee accrues like the freelancer's,
+        // and no longer counts towards what the escrow owes
+        let referral = ctx
+            .accounts
+            .client_account
+            .referral_cut(&ctx.accounts.config, client_fee);
+        transfer_from_escrow(
+            &escrow,
+            &ctx.accounts.config.to_account_info(),
+            client_fee - referral,
+        )?;
+        ctx.accounts.config.accrued_fees += client_fee - referral;
+        pay_referral(
+            &escrow,
+            ctx.accounts.client_referrer.as_ref(),
+            &ctx.accounts.client_account,
+            referral,
+        )?;
+        ctx.accounts.job_post.client_fee -= client_fee;
+        ctx.accounts.escrow.expected_amount -= client_fee;
+
+        let milestone = &mut ctx.accounts.milestone;
+        milestone.status = MilestoneStatus::Released;
+
+        emit!(MilestoneApproved {
+            milestone: milestone.key(),
+            application: ctx.accounts.application.key(),
+            index: milestone.index,
+            amount,
+            fee: amount - paid,
+            client_fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Milestone {} approved, {} lamports released",
+            milestone.index,
+            amount
+        );
+        Ok(())
+    }
+
+    // Sends submitted work back to the freelancer instead of approving it
+    pub fn request_revision(ctx: Context<RequestRevision>, feedback: String) -> Result<()> {
+        if let Some(delegate) = &ctx.accounts.delegate {
+            delegate.authorize(DELEGATE_REQUEST_REVISION)?;
+        }
+        let application = &mut ctx.accounts.application;
+        require!(
+            application.status == ApplicationStatus::Submitted,
+            ErrorCode::WorkNotCompleted
+        );
+        require!(
+            application.revision_count < ctx.accounts.config.max_revisions,
+            ErrorCode::RevisionLimitReached
+        );
+
+        application.set_status(ApplicationStatus::Approved)?;
+        application.revision_count += 1;
+        application.revision_feedback = feedback;
+
+        emit!(RevisionRequested {
+            application: application.key(),
+            job_post: application.job_post,
+            revision_count: application.revision_count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Revision {} of {} requested",
+            application.revision_count,
+            ctx.accounts.config.max_revisions
+        );
+        Ok(())
+    }
+
+    // The hired freelancer asks for a later end date; a new request replaces any
+    // still pending
+    pub fn request_extension(ctx: Context<RequestExtension>, new_end_date: i64) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+        require!(
+            new_end_date > ctx.accounts.job_post.end_date,
+            ErrorCode::InvalidDates
+        );
+        let application = &mut ctx.accounts.application;
+        require!(
+            matches!(
+                application.status,
+                ApplicationStatus::Approved | ApplicationStatus::Submitted
+            ),
+            ErrorCode::ApplicationNotApproved
+        );
+        application.requested_end_date = new_end_date;
+
+        emit!(ExtensionRequested {
+            application: application.key(),
+            job_post: application.job_post,
+            current_end_date: ctx.accounts.job_post.end_date,
+            requested_end_date: new_end_date,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Extension of job #{} to {} requested",
+            ctx.accounts.job_post.job_id,
+            new_end_date
+        );
+        Ok(())
+    }
+
+    // Moves the job's end date to the pending request; the escrow is untouched
+    pub fn approve_extension(ctx: Context<ApproveExtension>) -> Result<()> {
+        let application = &mut ctx.accounts.application;
+        let new_end_date = application.requested_end_date;
+        require!(new_end_date > 0, ErrorCode::NoExtensionRequested);
+        let job_post = &mut ctx.accounts.job_post;
+        require!(
+            job_post.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+
+        let previous_end_date = job_post.end_date;
+        job_post.end_date = new_end_date;
+        application.requested_end_date = 0;
+
+        emit!(ExtensionApproved {
+            application: application.key(),
+            job_post: job_post.key(),
+            previous_end_date,
+            end_date: new_end_date,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Job #{} now ends at {}", job_post.job_id, new_end_date);
+        Ok(())
+    }
+
+    // A bonus from the client on top of a paid-out job, straight from their wallet
+    pub fn tip_freelancer(ctx: Context<TipFreelancer>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.job_post.mint.is_none(),
+            ErrorCode::TokenEscrowedJob
+        );
+        ctx.accounts.require_tippable(amount)?;
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: ctx.accounts.freelancer.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, amount)?;
+        ctx.accounts.freelancer_reputation.total_earned += amount;
+        ctx.accounts.record_tip(amount)
+    }
+
+    // Token-escrowed counterpart of tip_freelancer, paid in the job's mint
+    pub fn tip_freelancer_spl(ctx: Context<TipFreelancerSpl>, amount: u64) -> Result<()> {
+        ctx.accounts.base.require_tippable(amount)?;
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.client_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.freelancer_token_account.to_account_info(),
+                authority: ctx.accounts.base.client.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(cpi_context, amount, ctx.accounts.mint.decimals)?;
+        ctx.accounts.base.record_tip(amount)
+    }
+
+    // Mints the paid freelancer a one-of-one completion certificate through the
+    // Token Metadata program. The program's certificate authority is its verified
+    // creator, so the certificate can be told apart from copies.
+    pub fn mint_completion_certificate(ctx: Context<MintCompletionCertificate>) -> Result<()> {
+        require!(
+            ctx.accounts.application.status == ApplicationStatus::Paid,
+            ErrorCode::ApplicationNotPaid
+        );
+        let authority_bump = ctx.bumps.certificate_authority;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"certificate_authority", &[authority_bump]]];
+
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.certificate_mint.to_account_info(),
+                to: ctx.accounts.freelancer_token_account.to_account_info(),
+                authority: ctx.accounts.certificate_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::mint_to(cpi_context, 1)?;
+
+        ctx.accounts.create_metadata(signer_seeds)?;
+        ctx.accounts.create_master_edition(signer_seeds)?;
+
+        emit!(CompletionCertificateMinted {
+            application: ctx.accounts.application.key(),
+            job_post: ctx.accounts.job_post.key(),
+            freelancer: ctx.accounts.freelancer.key(),
+            mint: ctx.accounts.certificate_mint.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Completion certificate {} minted for job #{}",
+            ctx.accounts.certificate_mint.key(),
+            ctx.accounts.job_post.job_id
+        );
+        Ok(())
+    }
+
+    // The paid freelancer's one-time review of the client
+    pub fn review_client(ctx: Context<ReviewClient>, text: String, rating: u8) -> Result<()> {
+        require!((1..=MAX_RATING).contains(&rating), ErrorCode::InvalidRating);
+        let application = &mut ctx.accounts.application;
+        require!(
+            application.status == ApplicationStatus::Paid,
+            ErrorCode::NotPaid
+        );
+        require!(application.client_rating == 0, ErrorCode::AlreadyReviewed);
+
+        application.freelancer_review = text;
+        application.client_rating = rating;
+        ctx.accounts.client_reputation.record_rating(rating);
+
+        emit!(ClientReviewed {
+            application: application.key(),
+            client: ctx.accounts.job_post.client,
+            rating,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Client of job #{} rated {} of {}",
+            ctx.accounts.job_post.job_id,
+            rating,
+            MAX_RATING
+        );
+        Ok(())
+    }
+
+    // remaining_accounts: the wallet of every payout split recipient, if any
+    pub fn approve_submission<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveSubmission<'info>>,
+        client_review: String,
+        rating: u8,
+    ) -> Result<()> {
+        require_top_level()?;
+        require!(
+            ctx.accounts.job_post.mint.is_none(),
+            ErrorCode::TokenEscrowedJob
+        );
+        // Approvals short of the threshold are only counted; the one that
+        // releases the funds records the review
+        if !ctx.accounts.collect_approval()? {
+            return Ok(());
+        }
+        ctx.accounts.record_review(client_review, rating)?;
+        ctx.accounts.release_lamports(ctx.remaining_accounts)
+    }
+
+    // Token-escrowed counterpart of approve_submission; payouts go to token
+    // accounts of the job's mint owned by the freelancer and client
+    pub fn approve_submission_spl(
+        ctx: Context<ApproveSubmissionSpl>,
+        client_review: String,
+        rating: u8,
+    ) -> Result<()> {
+        require_top_level()?;
+        // Approvals short of the threshold are only counted; the one that
+        // releases the funds records the review
+        if !ctx.accounts.base.collect_approval()? {
+            return Ok(());
+        }
+        ctx.accounts.base.record_review(client_review, rating)?;
+        ctx.accounts.release_tokens()
+    }
+
+    // Permissionless: the client let the review window lapse after submission, so
+    // the next tranche is released as if approved. The caller signs and pays fees;
+    // user_account is the caller's own, so they must be registered.
+    pub fn claim_timed_out_payment<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveSubmission<'info>>,
+    ) -> Result<()> {
+        require_top_level()?;
+        require!(
+            ctx.accounts.job_post.mint.is_none(),
+            ErrorCode::TokenEscrowedJob
+        );
+        ctx.accounts.require_review_window_elapsed()?;
+
+        ctx.accounts.release_lamports(ctx.remaining_accounts)
+    }
+
+    pub fn claim_timed_out_payment_spl(ctx: Context<ApproveSubmissionSpl>) -> Result<()> {
+        require_top_level()?;
+        ctx.accounts.base.require_review_window_elapsed()?;
+
+        ctx.accounts.release_tokens()
+    }
+
+    // Returns lamports sent directly to the escrow PDA, beyond what it owes and its
+    // rent floor, to the client. Pass the hired application once the job is filled.
+    pub fn sweep_excess_to_client(ctx: Context<SweepExcessToClient>) -> Result<()> {
+        let job_post = &ctx.accounts.job_post;
+        // One application cannot account for what the rest of a team is owed
+        require!(
+            !(job_post.is_team() && job_post.status == JobStatus::InProgress),
+            ErrorCode::TeamJob
+        );
+        let outstanding = match &ctx.accounts.application {
+            // Token-escrowed jobs owe nothing in lamports
+            _ if job_post.mint.is_some() => 0,
+            Some(application) => {
+                require!(application.is_hired(), ErrorCode::ApplicationNotApproved);
+                if application.status == ApplicationStatus::Paid {
+                    0
+                } else {
+                    application.unreleased_amount(job_post)
+                }
+            }
+            None => {
+                require!(
+                    matches!(job_post.status, JobStatus::Open | JobStatus::Cancelled),
+                    ErrorCode::ApprovedApplicationRequired
+                );
+                job_post.amount
+            }
+        };
+        // The referral bounty and a lamport job's client-side fee are held until
+        // the job completes
+        let reserved = match &ctx.accounts.application {
+            Some(application) if application.status == ApplicationStatus::Paid => {
+                application.referral_claimable
+            }
+            _ if job_post.mint.is_some() => job_post.referral_bounty,
+            _ => job_post.referral_bounty + job_post.client_fee,
+        };
+
+        // Until payout, surplus is yield the client assigned to the freelancer
+        require!(
+            outstanding == 0 || job_post.yield_recipient != YieldRecipient::Freelancer,
+            ErrorCode::ExcessReservedForFreelancer
+        );
+
+        let escrow = ctx.accounts.escrow.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+        let excess = escrow
+            .lamports()
+            .saturating_sub(rent_floor)
+            .saturating_sub(outstanding)
+            .saturating_sub(reserved);
+        require!(excess > 0, ErrorCode::NoExcessFunds);
+        // Whatever the job reserves, the sweep never reaches into the budget and
+        // client-side fee a funded lamport escrow still owes
+        let owed = match &ctx.accounts.application {
+            _ if job_post.mint.is_some() || ctx.accounts.escrow.status != EscrowStatus::Funded => 0,
+            Some(application) => ctx.accounts.escrow.outstanding(application.released_amount),
+            None => ctx.accounts.escrow.outstanding(0),
+        };
+        require!(
+            excess + owed <= escrow.lamports() - rent_floor,
+            ErrorCode::EscrowOverdrawn
+        );
+
+        let client = ctx.accounts.client.to_account_info();
+        transfer_from_escrow(&escrow, &client, excess)?;
+
+        msg!("Swept {} excess lamports back to the client", excess);
+        Ok(())
+    }
+
+    // Arbitrators lock lamports in their stake account before taking disputes
+    pub fn register_arbitrator(ctx: Context<RegisterArbitrator>, stake: u64) -> Result<()> {
+        require!(
+            ctx.accounts.user_account.has_role(UserRole::Arbitrator),
+            ErrorCode::Unauthorized
+        );
+
+        let arbitrator_stake = &mut ctx.accounts.arbitrator_stake;
+        arbitrator_stake.arbitrator = ctx.accounts.signer.key();
+        arbitrator_stake.staked = 0;
+        arbitrator_stake.active_disputes = 0;
+        arbitrator_stake.slashed_total = 0;
+        arbitrator_stake.bump = ctx.bumps.arbitrator_stake;
+        ctx.accounts
+            .arbitrator_registry
+            .add(ctx.accounts.signer.key())?;
+
+        fund_stake(
+            &mut ctx.accounts.arbitrator_stake,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            stake,
+        )
+    }
+
+    pub fn add_arbitrator_stake(ctx: Context<ManageArbitratorStake>, amount: u64) -> Result<()> {
+        // Back in the rotation after a full withdrawal
+        ctx.accounts
+            .arbitrator_registry
+            .add(ctx.accounts.signer.key())?;
+        fund_stake(
+            &mut ctx.accounts.arbitrator_stake,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            amount,
+        )
+    }
+
+    // Stake stays locked while the arbitrator has disputes assigned
+    pub fn withdraw_arbitrator_stake(
+        ctx: Context<ManageArbitratorStake>,
+        amount: u64,
+    ) -> Result<()> {
+        let stake = &mut ctx.accounts.arbitrator_stake;
+        require!(stake.active_disputes == 0, ErrorCode::ArbitratorHasDisputes);
+        require!(
+            amount > 0 && amount <= stake.staked,
+            ErrorCode::InvalidStakeAmount
+        );
+
+        let stake_info = stake.to_account_info();
+        let signer = ctx.accounts.signer.to_account_info();
+        transfer_from_escrow(&stake_info, &signer, amount)?;
+        stake.staked -= amount;
+        // Leaving the rotation keeps assignment from walking past empty stakes
+        if stake.staked == 0 {
+            ctx.accounts.arbitrator_registry.remove(&stake.arbitrator);
+        }
+
+        msg!(
+            "Withdrew {} lamports, {} still staked",
+            amount,
+            stake.staked
+        );
+        Ok(())
+    }
+
+    // Permissionless: forwards accrued lamport fees to the configured treasury
+    pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let amount = config.accrued_fees;
+        require!(amount > 0, ErrorCode::NothingToClaim);
+
+        let config_info = config.to_account_info();
+        let treasury = ctx.accounts.fee_treasury.to_account_info();
+        transfer_from_escrow(&config_info, &treasury, amount)?;
+        config.accrued_fees = 0;
+
+        emit!(FeesClaimed {
+            mint: None,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Claimed {} lamports of protocol fees", amount);
+        Ok(())
+    }
+
+    // Permissionless: sends a token job's accrued fees to the treasury's token account
+    pub fn claim_token_fees(ctx: Context<ClaimTokenFees>) -> Result<()> {
+        let job_post = &mut ctx.accounts.job_post;
+        let amount = job_post.accrued_token_fees;
+        require!(amount > 0, ErrorCode::NothingToClaim);
+
+        let job_key = job_post.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"escrow", job_key.as_ref(), &[job_post.escrow_bump]]];
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.escrow_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.treasury_token_account.to_account_info(),
+                authority: ctx.accounts.escrow.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_context, amount, ctx.accounts.mint.decimals)?;
+        job_post.accrued_token_fees = 0;
+
+        emit!(FeesClaimed {
+            mint: job_post.mint,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Claimed {} tokens of protocol fees", amount);
+        Ok(())
+    }
+
+    pub fn claim_referral_bounty(ctx: Context<ClaimReferralBounty>) -> Result<()> {
+        let application = &mut ctx.accounts.application;
+        let amount = application.referral_claimable;
+        require!(amount > 0, ErrorCode::NothingToClaim);
+
+        let escrow = ctx.accounts.escrow.to_account_info();
+        let referrer = ctx.accounts.referrer.to_account_info();
+        transfer_from_escrow(&escrow, &referrer, amount)?;
+        application.referral_claimable = 0;
+        // The bounty was the last thing the completed job's escrow held
+        let client = ctx.accounts.client.to_account_info();
+        close_escrow(&ctx.accounts.job_post, &escrow, &client)?;
+
+        emit!(ReferralBountyClaimed {
+            application: application.key(),
+            referrer: ctx.accounts.referrer.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!("Referral bounty of {} lamports claimed", amount);
+        Ok(())
+    }
+
+    // Either party to an approved, unpaid application can open a dispute
+    // The arbiter is assigned by the program from the arbitrator registry;
+    // remaining_accounts carry the candidates it walks, see assign_arbiter
+    pub fn open_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, OpenDispute<'info>>,
+        reason: String,
+    ) -> Result<()> {
+        let signer = ctx.accounts.user_account.wallet;
+        let client = ctx.accounts.job_post.client;
+        let freelancer = ctx.accounts.application.applicant;
+        require!(
+            signer == client || signer == freelancer,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ctx.accounts.application.is_hired(),
+            ErrorCode::ApplicationNotApproved
+        );
+        require!(
+            ctx.accounts.application.status != ApplicationStatus::Paid,
+            ErrorCode::AlreadyPaid
+        );
+        require!(
+            !ctx.accounts.application.defaulted,
+            ErrorCode::ApplicationDefaulted
+        );
+
+        // A team hire is disputed on its own while the rest of the team works on
+        let job_post = &mut ctx.accounts.job_post;
+        if job_post.is_team() {
+            job_post.open_disputes += 1;
+        } else {
+            job_post.transition(JobStatus::Disputed)?;
+        }
+
+        let arbiter = assign_arbiter(
+            &mut ctx.accounts.arbitrator_registry,
+            ctx.remaining_accounts,
+            ctx.program_id,
+            &ctx.accounts.config,
+            [client, freelancer],
+            ctx.accounts.organization.as_deref(),
+            None,
+        )?;
+
+        // Once the window after submission closes, the outcome is final
+        let clock = Clock::get()?;
+        let work_submitted = ctx.accounts.application.status == ApplicationStatus::Submitted;
+        if work_submitted {
+            require!(
+                clock.unix_timestamp
+                    <= ctx.accounts.application.submitted_at
+                        + ctx.accounts.config.dispute_window_secs,
+                ErrorCode::DisputeWindowClosed
+            );
+        }
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.application = ctx.accounts.application.key();
+        dispute.job_post = ctx.accounts.job_post.key();
+        dispute.claimant = signer;
+        dispute.respondent = if signer == client { freelancer } else { client };
+        dispute.arbiter = arbiter;
+        dispute.reason = reason;
+        dispute.evidence_link = String::new();
+        dispute.opened_at = clock.unix_timestamp;
+        dispute.response_deadline = clock.unix_timestamp + DISPUTE_RESPONSE_WINDOW_SECS;
+        dispute.ruling_deadline = clock.unix_timestamp + DISPUTE_RULING_WINDOW_SECS;
+        dispute.status = DisputeStatus::AwaitingResponse;
+        dispute.ruling = None;
+        dispute.resolution = None;
+        dispute.claimant_challenged = false;
+        dispute.respondent_challenged = false;
+        dispute.work_submitted = work_submitted;
+        dispute.bump = ctx.bumps.dispute;
+
+        // The bond sits on the dispute account until settlement. It is posted in
+        // lamports, so token jobs post the minimum.
+        let contested = if ctx.accounts.job_post.mint.is_none() {
+            ctx.accounts
+                .application
+                .unreleased_amount(&ctx.accounts.job_post)
+        } else {
+            0
+        };
+        dispute.bond = ctx.accounts.config.dispute_bond(contested);
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.signer.to_account_info(),
+                to: dispute.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, dispute.bond)?;
+
+        ctx.accounts
+            .application
+            .set_status(ApplicationStatus::Disputed)?;
+        ctx.accounts.protocol_stats.total_disputes += 1;
+
+        emit!(DisputeOpened {
+            dispute: dispute.key(),
+            application: dispute.application,
+            claimant: dispute.claimant,
+            respondent: dispute.respondent,
+            arbiter: dispute.arbiter,
+            response_deadline: dispute.response_deadline,
+            ruling_deadline: dispute.ruling_deadline,
+        });
+
+        msg!(
+            "Dispute opened, response due by {} and ruling by {}",
+            dispute.response_deadline,
+            dispute.ruling_deadline
+        );
+        Ok(())
+    }
+
+    pub fn respond_to_dispute(ctx: Context<RespondToDispute>, evidence_link: String) -> Result<()> {
+        let dispute = &mut ctx.accounts.dispute;
+        require!(
+            dispute.respondent == ctx.accounts.signer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            dispute.status == DisputeStatus::AwaitingResponse,
+            ErrorCode::InvalidDisputeState
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp <= dispute.response_deadline,
+            ErrorCode::DisputeDeadlinePassed
+        );
+
+        dispute.evidence_link = evidence_link;
+        dispute.status = DisputeStatus::AwaitingRuling;
+
+        msg!("Dispute response recorded: {}", dispute.evidence_link);
+        Ok(())
+    }
+
+    // Each party gets one peremptory challenge that replaces the assigned arbiter
+    pub fn challenge_arbiter<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReassignArbiter<'info>>,
+    ) -> Result<()> {
+        let signer = ctx.accounts.signer.key();
+        let dispute = &mut ctx.accounts.dispute;
+        if signer == dispute.claimant {
+            require!(
+                !dispute.claimant_challenged,
+                ErrorCode::ChallengeAlreadyUsed
+            );
+            dispute.claimant_challenged = true;
+        } else if signer == dispute.respondent {
+            require!(
+                !dispute.respondent_challenged,
+                ErrorCode::ChallengeAlreadyUsed
+            );
+            dispute.respondent_challenged = true;
+        } else {
+            return err!(ErrorCode::Unauthorized);
+        }
+
+        ctx.accounts
+            .reassign(ctx.remaining_accounts, ctx.program_id, false)
+    }
+
+    // Either party can replace an arbiter by proving a past job between the
+    // arbiter and a party; this does not use up their peremptory challenge
+    pub fn report_arbiter_conflict<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReportArbiterConflict<'info>>,
+    ) -> Result<()> {
+        let dispute = &ctx.accounts.reassignment.dispute;
+        let signer = ctx.accounts.reassignment.signer.key();
+        require!(
+            signer == dispute.claimant || signer == dispute.respondent,
+            ErrorCode::Unauthorized
+        );
+
+        let arbiter = dispute.arbiter;
+        let is_party = |key: Pubkey| key == dispute.claimant || key == dispute.respondent;
+        let evidence_client = ctx.accounts.evidence_job_post.client;
+        let evidence_freelancer = ctx.accounts.evidence_application.applicant;
+        require!(
+            ctx.accounts.evidence_application.is_hired()
+                && ((evidence_freelancer == arbiter && is_party(evidence_client))
+                    || (evidence_client == arbiter && is_party(evidence_freelancer))),
+            ErrorCode::InvalidConflictEvidence
+        );
+
+        ctx.accounts
+            .reassignment
+            .reassign(ctx.remaining_accounts, ctx.program_id, true)
+    }
+
+    // The arbiter's reasoning is kept in an immutable Ruling record for precedent.
+    // remaining_accounts: the wallet of every payout split recipient, when the
+    // ruling pays the freelancer
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>,
+        ruling: DisputeRuling,
+        reason_code: RulingReason,
+        document_hash: [u8; 32],
+    ) -> Result<()> {
+        require_top_level()?;
+
+        let dispute = &ctx.accounts.settlement.dispute;
+        require!(
+            dispute.arbiter == ctx.accounts.signer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            dispute.status == DisputeStatus::AwaitingRuling,
+            ErrorCode::InvalidDisputeState
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp <= dispute.ruling_deadline,
+            ErrorCode::DisputeDeadlinePassed
+        );
+
+        let record = &mut ctx.accounts.ruling;
+        record.dispute = dispute.key();
+        record.application = dispute.application;
+        record.job_post = dispute.job_post;
+        record.arbiter = dispute.arbiter;
+        record.ruling = ruling;
+        record.reason_code = reason_code;
+        record.document_hash = document_hash;
+        record.ruled_at = clock.unix_timestamp;
+        record.bump = ctx.bumps.ruling;
+
+        ctx.accounts
+            .settlement
+            .settle(ruling, DisputeResolution::Arbiter, ctx.remaining_accounts)
+    }
+
+    // Release part of the escrow to the freelancer while the rest stays frozen
+    pub fn order_interim_release(ctx: Context<OrderInterimRelease>, amount: u64) -> Result<()> {
+        require_top_level()?;
+
+        let dispute = &ctx.accounts.dispute;
+        require!(
+            dispute.arbiter == ctx.accounts.signer.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            dispute.status != DisputeStatus::Resolved,
+            ErrorCode::InvalidDisputeState
+        );
+
+        // The contested remainder must stay in escrow until the final ruling
+        let remaining = ctx
+            .accounts
+            .application
+            .unreleased_amount(&ctx.accounts.job_post);
+        require!(
+            amount > 0 && amount < remaining,
+            ErrorCode::InvalidReleaseAmount
+        );
+
+        let escrow = ctx.accounts.escrow.to_account_info();
+        if ctx.accounts.job_post.mint.is_none() {
+            let freelancer = ctx.accounts.freelancer.to_account_info();
+            transfer_from_escrow(&escrow, &freelancer, amount)?;
+            ctx.accounts.protocol_stats.record_payout(amount);
+        } else {
+            let accounts = &ctx.accounts;
+            let (Some(mint), Some(vault), Some(to), Some(token_program)) = (
+                &accounts.mint,
+                &accounts.escrow_vault,
+                &accounts.freelancer_token_account,
+                &accounts.token_program,
+            ) else {
+                return err!(ErrorCode::TokenAccountsRequired);
+            };
+            transfer_from_vault(
+                &accounts.job_post,
+                &escrow,
+                vault,
+                mint,
+                to,
+                token_program,
+                amount,
+            )?;
+        }
+
+        let application = &mut ctx.accounts.application;
+        application.released_amount += amount;
+
+        emit!(InterimReleaseOrdered {
+            dispute: dispute.key(),
+            application: application.key(),
+            amount,
+            total_released: application.released_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Interim release of {} ordered, {} still frozen",
+            amount,
+            remaining - amount
+        );
+        Ok(())
+    }
+
+    // Permissionless: the respondent missed the response deadline, so the claimant
+    // wins. remaining_accounts as in resolve_dispute.
+    pub fn default_ruling<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleDispute<'info>>,
+    ) -> Result<()> {
+        let dispute = &ctx.accounts.dispute;
+        require!(
+            dispute.status == DisputeStatus::AwaitingResponse,
+            ErrorCode::InvalidDisputeState
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp > dispute.response_deadline,
+            ErrorCode::DisputeDeadlineNotReached
+        );
+
+        let ruling = if dispute.claimant == ctx.accounts.job_post.client {
+            DisputeRuling::RefundClient
+        } else {
+            DisputeRuling::ReleaseToFreelancer
+        };
+        ctx.accounts
+            .settle(ruling, DisputeResolution::Default, ctx.remaining_accounts)
+    }
+
+    // Permissionless: the arbiter missed the ruling deadline, so fall back to the
+    // timeout settlement of paying for submitted work and refunding otherwise.
+    // remaining_accounts as in resolve_dispute.
+    pub fn settle_expired_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleDispute<'info>>,
+    ) -> Result<()> {
+        let dispute = &ctx.accounts.dispute;
+        require!(
+            dispute.status == DisputeStatus::AwaitingRuling,
+            ErrorCode::InvalidDisputeState
+        );
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp > dispute.ruling_deadline,
+            ErrorCode::DisputeDeadlineNotReached
+        );
+
+        let ruling = if dispute.work_submitted {
+            DisputeRuling::ReleaseToFreelancer
+        } else {
+            DisputeRuling::RefundClient
+        };
+        ctx.accounts
+            .settle(ruling, DisputeResolution::Timeout, ctx.remaining_accounts)
+    }
+}
+
+fn fund_stake<'info>(
+    stake: &mut Account<'info, ArbitratorStake>,
+    from: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidStakeAmount);
+    let cpi_context = CpiContext::new(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: from.to_account_info(),
+            to: stake.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)?;
+    stake.staked += amount;
+
+    msg!(
+        "Arbitrator {} now has {} lamports staked",
+        stake.arbitrator,
+        stake.staked
+    );
+    Ok(())
+}
+
+// Arbiters must hold the arbitrator role, keep the minimum stake, and neither be
+// a party to the dispute nor run the job's organization
+fn is_eligible_arbiter(
+    arbiter: &UserAccount,
+    stake: &ArbitratorStake,
+    config: &Config,
+    parties: [Pubkey; 2],
+    organization: Option<&Organization>,
+) -> bool {
+    arbiter.has_role(UserRole::Arbitrator)
+        && stake.staked >= config.min_arbitrator_stake
+        && !parties.contains(&arbiter.wallet)
+        && !organization.is_some_and(|organization| {
+            organization.creator == arbiter.wallet || organization.is_admin(&arbiter.wallet)
+        })
+}
+
+// Hands the dispute to the first eligible arbitrator in registry order from
+// next_index, so neither party picks who rules. remaining_accounts hold
+// [user_account, arbitrator_stake, relationship with parties[0], relationship
+// with parties[1]] for each candidate in turn, and every candidate passed over
+// must be shown to be ineligible. A candidate who has been hired by or has
+// hired either party, or who runs the job's organization, is passed over.
+fn assign_arbiter<'info>(
+    registry: &mut ArbitratorRegistry,
+    candidates: &'info [AccountInfo<'info>],
+    program_id: &Pubkey,
+    config: &Config,
+    parties: [Pubkey; 2],
+    organization: Option<&Organization>,
+    current: Option<Pubkey>,
+) -> Result<Pubkey> {
+    let count = registry.arbitrators.len();
+    require!(count > 0, ErrorCode::NoEligibleArbiter);
+    let groups = validate_remaining_accounts(
+        candidates,
+        &[
+            AccountRule::owned_by(program_id),
+            AccountRule::writable_owned_by(program_id),
+            AccountRule::any(),
+            AccountRule::any(),
+        ],
+        count,
+    )?;
+
+    for (offset, group) in groups.enumerate() {
+        let index = (registry.next_index as usize + offset) % count;
+        let candidate = registry.arbitrators[index];
+        let (user_key, _) =
+            Pubkey::find_program_address(&[b"user", candidate.as_ref()], program_id);
+        let (stake_key, _) =
+            Pubkey::find_program_address(&[b"arbitrator", candidate.as_ref()], program_id);
+        require!(
+            group[0].key() == user_key
+                && group[1].key() == stake_key
+                && group[2].key() == WorkRelationship::address(candidate, parties[0]).0
+                && group[3].key() == WorkRelationship::address(candidate, parties[1]).0,
+            ErrorCode::InvalidArbiterCandidates
+        );
+        // Only this program can own an account at a relationship address
+        let worked_with_party = group[2].owner == program_id || group[3].owner == program_id;
+        let arbiter = Account::<UserAccount>::try_from(&group[0])?;
+        let mut stake = Account::<ArbitratorStake>::try_from(&group[1])?;
+        if current == Some(candidate)
+            || worked_with_party
+            || !is_eligible_arbiter(&arbiter, &stake, config, parties, organization)
+        {
+            continue;
+        }
+
+        stake.active_disputes += 1;
+        stake.exit(program_id)?;
+        registry.next_index = ((index + 1) % count) as u32;
+        return Ok(candidate);
+    }
+    err!(ErrorCode::NoEligibleArbiter)
+}
+
+// Notes a hire between client and freelancer, creating their WorkRelationship
+// the first time. Arbiter assignment reads these so past collaborators never
+// rule on each other's disputes.
+fn record_relationship<'info>(
+    relationship: &UncheckedAccount<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    client: Pubkey,
+    freelancer: Pubkey,
+) -> Result<()> {
+    let (expected, bump) = WorkRelationship::address(client, freelancer);
+    require_keys_eq!(
+        relationship.key(),
+        expected,
+        ErrorCode::InvalidRelationshipAccount
+    );
+    let info = relationship.to_account_info();
+    if info.owner == &ID {
+        let mut record = WorkRelationship::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        record.hires = record.hires.saturating_add(1);
+        return record.try_serialize(&mut &mut info.try_borrow_mut_data()?[..]);
+    }
+
+    let parties = WorkRelationship::ordered(client, freelancer);
+    create_pda(
+        &info,
+        payer,
+        system_program,
+        &[
+            b"relationship",
+            parties[0].as_ref(),
+            parties[1].as_ref(),
+            &[bump],
+        ],
+        8 + WorkRelationship::INIT_SPACE,
+    )?;
+
+    let record = WorkRelationship {
+        parties,
+        hires: 1,
+        first_hired_at: Clock::get()?.unix_timestamp,
+        bump,
+    };
+    let mut data = info.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])
+}
+
+// Creates a program-owned PDA of `space` bytes at `info`, signing with `seeds`.
+// Lamports sent to the address beforehand must not block it, so a funded
+// address is topped up to rent, then allocated and assigned instead.
+fn create_pda<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let signer = &[seeds];
+    let system = system_program.to_account_info();
+    if info.lamports() == 0 {
+        let cpi_context = CpiContext::new_with_signer(
+            system,
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: info.clone(),
+            },
+            signer,
+        );
+        return system_program::create_account(cpi_context, rent, space as u64, &ID);
+    }
+    let shortfall = rent.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        let cpi_context = CpiContext::new(
+            system.clone(),
+            system_program::Transfer {
+                from: payer.clone(),
+                to: info.clone(),
+            },
+        );
+        system_program::transfer(cpi_context, shortfall)?;
+    }
+    let cpi_context = CpiContext::new_with_signer(
+        system.clone(),
+        system_program::Allocate {
+            account_to_allocate: info.clone(),
+        },
+        signer,
+    );
+    system_program::allocate(cpi_context, space as u64)?;
+    let cpi_context = CpiContext::new_with_signer(
+        system,
+        system_program::Assign {
+            account_to_assign: info.clone(),
+        },
+        signer,
+    );
+    system_program::assign(cpi_context, &ID)
+}
+
+// Expected shape of one account within each remaining_accounts group
+pub struct AccountRule {
+    pub writable: bool,
+    pub owner: Option<Pubkey>,
+}
+
+impl AccountRule {
+    pub fn owned_by(owner: &Pubkey) -> Self {
+        AccountRule {
+            writable: false,
+            owner: Some(*owner),
+        }
+    }
+
+    pub fn writable_owned_by(owner: &Pubkey) -> Self {
+        AccountRule {
+            writable: true,
+            owner: Some(*owner),
+        }
+    }
+
+    // An account that may or may not exist yet, only read
+    pub fn any() -> Self {
+        AccountRule {
+            writable: false,
+            owner: None,
+        }
+    }
+
+    // An account this instruction is about to create
+    pub fn new_account() -> Self {
+        AccountRule {
+            writable: true,
+            owner: Some(system_program::ID),
+        }
+    }
+}
+
+// Checks that remaining_accounts form 1..=max_groups groups matching `layout`,
+// with no account repeated, and returns the groups
+fn validate_remaining_accounts<'a, 'info>(
+    accounts: &'a [AccountInfo<'info>],
+    layout: &[AccountRule],
+    max_groups: usize,
+) -> Result<std::slice::ChunksExact<'a, AccountInfo<'info>>> {
+    let groups = accounts.chunks_exact(layout.len());
+    require!(
+        !accounts.is_empty() && groups.remainder().is_empty() && groups.len() <= max_groups,
+        ErrorCode::InvalidRemainingAccountsCount
+    );
+
+    for (i, info) in accounts.iter().enumerate() {
+        require!(
+            !accounts[..i].iter().any(|other| other.key == info.key),
+            ErrorCode::DuplicateRemainingAccount
+        );
+        let rule = &layout[i % layout.len()];
+        require!(
+            !rule.writable || info.is_writable,
+            ErrorCode::RemainingAccountNotWritable
+        );
+        if let Some(owner) = rule.owner {
+            require_keys_eq!(*info.owner, owner, ErrorCode::RemainingAccountOwnerMismatch);
+        }
+    }
+    Ok(groups)
+}
+
+// Cyrillic and Greek letters that render like Latin ones
+const LATIN_LOOKALIKES: &[char] = &[
+    'а', 'е', 'о', 'р', 'с', 'у', 'х', 'і', 'ј', 'ѕ', 'һ', 'ԁ', 'ԛ', 'ԝ', 'А', 'В', 'Е', 'К', 'М',
+    'Н', 'О', 'Р', 'С', 'Т', 'Х', 'І', 'Ј', 'Ѕ', 'α', 'ο', 'ρ', 'ν', 'τ', 'υ', 'Α', 'Β', 'Ε', 'Ζ',
+    'Η', 'Ι', 'Κ', 'Μ', 'Ν', 'Ο', 'Ρ', 'Τ', 'Υ', 'Χ',
+];
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+}
+
+fn letter_script(c: char) -> Option<Script> {
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => Some(Script::Latin),
+        '\u{0370}'..='\u{03FF}' => Some(Script::Greek),
+        '\u{0400}'..='\u{052F}' => Some(Script::Cyrillic),
+        _ => None,
+    }
+}
+
+// Zero-width and bidirectional formatting characters that hide or reorder text
+fn is_invisible_format(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+    )
+}
+
+// Names and titles are shown verbatim by frontends, so reject strings that could
+// impersonate another user: hidden characters, padding, mixed Latin/Greek/Cyrillic
+// letters, or non-Latin text made only of Latin lookalikes
+fn validate_display_text(text: &str) -> Result<()> {
+    require!(!text.is_empty(), ErrorCode::EmptyText);
+    require!(text.trim() == text, ErrorCode::SurroundingWhitespace);
+    require!(
+        !text
+            .chars()
+            .any(|c| c.is_control() || is_invisible_format(c)),
+        ErrorCode::ControlCharacters
+    );
+
+    let mut script = None;
+    for c in text.chars() {
+        if let Some(current) = letter_script(c) {
+            require!(
+                script.is_none() || script == Some(current),
+                ErrorCode::MixedScripts
+            );
+            script = Some(current);
+        }
+    }
+
+    if matches!(script, Some(Script::Greek) | Some(Script::Cyrillic)) {
+        require!(
+            text.chars()
+                .any(|c| letter_script(c).is_some() && !LATIN_LOOKALIKES.contains(&c)),
+            ErrorCode::ConfusableText
+        );
+    }
+    Ok(())
+}
+
+fn upsert_extension_field(
+    fields: &mut Vec<ExtensionField>,
+    key: String,
+    value: ExtensionValue,
+) -> Result<()> {
+    require!(
+        !key.is_empty() && key.len() <= MAX_EXTENSION_KEY_LEN,
+        ErrorCode::InvalidExtensionField
+    );
+    if let ExtensionValue::Text { value: text } = &value {
+        require!(
+            text.len() <= MAX_EXTENSION_TEXT_LEN,
+            ErrorCode::InvalidExtensionField
+        );
+    }
+
+    msg!("Extension field set: {}", key);
+    match fields.iter_mut().find(|field| field.key == key) {
+        Some(field) => field.value = value,
+        None => {
+            require!(
+                fields.len() < MAX_EXTENSION_FIELDS,
+                ErrorCode::ExtensionFull
+            );
+            fields.push(ExtensionField { key, value });
+        }
+    }
+    Ok(())
+}
+
+fn remove_extension_field(fields: &mut Vec<ExtensionField>, key: &str) -> Result<()> {
+    let index = fields
+        .iter()
+        .position(|field| field.key == key)
+        .ok_or(ErrorCode::ExtensionFieldNotFound)?;
+    fields.remove(index);
+
+    msg!("Extension field removed: {}", key);
+    Ok(())
+}
+
+// "<title> #<amount>", with the title cut short to keep within Metaplex's name limit
+fn certificate_name(title: &str, amount: u64) -> String {
+    let suffix = format!(" #{amount}");
+    let title = truncate_utf8(title, MAX_CERTIFICATE_NAME_LEN - suffix.len());
+    format!("{title}{suffix}")
+}
+
+// Longest prefix of `text` within `max_len` bytes that ends on a char boundary
+fn truncate_utf8(text: &str, max_len: usize) -> &str {
+    let mut end = text.len().min(max_len);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+fn validate_user_name(name: &str) -> Result<()> {
+    require!(name.len() <= MAX_NAME_LEN, ErrorCode::NameTooLong);
+    validate_display_text(name)
+}
+
+// Tags are compared byte for byte, so they are kept to lowercase ASCII
+// letters, digits and inner hyphens, e.g. "rust" or "ui-design"
+fn validate_tags(tags: &[String], max_tags: usize) -> Result<()> {
+    require!(tags.len() <= max_tags, ErrorCode::TooManyTags);
+    for (index, tag) in tags.iter().enumerate() {
+        let well_formed = !tag.is_empty()
+            && tag.len() <= MAX_TAG_LEN
+            && !tag.starts_with('-')
+            && !tag.ends_with('-')
+            && tag
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+        require!(well_formed, ErrorCode::InvalidTag);
+        require!(!tags[..index].contains(tag), ErrorCode::DuplicateTag);
+    }
+    Ok(())
+}
+
+// Handles double as PDA seeds, so they are canonical: lowercase ASCII
+// letters, digits and underscores only
+fn validate_handle(handle: &str) -> Result<()> {
+    require!(
+        (MIN_HANDLE_LEN..=MAX_HANDLE_LEN).contains(&handle.len())
+            && handle
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_'),
+        ErrorCode::InvalidHandle
+    );
+    Ok(())
+}
+
+fn validate_job_title(title: &str) -> Result<()> {
+    require!(title.len() <= MAX_TITLE_LEN, ErrorCode::TitleTooLong);
+    validate_display_text(title)
+}
+
+fn validate_job_description(description: &str) -> Result<()> {
+    require!(
+        description.len() <= MAX_DESCRIPTION_LEN,
+        ErrorCode::DescriptionTooLong
+    );
+    Ok(())
+}
+
+fn validate_metadata_uri(uri: &str) -> Result<()> {
+    require!(
+        uri.len() <= MAX_METADATA_URI_LEN
+            && METADATA_URI_SCHEMES
+                .iter()
+                .any(|scheme| uri.len() > scheme.len() && uri.starts_with(scheme)),
+        ErrorCode::InvalidMetadataUri
+    );
+    Ok(())
+}
+
+// Jobs cannot end before they start or start in the past
+fn validate_job_dates(start_date: i64, end_date: i64, now: i64) -> Result<()> {
+    require!(start_date <= end_date, ErrorCode::InvalidDates);
+    require!(start_date >= now, ErrorCode::InvalidDates);
+    Ok(())
+}
+
+// Numeric code of an error, as reported to clients; builtin program errors
+// without a custom code map to u32::MAX
+fn error_number(error: Error) -> u32 {
+    match error {
+        Error::AnchorError(error) => error.error_code_number,
+        Error::ProgramError(error) => match error.program_error {
+            ProgramError::Custom(code) => code,
+            _ => u32::MAX,
+        },
+    }
+}
+
+// Sensitive instructions must be signed for directly, never reached through CPI
+fn require_top_level() -> Result<()> {
+    require!(
+        get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT,
+        ErrorCode::CpiNotAllowed
+    );
+    Ok(())
+}
+
+fn validate_lamport_destination(to: &AccountInfo) -> Result<()> {
+    require!(
+        !to.executable && !TOKEN_PROGRAM_IDS.contains(to.owner),
+        ErrorCode::InvalidPayoutDestination
+    );
+    Ok(())
+}
+
+// Fee a Token-2022 mint withholds from a transfer of `amount`; zero for legacy
+// SPL Token mints and mints without the transfer-fee extension
+fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != anchor_spl::token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let Ok(fee_config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+    let fee = fee_config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or(ErrorCode::InvalidTransferFee)?;
+    Ok(fee)
+}
+
+fn bps_of(amount: u64, bps: u16) -> u64 {
+    (u128::from(amount) * u128::from(bps) / u128::from(BPS_DENOMINATOR)) as u64
+}
+
+// A whitelisted vesting program and the account it locks a freelancer's payouts in
+struct VestingTarget<'info> {
+    program_id: Pubkey,
+    deposit_discriminator: [u8; 8],
+    program: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+}
+
+impl<'info> VestingTarget<'info> {
+    // Moves the payout into the vesting account, then notifies the vesting program
+    // with `deposit_discriminator || amount (u64 LE)` and accounts
+    // [vesting account (writable), beneficiary] so it can start the lockup
+    fn deposit(
+        &self,
+        escrow: &AccountInfo<'info>,
+        beneficiary: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        transfer_from_escrow(escrow, &self.account, amount)?;
+
+        let mut data = self.deposit_discriminator.to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        let instruction = Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.account.key(), false),
+                AccountMeta::new_readonly(beneficiary.key(), false),
+            ],
+            data,
+        };
+        invoke(
+            &instruction,
+            &[
+                self.account.clone(),
+                beneficiary.clone(),
+                self.program.clone(),
+            ],
+        )?;
+
+        emit!(PayoutVested {
+            beneficiary: beneficiary.key(),
+            vesting_program: self.program_id,
+            vesting_account: self.account.key(),
+            amount,
+        });
+        Ok(())
+    }
+}
+
+// Which party an escrow release is paid to
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Payee {
+    Freelancer,
+    Client,
+    Treasury,
+}
+
+// Where a freelancer's lamport earnings go: into their vesting program if they
+// chose one, else divided between their payout splits, else to their wallet
+struct FreelancerPayee<'a, 'info> {
+    freelancer: AccountInfo<'info>,
+    referrer: Option<UncheckedAccount<'info>>,
+    vesting: Option<VestingTarget<'info>>,
+    splits: Vec<PayoutSplit>,
+    split_recipients: &'a [AccountInfo<'info>],
+}
+
+impl<'info> FreelancerPayee<'_, 'info> {
+    // Pays `amount` out of `escrow`; returns how much of it went into vesting
+    fn deposit(&self, escrow: &AccountInfo<'info>, amount: u64) -> Result<u64> {
+        if amount == 0 {
+            return Ok(0);
+        }
+        match &self.vesting {
+            Some(vesting) => {
+                vesting.deposit(escrow, &self.freelancer, amount)?;
+                return Ok(amount);
+            }
+            None if !self.splits.is_empty() => {
+                pay_splits(escrow, &self.splits, self.split_recipients, amount)?
+            }
+            None => transfer_from_escrow(escrow, &self.freelancer, amount)?,
+        }
+        Ok(0)
+    }
+
+    // Pays out `earned` net of the freelancer fee, which accrues on the config
+    // account until claim_fees less the referrer's share. Returns the net payout.
+    fn pay_earnings(
+        &self,
+        escrow: &AccountInfo<'info>,
+        earned: u64,
+        application: &mut Application,
+        freelancer_account: &UserAccount,
+        config: &mut Account<'info, Config>,
+    ) -> Result<u64> {
+        let (fee, referral) = freelancer_fee(config, freelancer_account, earned, true);
+        application.vested_amount += self.deposit(escrow, earned - fee)?;
+        transfer_from_escrow(escrow, &config.to_account_info(), fee - referral)?;
+        config.accrued_fees += fee - referral;
+        pay_referral(escrow, self.referrer.as_ref(), freelancer_account, referral)?;
+        application.fees_paid += fee;
+        Ok(earned - fee)
+    }
+}
+
+// The freelancer fee on `earned` and the referrer's cut of it. Referral shares
+// are paid in lamports, so token jobs keep the whole fee.
+fn freelancer_fee(
+    config: &Config,
+    freelancer_account: &UserAccount,
+    earned: u64,
+    lamport_job: bool,
+) -> (u64, u64) {
+    let fee = bps_of(earned, config.freelancer_fee_bps);
+    let referral = if lamport_job {
+        freelancer_account.referral_cut(config, fee)
+    } else {
+        0
+    };
+    (fee, referral)
+}
+
+// Divides a freelancer payout between split recipients by share; the last
+// recipient takes the rounding remainder so the whole amount is paid
+fn pay_splits<'info>(
+    escrow: &AccountInfo<'info>,
+    splits: &[PayoutSplit],
+    recipients: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    let mut remaining = amount;
+    for (i, split) in splits.iter().enumerate() {
+        let recipient = recipients
+            .iter()
+            .find(|info| *info.key == split.recipient)
+            .ok_or(ErrorCode::SplitRecipientMissing)?;
+        let share = if i + 1 == splits.len() {
+            remaining
+        } else {
+            bps_of(amount, split.share_bps)
+        };
+        transfer_from_escrow(escrow, recipient, share)?;
+        remaining -= share;
+    }
+    Ok(())
+}
+
+// Moves the job's upfront share from escrow to the freelancer at hire, net of
+// the freelancer fee; later releases only pay what remains unreleased
+#[allow(clippy::too_many_arguments)]
+fn release_upfront<'info>(
+    job_post: &JobPost,
+    application: &mut Account<'info, Application>,
+    escrow: Option<&AccountInfo<'info>>,
+    freelancer: Option<&AccountInfo<'info>>,
+    freelancer_account: &UserAccount,
+    payout: &PayoutRouting<'info>,
+    split_recipients: &[AccountInfo<'info>],
+    config: &mut Account<'info, Config>,
+    stats: &mut ProtocolStats,
+) -> Result<()> {
+    if job_post.upfront_bps == 0 {
+        return Ok(());
+    }
+    let (escrow, freelancer) = escrow.zip(freelancer).ok_or(ErrorCode::EscrowRequired)?;
+    let amount = bps_of(application.budget(job_post), job_post.upfront_bps);
+    let payee = payout.payee(
+        application,
+        freelancer_account,
+        freelancer.clone(),
+        split_recipients,
+    )?;
+    let paid = payee.pay_earnings(escrow, amount, application, freelancer_account, config)?;
+    application.released_amount += amount;
+    stats.record_payout(paid);
+
+    emit!(UpfrontPaymentReleased {
+        application: application.key(),
+        job_post: application.job_post,
+        amount,
+        fee: amount - paid,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Pays a completion bond out of the application account holding it: to the
+// freelancer on completion, or to the client when the freelancer defaults
+fn pay_out_bond<'info>(
+    application: &mut Account<'info, Application>,
+    to: &AccountInfo<'info>,
+) -> Result<()> {
+    let bond = application.bond_posted;
+    application.bond_posted = 0;
+    transfer_from_escrow(&application.to_account_info(), to, bond)
+}
+
+// Sends a referrer their cut of a protocol fee straight from the escrow
+fn pay_referral<'info>(
+    escrow: &AccountInfo<'info>,
+    referrer: Option<&UncheckedAccount<'info>>,
+    user: &UserAccount,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let referrer = referrer.ok_or(ErrorCode::ReferrerAccountRequired)?;
+    transfer_from_escrow(escrow, referrer, amount)?;
+
+    emit!(ReferralFeePaid {
+        referrer: referrer.key(),
+        user: user.wallet,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Resizes a program-owned account to `len` bytes and keeps its rent exemption
+// in step: `payer` funds growth and freed rent goes to `refund_to`. Other
+// lamports the account holds, such as a bond, are left alone.
+fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    len: usize,
+    payer: &AccountInfo<'info>,
+    refund_to: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let current_len = account.data_len();
+    if len == current_len {
+        return Ok(());
+    }
+    let rent = Rent::get()?;
+    let (old_rent, new_rent) = (rent.minimum_balance(current_len), rent.minimum_balance(len));
+    if new_rent > old_rent {
+        let cpi_context = CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+        );
+        system_program::transfer(cpi_context, new_rent - old_rent)?;
+    } else {
+        transfer_from_escrow(account, refund_to, old_rent - new_rent)?;
+    }
+    account.realloc(len, false)?;
+    Ok(())
+}
+
+// Sweeps a settled job's escrow PDA into `to` and hands the account back to
+// the system program, as Anchor's close constraint does
+fn close_escrow<'info>(
+    job_post: &Account<'info, JobPost>,
+    escrow: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+) -> Result<()> {
+    let swept = escrow.lamports();
+    transfer_from_escrow(escrow, to, swept)?;
+    escrow.assign(&system_program::ID);
+    escrow.realloc(0, false)?;
+
+    emit!(EscrowClosed {
+        job_post: job_post.key(),
+        client: job_post.client,
+        swept,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// The escrow PDA is owned by this program, so lamports are moved directly
+// rather than through a system program transfer
+fn transfer_from_escrow<'info>(
+    escrow: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    validate_lamport_destination(to)?;
+    let escrow_balance = escrow
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ErrorCode::InsufficientEscrowFunds)?;
+    let to_balance = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ErrorCode::InsufficientEscrowFunds)?;
+    **escrow.try_borrow_mut_lamports()? = escrow_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+    Ok(())
+}
+
+// Sends a token job's escrowed tokens out of its vault, with the escrow PDA
+// signing as vault authority
+fn transfer_from_vault<'info>(
+    job_post: &Account<'info, JobPost>,
+    escrow: &AccountInfo<'info>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let job_key = job_post.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[b"escrow", job_key.as_ref(), &[job_post.escrow_bump]]];
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: vault.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: escrow.clone(),
+        },
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_context, amount, mint.decimals)
+}
+
+// Global marketplace settings, a singleton PDA
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub authority: Pubkey,
+    // How long after submission a dispute may still be opened
+    pub dispute_window_secs: i64,
+    // Reputation decay per period, in basis points; a period of 0 disables decay
+    pub reputation_decay_bps: u16,
+    pub strike_decay_bps: u16,
+    pub decay_period_secs: i64,
+    // Jobs above tranche_threshold lamports are split into tranche_count releases; 0 disables
+    pub tranche_threshold: u64,
+    pub tranche_count: u8,
+    // Revisions a client may request per application
+    pub max_revisions: u8,
+    // Protocol fees in basis points: added on top of the budget at funding, and
+    // deducted from the freelancer's payouts
+    pub client_fee_bps: u16,
+    pub freelancer_fee_bps: u16,
+    pub fee_treasury: Pubkey,
+    // Emergency stop: while set, everything but governance is rejected
+    pub paused: bool,
+    // Lamports an arbitrator must keep staked to be assigned disputes, and their
+    // cut of the contested escrow when they rule
+    pub min_arbitrator_stake: u64,
+    pub arbitrator_fee_bps: u16,
+    // Bond a claimant posts to open a dispute, as a share of the contested amount
+    // clamped to [min, max]; a max of 0 leaves it uncapped
+    pub dispute_bond_bps: u16,
+    pub min_dispute_bond: u64,
+    pub max_dispute_bond: u64,
+    // Lamport protocol fees held on this account until claim_fees sends them on
+    pub accrued_fees: u64,
+    // How long after end_date a hired freelancer may still deliver before the
+    // client can reclaim the escrow
+    pub delivery_grace_secs: i64,
+    // Current protocol terms; users must have accepted it to open new engagements
+    pub terms_version: u32,
+    // Collateral a USD-denominated token job must hold, as a share of the agreed value
+    pub collateral_ratio_bps: u16,
+    // Lowest budget, in lamports, a job can be reduced to with decrease_job_budget
+    pub min_job_amount: u64,
+    pub bump: u8,
+    // Share of protocol fees paid to a referrer, on a referred user's first
+    // referral_job_limit jobs
+    pub referral_fee_share_bps: u16,
+    pub referral_job_limit: u32,
+    #[max_len(64)] // MAX_CERTIFICATE_URI_BASE_LEN
+    pub certificate_base_uri: String,
+    // Price of featuring a job, per started day; 0 disables boost_job_post
+    pub boost_fee_per_day: u64,
+}
+
+impl Config {
+    pub fn dispute_bond(&self, contested: u64) -> u64 {
+        if self.dispute_bond_bps == 0 {
+            return 0;
+        }
+        let bond = bps_of(contested, self.dispute_bond_bps).max(self.min_dispute_bond);
+        if self.max_dispute_bond == 0 {
+            bond
+        } else {
+            bond.min(self.max_dispute_bond)
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserAccount {
+    pub wallet: Pubkey,
+    #[max_len(50)]
+    pub name: String,
+    // Bitflags of ROLE_*; one wallet may act on both sides of the marketplace
+    pub roles: u8,
+    // Freelancer work capacity: 0 means unlimited
+    pub max_active_jobs: u16,
+    pub active_jobs: u16,
+    // Wallet migration: the requested new wallet and when it can be finalized
+    pub pending_wallet: Option<Pubkey>,
+    pub migration_available_at: i64,
+    // Set on profiles created by a wallet migration
+    pub migrated_from: Option<Pubkey>,
+    // Contingency: who may take over the profile after a long inactivity horizon
+    pub beneficiary: Option<Pubkey>,
+    pub inactivity_horizon_secs: i64,
+    pub last_active_at: i64,
+    // Latest protocol terms version this user signed off on
+    pub accepted_terms_version: u32,
+    // Jobs posted and not yet cancelled or settled, and applications not yet
+    // withdrawn or settled; both must be zero to close the profile
+    pub open_job_posts: u16,
+    pub pending_applications: u16,
+    // Jobs this wallet has posted; the next job PDA is seeded with this value
+    pub job_counter: u64,
+    // Who referred this user, and how many of their jobs have paid out since
+    pub referred_by: Option<Pubkey>,
+    pub referral_jobs_paid: u32,
+    // Unique username, owned through the UsernameRecord seeded by it
+    #[max_len(32)] // MAX_HANDLE_LEN
+    pub handle: String,
+}
+
+// Maps a username to the wallet holding it; exists while the handle is taken
+#[account]
+#[derive(InitSpace)]
+pub struct UsernameRecord {
+    #[max_len(32)] // MAX_HANDLE_LEN
+    pub handle: String,
+    pub wallet: Pubkey,
+    pub bump: u8,
+}
+
+impl UserAccount {
+    pub fn has_role(&self, role: UserRole) -> bool {
+        self.roles & role.flag() != 0
+    }
+
+    pub fn has_accepted_terms(&self, config: &Config) -> bool {
+        self.accepted_terms_version >= config.terms_version
+    }
+
+    // The referrer's cut of a protocol fee charged on this user's job
+    pub fn referral_cut(&self, config: &Config, fee: u64) -> u64 {
+        if self.referred_by.is_none() || self.referral_jobs_paid >= config.referral_job_limit {
+            return 0;
+        }
+        bps_of(fee, config.referral_fee_share_bps)
+    }
+
+    pub fn record_referred_job(&mut self, config: &Config) {
+        if self.referred_by.is_some() && self.referral_jobs_paid < config.referral_job_limit {
+            self.referral_jobs_paid += 1;
+        }
+    }
+
+    pub fn reserve_job_slot(&mut self) -> Result<()> {
+        require!(
+            self.max_active_jobs == 0 || self.active_jobs < self.max_active_jobs,
+            ErrorCode::FreelancerAtCapacity
+        );
+        self.active_jobs = self.active_jobs.saturating_add(1);
+        Ok(())
+    }
+}
+
+// Structured freelancer data for matching, kept apart so UserAccount stays lean
+#[account]
+#[derive(InitSpace)]
+pub struct FreelancerProfile {
+    pub wallet: Pubkey,
+    #[max_len(10, 32)] // MAX_SKILLS, MAX_TAG_LEN
+    pub skills: Vec<String>,
+    // Unavailable freelancers cannot apply to jobs until they flip this back
+    pub available: bool,
+    // Advertised rate in lamports per hour; 0 when not quoted
+    pub hourly_rate: u64,
+    pub bump: u8,
+}
+
+// Arbitrators available for assignment, in the order disputes are handed out.
+// next_index is where the next assignment starts looking.
+#[account]
+#[derive(InitSpace)]
+pub struct ArbitratorRegistry {
+    pub authority: Pubkey,
+    #[max_len(64)] // MAX_ARBITRATORS
+    pub arbitrators: Vec<Pubkey>,
+    pub next_index: u32,
+    pub bump: u8,
+}
+
+impl ArbitratorRegistry {
+    pub fn add(&mut self, arbitrator: Pubkey) -> Result<()> {
+        if self.arbitrators.contains(&arbitrator) {
+            return Ok(());
+        }
+        require!(
+            self.arbitrators.len() < MAX_ARBITRATORS,
+            ErrorCode::ArbitratorRegistryFull
+        );
+        self.arbitrators.push(arbitrator);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, arbitrator: &Pubkey) {
+        self.arbitrators.retain(|key| key != arbitrator);
+        if self.next_index as usize >= self.arbitrators.len() {
+            self.next_index = 0;
+        }
+    }
+}
+
+// Stored values are as of last_decay_at; call apply_decay before reading or updating
+#[account]
+#[derive(InitSpace)]
+pub struct Reputation {
+    pub wallet: Pubkey,
+    pub score: u64,
+    pub strike_points: u64,
+    pub last_decay_at: i64,
+    pub snapshot_count: u32,
+    // Sum of weighted attestations from whitelisted external issuers
+    pub imported_score: u64,
+    pub bump: u8,
+    // Lifetime activity, never decayed. Totals are lamports from lamport-escrowed
+    // jobs only, since token amounts are not comparable across mints.
+    pub completed_jobs: u32,
+    pub total_earned: u64,
+    pub total_spent: u64,
+    pub rating_sum: u64,
+    pub rating_count: u32,
+    pub dispute_count: u32,
+}
+
+impl Reputation {
+    pub fn record_rating(&mut self, rating: u8) {
+        self.rating_sum = self.rating_sum.saturating_add(u64::from(rating));
+        self.rating_count = self.rating_count.saturating_add(1);
+    }
+
+    // Average star rating scaled by 100, e.g. 450 for 4.5 stars; 0 when unrated
+    pub fn average_rating_x100(&self) -> u64 {
+        (self.rating_sum * 100)
+            .checked_div(u64::from(self.rating_count))
+            .unwrap_or(0)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ReputationIssuer {
+    pub issuer: Pubkey,
+    // Multiplier applied to this issuer's scores, in basis points
+    pub weight_bps: u16,
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl ReputationIssuer {
+    pub fn effective_weight_bps(&self) -> u16 {
+        if self.active {
+            self.weight_bps
+        } else {
+            0
+        }
+    }
+}
+
+// USD price of a mint, published by the oracle governance registered for it
+#[account]
+#[derive(InitSpace)]
+pub struct PriceFeed {
+    pub mint: Pubkey,
+    pub oracle: Pubkey,
+    // Micro-USD per whole token
+    pub price_e6: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl PriceFeed {
+    // Micro-USD value of amount base units, from a price no older than MAX_PRICE_AGE_SECS
+    pub fn usd_value(&self, amount: u64, decimals: u8) -> Result<u64> {
+        require!(
+            self.updated_at > 0
+                && Clock::get()?.unix_timestamp - self.updated_at <= MAX_PRICE_AGE_SECS,
+            ErrorCode::StalePrice
+        );
+        // Token-2022 mints may have up to 255 decimals; beyond 38 the scale
+        // overflows u128, and no real price can be expressed at that precision
+        let scale = 10u128
+            .checked_pow(u32::from(decimals))
+            .ok_or(ErrorCode::InvalidPrice)?;
+        let value = u128::from(amount) * u128::from(self.price_e6) / scale;
+        Ok(u64::try_from(value).unwrap_or(u64::MAX))
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VestingProgram {
+    pub program: Pubkey,
+    pub deposit_discriminator: [u8; 8],
+    pub active: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ReputationAttestation {
+    pub issuer: Pubkey,
+    pub subject: Pubkey,
+    // Raw score as reported by the issuer
+    pub score: u64,
+    // Portion currently counted in the subject's imported_score
+    pub weighted_score: u64,
+    pub attested_at: i64,
+    pub bump: u8,
+}
+
+impl ReputationAttestation {
+    pub fn reweigh(&mut self, reputation: &mut Reputation, weight_bps: u16) {
+        let weighted =
+            (u128::from(self.score) * u128::from(weight_bps) / u128::from(BPS_DENOMINATOR)) as u64;
+        reputation.imported_score = reputation
+            .imported_score
+            .saturating_sub(self.weighted_score)
+            .saturating_add(weighted);
+        self.weighted_score = weighted;
+    }
+}
+
+// Soulbound reputation credential: a PDA per wallet, so it cannot be transferred,
+// holding the figures other programs gate on. Only this program updates it.
+#[account]
+#[derive(InitSpace)]
+pub struct ReputationCredential {
+    pub wallet: Pubkey,
+    pub completed_jobs: u32,
+    // Scaled by 100, as Reputation::average_rating_x100
+    pub average_rating_x100: u64,
+    pub rating_count: u32,
+    pub score: u64,
+    pub strike_points: u64,
+    pub dispute_count: u32,
+    pub issued_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ReputationCredential {
+    pub fn sync(&mut self, reputation: &Reputation, now: i64) {
+        self.completed_jobs = reputation.completed_jobs;
+        self.average_rating_x100 = reputation.average_rating_x100();
+        self.rating_count = reputation.rating_count;
+        self.score = reputation.score;
+        self.strike_points = reputation.strike_points;
+        self.dispute_count = reputation.dispute_count;
+        self.updated_at = now;
+    }
+}
+
+// Point-in-time copy of a Reputation, never modified after creation
+#[account]
+#[derive(InitSpace)]
+pub struct ReputationSnapshot {
+    pub wallet: Pubkey,
+    pub sequence: u32,
+    pub score: u64,
+    pub strike_points: u64,
+    pub imported_score: u64,
+    pub taken_at: i64,
+    pub bump: u8,
+}
+
+impl Reputation {
+    pub fn apply_decay(&mut self, config: &Config, now: i64) {
+        if config.decay_period_secs == 0 {
+            self.last_decay_at = now;
+            return;
+        }
+        let periods = (now - self.last_decay_at) / config.decay_period_secs;
+        if periods <= 0 {
+            return;
+        }
+        self.score = decay(self.score, config.reputation_decay_bps, periods as u64);
+        self.strike_points = decay(self.strike_points, config.strike_decay_bps, periods as u64);
+        self.last_decay_at += periods * config.decay_period_secs;
+    }
+}
+
+// value * (1 - decay_bps / 10_000) ^ periods, using fixed-point exponentiation by squaring
+fn decay(value: u64, decay_bps: u16, periods: u64) -> u64 {
+    const SCALE: u128 = 1_000_000_000_000;
+    let bps = u128::from(BPS_DENOMINATOR);
+    let mut factor = SCALE * (bps - u128::from(decay_bps).min(bps)) / bps;
+    let mut multiplier = SCALE;
+    let mut remaining = periods;
+    while remaining > 0 && multiplier > 0 {
+        if remaining & 1 == 1 {
+            multiplier = multiplier * factor / SCALE;
+        }
+        factor = factor * factor / SCALE;
+        remaining >>= 1;
+    }
+    (u128::from(value) * multiplier / SCALE) as u64
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum UserRole {
+    Client,
+    Freelancer,
+    Arbitrator,
+}
+
+pub const ROLE_CLIENT: u8 = 1 << 0;
+pub const ROLE_FREELANCER: u8 = 1 << 1;
+pub const ROLE_ARBITRATOR: u8 = 1 << 2;
+pub const ALL_ROLES: u8 = ROLE_CLIENT | ROLE_FREELANCER | ROLE_ARBITRATOR;
+
+impl UserRole {
+    pub fn flag(&self) -> u8 {
+        match self {
+            UserRole::Client => ROLE_CLIENT,
+            UserRole::Freelancer => ROLE_FREELANCER,
+            UserRole::Arbitrator => ROLE_ARBITRATOR,
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct JobPost {
+    pub client: Pubkey,
+    #[max_len(100)]
+    pub title: String,
+    pub amount: u64,
+    #[max_len(500)]
+    pub description: String,
+    pub status: JobStatus,
+    pub escrow_bump: u8,
+    // New: start and end dates (unix timestamps, in seconds)
+    pub start_date: i64,
+    pub end_date: i64,
+    pub yield_recipient: YieldRecipient,
+    // Number of sequential escrow releases; 1 for jobs under the tranche threshold
+    pub tranche_count: u8,
+    // Global sequential ID, e.g. "job #14302"
+    pub job_id: u64,
+    // Position among the client's jobs, which seeds this account's PDA
+    pub client_job_index: u64,
+    pub milestone_count: u8,
+    // Sum of all milestone amounts, never more than amount
+    pub milestone_total: u64,
+    // SPL mint the budget is escrowed in; None for lamport escrows
+    pub mint: Option<Pubkey>,
+    // Lamports paid to the hired freelancer's referrer at completion
+    pub referral_bounty: u64,
+    // Client-side protocol fee escrowed on top of amount, paid to the treasury at completion
+    pub client_fee: u64,
+    // After this long without approval, a submission can be paid out by anyone
+    pub review_window_secs: i64,
+    // Protocol fees left in the token vault until claim_token_fees
+    pub accrued_token_fees: u64,
+    // A Schedule account tracks this job's installments
+    pub has_schedule: bool,
+    // Agreed value in micro-USD for token jobs priced in dollars; 0 when unset
+    pub agreed_usd_value: u64,
+    // When a top-up call was raised against this job; 0 when none is outstanding
+    pub collateral_call_at: i64,
+    // Applications received and not withdrawn
+    pub applicant_count: u32,
+    pub visibility: JobVisibility,
+    // Cap on applicant_count; 0 leaves applications uncapped
+    pub max_applicants: u32,
+    // Refundable lamport deposit required with each application; 0 for none
+    pub application_fee: u64,
+    // Lamport bond the hired freelancer must post; 0 for none
+    pub bond_amount: u64,
+    // Hourly contracts pay hourly_rate lamports per approved hour, up to
+    // max_hours; 0 for fixed-price jobs
+    pub hourly_rate: u64,
+    pub max_hours: u32,
+    // Budget vests linearly over the job's dates and can be claimed as it does
+    pub streaming: bool,
+    // Share of amount released to the freelancer on hire
+    pub upfront_bps: u16,
+    // Deducted from a late submission's payout per day past end_date; 0 for none
+    pub late_penalty_bps_per_day: u16,
+    // Organization whose admins can act as the client on this job
+    pub organization: Option<Pubkey>,
+    // Team jobs hire up to max_hires freelancers; 0 or 1 hires a single one
+    pub max_hires: u8,
+    pub hire_count: u8,
+    // Team hires paid out or defaulted; the job closes when all are
+    pub settled_hires: u8,
+    // Sum of the shares handed to team hires so far
+    pub hired_share_bps: u16,
+    // Team hires under dispute; the job itself stays in progress meanwhile
+    pub open_disputes: u8,
+    pub category: JobCategory,
+    #[max_len(5, 32)] // MAX_JOB_TAGS, MAX_TAG_LEN
+    pub tags: Vec<String>,
+    // Paid placement: frontends surface the job first until this time
+    pub featured_until: i64,
+    // Off-chain description, replacing the on-chain one when set
+    pub metadata: Option<ContentRef>,
+    // When set, approval_threshold of these keys must approve each submission
+    // instead of the client
+    #[max_len(5)] // MAX_JOB_APPROVERS
+    pub approvers: Vec<Pubkey>,
+    pub approval_threshold: u8,
+}
+
+impl JobPost {
+    // Account size with the title and description at their current length and
+    // every other field at its maximum, so later updates still fit
+    pub fn space(&self) -> usize {
+        8 + JobPost::INIT_SPACE
+            - (MAX_TITLE_LEN - self.title.len())
+            - (MAX_DESCRIPTION_LEN - self.description.len())
+    }
+
+    pub fn is_team(&self) -> bool {
+        self.max_hires > 1
+    }
+
+    // Team jobs keep hiring while under way, until every slot is filled
+    pub fn require_team_slot(&self) -> Result<()> {
+        match self.status {
+            JobStatus::Open | JobStatus::InProgress if self.hire_count < self.max_hires => Ok(()),
+            JobStatus::Open | JobStatus::InProgress => err!(ErrorCode::TeamFull),
+            JobStatus::Cancelled => err!(ErrorCode::JobCancelled),
+            JobStatus::Completed => err!(ErrorCode::JobCompleted),
+            _ => err!(ErrorCode::JobAlreadyFilled),
+        }
+    }
+
+    // Budget shares are equal; the last slot takes any rounding remainder
+    pub fn take_team_share(&mut self) -> u16 {
+        self.hire_count += 1;
+        let share = if self.hire_count == self.max_hires {
+            BPS_DENOMINATOR as u16 - self.hired_share_bps
+        } else {
+            (BPS_DENOMINATOR / u64::from(self.max_hires)) as u16
+        };
+        self.hired_share_bps += share;
+        share
+    }
+
+    // Records a hire as finished; true once no hire is outstanding, which for
+    // single-hire jobs is always
+    pub fn settle_hire(&mut self) -> bool {
+        if !self.is_team() {
+            return true;
+        }
+        self.settled_hires += 1;
+        self.settled_hires >= self.hire_count
+    }
+
+    // Budget of team slots that were never filled
+    pub fn unhired_amount(&self) -> u64 {
+        if !self.is_team() {
+            return 0;
+        }
+        bps_of(self.amount, BPS_DENOMINATOR as u16 - self.hired_share_bps)
+    }
+
+    // Portion of `amount` withheld for a submission made at `submitted_at`;
+    // a partial day late counts as a full one
+    pub fn late_penalty(&self, amount: u64, submitted_at: i64) -> u64 {
+        if self.late_penalty_bps_per_day == 0 || submitted_at <= self.end_date {
+            return 0;
+        }
+        let days_late = (submitted_at - self.end_date + SECONDS_PER_DAY - 1) / SECONDS_PER_DAY;
+        let bps = (days_late as u128 * u128::from(self.late_penalty_bps_per_day))
+            .min(u128::from(BPS_DENOMINATOR));
+        (u128::from(amount) * bps / u128::from(BPS_DENOMINATOR)) as u64
+    }
+
+    // The share of the client-side fee still in escrow that releasing `amount` of
+    // the `unreleased` budget earns, so the fee is collected in step with it
+    pub fn client_fee_share(&self, amount: u64, unreleased: u64) -> u64 {
+        if unreleased == 0 {
+            return 0;
+        }
+        (u128::from(self.client_fee) * u128::from(amount) / u128::from(unreleased)) as u64
+    }
+
+    // Portion of the budget vested at `now`, linear between start_date and end_date
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if now <= self.start_date {
+            return 0;
+        }
+        if now >= self.end_date {
+            return self.amount;
+        }
+        let elapsed = (now - self.start_date) as u128;
+        let duration = (self.end_date - self.start_date) as u128;
+        (u128::from(self.amount) * elapsed / duration) as u64
+    }
+
+    // Offers, hires and pre-hire edits need the job open; the error says why it is not
+    pub fn require_open(&self) -> Result<()> {
+        match self.status {
+            JobStatus::Open => Ok(()),
+            JobStatus::Cancelled => err!(ErrorCode::JobCancelled),
+            _ => err!(ErrorCode::JobAlreadyFilled),
+        }
+    }
+
+    // Applications stay welcome while an offer is pending, since it may lapse
+    pub fn require_accepting_applications(&self) -> Result<()> {
+        match self.status {
+            JobStatus::Open | JobStatus::Filled => Ok(()),
+            JobStatus::Cancelled => err!(ErrorCode::JobCancelled),
+            _ => err!(ErrorCode::JobAlreadyFilled),
+        }
+    }
+
+    // Most an hourly contract can bill; 0 for fixed-price jobs
+    pub fn hourly_cap(&self) -> u64 {
+        self.hourly_rate * u64::from(self.max_hours)
+    }
+
+    pub fn require_not_closed(&self) -> Result<()> {
+        match self.status {
+            JobStatus::Cancelled => err!(ErrorCode::JobCancelled),
+            JobStatus::Completed => err!(ErrorCode::JobCompleted),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn transition(&mut self, next: JobStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(next),
+            ErrorCode::InvalidJobTransition
+        );
+        self.status = next;
+        Ok(())
+    }
+}
+
+// A job's escrow PDA. Lamport jobs hold the budget on it; token jobs hold it in
+// the vault this PDA signs for.
+#[account]
+#[derive(InitSpace)]
+pub struct Escrow {
+    pub job_post: Pubkey,
+    // Client who funded the escrow
+    pub funder: Pubkey,
+    // Budget plus client-side fee in the job's currency; follows budget changes
+    // but not payouts. Referral bounties are tracked on the job.
+    pub expected_amount: u64,
+    // SPL mint held in the vault; None for lamport escrows
+    pub mint: Option<Pubkey>,
+    pub status: EscrowStatus,
+}
+
+impl Escrow {
+    // What the escrow still owes once `released` of the budget has been paid out
+    pub fn outstanding(&self, released: u64) -> u64 {
+        self.expected_amount.saturating_sub(released)
+    }
+
+    // Releases and refunds never pay out more than the escrow was funded for
+    pub fn require_covers(&self, released: u64, amount: u64) -> Result<()> {
+        require!(
+            amount <= self.outstanding(released),
+            ErrorCode::EscrowOverdrawn
+        );
+        Ok(())
+    }
+}
+
+// Funded until the job settles. Released: the job completed and paid out.
+// Refunded: the job was cancelled and the client took the funds back.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum EscrowStatus {
+    Funded,
+    Released,
+    Refunded,
+}
+
+// Open: taking applications. Filled: held by a pending offer. InProgress: a
+// freelancer is hired. Completed and Cancelled are final.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum JobStatus {
+    Open,
+    Filled,
+    InProgress,
+    Completed,
+    Cancelled,
+    Disputed,
+}
+
+impl JobStatus {
+    pub fn can_transition_to(&self, next: JobStatus) -> bool {
+        use JobStatus::*;
+        matches!(
+            (self, next),
+            (Open, Filled)
+                | (Open, InProgress)
+                | (Open, Cancelled)
+                | (Filled, Open)
+                | (Filled, InProgress)
+                | (InProgress, Completed)
+                | (InProgress, Disputed)
+                | (InProgress, Cancelled)
+                | (Disputed, Completed)
+                | (Disputed, Cancelled)
+        )
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Milestone {
+    pub job_post: Pubkey,
+    pub index: u8,
+    #[max_len(100)]
+    pub title: String,
+    pub amount: u64,
+    pub deadline: i64,
+    pub status: MilestoneStatus,
+    pub bump: u8,
+}
+
+// A job's installments laid out up front, one period per tranche, so clients,
+// freelancers and cranks can see what is owed when
+#[account]
+#[derive(InitSpace)]
+pub struct Schedule {
+    pub job_post: Pubkey,
+    #[max_len(24)] // MAX_SCHEDULE_PERIODS
+    pub periods: Vec<SchedulePeriod>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct SchedulePeriod {
+    pub starts_at: i64,
+    pub ends_at: i64,
+    // Planned installment, replaced by the actual amount once released
+    pub amount: u64,
+    pub status: PeriodStatus,
+    pub released_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum PeriodStatus {
+    Pending,
+    Released,
+}
+
+// A freelancer's proposed engagement, waiting for the client to accept it
+#[account]
+#[derive(InitSpace)]
+pub struct Quote {
+    pub freelancer: Pubkey,
+    pub client: Pubkey,
+    // Hash of the off-chain scope of work both sides agreed on
+    pub scope_hash: [u8; 32],
+    pub amount: u64,
+    pub start_date: i64,
+    pub end_date: i64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+// Recurring engagement between a client and a freelancer; holds the unclaimed
+// period payments in lamports
+#[account]
+#[derive(InitSpace)]
+pub struct Retainer {
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub period_amount: u64,
+    pub period_secs: i64,
+    pub periods_funded: u16,
+    pub periods_claimed: u16,
+    pub started_at: i64,
+    pub bump: u8,
+}
+
+impl Retainer {
+    // Fully elapsed periods not yet paid out
+    pub fn claimable_periods(&self, now: i64) -> u16 {
+        let elapsed = (now - self.started_at).max(0) / self.period_secs;
+        let elapsed = u16::try_from(elapsed)
+            .unwrap_or(u16::MAX)
+            .min(self.periods_funded);
+        elapsed - self.periods_claimed
+    }
+}
+
+// An applicant's refundable application fee, held in lamports on this PDA
+#[account]
+#[derive(InitSpace)]
+pub struct ApplicationDeposit {
+    pub application: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+// Paid tryout attached to an application; holds its own escrowed lamports
+#[account]
+#[derive(InitSpace)]
+pub struct Trial {
+    pub application: Pubkey,
+    pub amount: u64,
+    // Last moment the applicant may submit the trial deliverable
+    pub deadline: i64,
+    pub convert_on_approval: bool,
+    pub status: TrialStatus,
+    #[max_len(200)]
+    pub submission_link: String,
+    pub submitted_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum TrialStatus {
+    Funded,
+    Submitted,
+    Approved,
+}
+
+// Tamper-evident record of a freelancer answering the client's check-in nonces
+#[account]
+#[derive(InitSpace)]
+pub struct WorkSessionLog {
+    pub application: Pubkey,
+    pub check_in_count: u32,
+    // Outstanding challenge, cleared once answered
+    pub pending_nonce: Option<[u8; 32]>,
+    pub nonce_issued_at: i64,
+    pub last_check_in_at: i64,
+    // Running hash over every answered nonce, signer and timestamp
+    pub evidence_hash: [u8; 32],
+    pub bump: u8,
+}
+
+// Hours an hourly contract's freelancer has logged; entries are cleared as the
+// client approves them
+#[account]
+#[derive(InitSpace)]
+pub struct TimeLog {
+    pub application: Pubkey,
+    #[max_len(16)] // MAX_TIME_ENTRIES
+    pub entries: Vec<TimeEntry>,
+    // Every hour logged so far, approved or not; capped at the job's max_hours
+    pub logged_hours: u32,
+    pub approved_hours: u32,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct TimeEntry {
+    pub hours: u16,
+    #[max_len(100)] // MAX_TIME_MEMO_LEN
+    pub memo: String,
+    pub logged_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum MilestoneStatus {
+    Pending,
+    Released,
+}
+
+// Who receives yield accrued on escrowed funds, settled at payout. FeeOffset
+// refunds the freelancer's protocol fees from yield and rebates the rest to the client.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum YieldRecipient {
+    Client,
+    Freelancer,
+    FeeOffset,
+}
+
+// Broad domain of a job, for filtering listings
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum JobCategory {
+    Development,
+    Design,
+    Writing,
+    Marketing,
+    Data,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum JobVisibility {
+    Public,
+    // Only wallets on the job's JobWhitelist can apply
+    Private,
+}
+
+// Shared client identity; any admin can hire and approve work on the
+// organization's jobs
+#[account]
+#[derive(InitSpace)]
+pub struct Organization {
+    pub creator: Pubkey,
+    #[max_len(50)] // MAX_NAME_LEN
+    pub name: String,
+    #[max_len(500)] // MAX_DESCRIPTION_LEN
+    pub description: String,
+    #[max_len(10)] // MAX_ORG_ADMINS
+    pub admins: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Organization {
+    pub fn is_admin(&self, wallet: &Pubkey) -> bool {
+        self.admins.contains(wallet)
+    }
+}
+
+// Actions a Delegate can take for its principal; none of them move funds
+pub const DELEGATE_APPROVE_APPLICATION: u8 = 1 << 0;
+pub const DELEGATE_REQUEST_REVISION: u8 = 1 << 1;
+pub const DELEGATE_SCOPES: u8 = DELEGATE_APPROVE_APPLICATION | DELEGATE_REQUEST_REVISION;
+
+// A session key allowed to act for its principal within scope until expires_at
+#[account]
+#[derive(InitSpace)]
+pub struct Delegate {
+    pub principal: Pubkey,
+    pub session_key: Pubkey,
+    pub scope: u8,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl Delegate {
+    pub fn authorize(&self, scope: u8) -> Result<()> {
+        require!(
+            self.scope & scope == scope,
+            ErrorCode::DelegateNotAuthorized
+        );
+        require!(
+            Clock::get()?.unix_timestamp < self.expires_at,
+            ErrorCode::DelegateExpired
+        );
+        Ok(())
+    }
+}
+
+// The wallet a signer acts for: its delegate's principal, or its own
+fn acting_wallet(signer: &Signer, delegate: &Option<Account<Delegate>>) -> Pubkey {
+    delegate
+        .as_ref()
+        .map_or(signer.key(), |delegate| delegate.principal)
+}
+
+// Client actions on a job are open to its client and, for organization jobs, to
+// the organization's admins
+fn is_job_client(
+    job_post: &JobPost,
+    organization: Option<&Account<Organization>>,
+    wallet: &Pubkey,
+) -> bool {
+    job_post.client == *wallet
+        || organization.is_some_and(|organization| {
+            job_post.organization == Some(organization.key()) && organization.is_admin(wallet)
+        })
+}
+
+// Long-form description for a job, kept in fixed-size bytes and accessed
+// without deserializing. The text is description[..description_len].
+#[account(zero_copy)]
+pub struct JobPostLarge {
+    pub job_post: Pubkey,
+    pub description_len: u32,
+    pub description: [u8; 8192], // MAX_LARGE_DESCRIPTION_LEN
+}
+
+impl JobPostLarge {
+    pub fn write(&mut self, offset: usize, chunk: &[u8]) -> Result<()> {
+        let end = offset
+            .checked_add(chunk.len())
+            .filter(|end| *end <= MAX_LARGE_DESCRIPTION_LEN)
+            .ok_or(ErrorCode::DescriptionTooLong)?;
+        require!(
+            offset <= self.description_len as usize,
+            ErrorCode::DescriptionGap
+        );
+        self.description[offset..end].copy_from_slice(chunk);
+        self.description_len = end as u32;
+        Ok(())
+    }
+
+    pub fn text(&self) -> &[u8] {
+        &self.description[..self.description_len as usize]
+    }
+}
+
+// Wallets allowed to apply to a private job, maintained by its client
+#[account]
+#[derive(InitSpace)]
+pub struct JobWhitelist {
+    pub job_post: Pubkey,
+    #[max_len(32)] // MAX_WHITELIST_LEN
+    pub applicants: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+// Optional marketplace-specific data attached to a job, so JobPost stays lean
+#[account]
+#[derive(InitSpace)]
+pub struct JobExtension {
+    pub job_post: Pubkey,
+    #[max_len(8)] // MAX_EXTENSION_FIELDS
+    pub fields: Vec<ExtensionField>,
+    pub bump: u8,
+}
+
+// Optional marketplace-specific applicant data, written by the applicant
+#[account]
+#[derive(InitSpace)]
+pub struct ApplicationExtension {
+    pub application: Pubkey,
+    #[max_len(8)] // MAX_EXTENSION_FIELDS
+    pub fields: Vec<ExtensionField>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct ExtensionField {
+    #[max_len(32)] // MAX_EXTENSION_KEY_LEN
+    pub key: String,
+    pub value: ExtensionValue,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum ExtensionValue {
+    Text {
+        #[max_len(64)] // MAX_EXTENSION_TEXT_LEN
+        value: String,
+    },
+    Integer {
+        value: i64,
+    },
+    Flag {
+        value: bool,
+    },
+    Address {
+        value: Pubkey,
+    },
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Application {
+    pub applicant: Pubkey,
+    pub job_post: Pubkey,
+    #[max_len(200)]
+    pub resume_link: String,
+    pub status: ApplicationStatus,
+    #[max_len(200)]
+    pub submission_link: String, // MAX_SUBMISSION_LINK_LEN
+    #[max_len(300)]
+    pub narration: String, // freelancer’s narration, MAX_NARRATION_LEN
+    #[max_len(300)]
+    pub client_review: String, // client’s review, MAX_REVIEW_LEN
+    // New: freelancer's expected end date for the job (unix timestamp, in seconds)
+    pub expected_end_date: i64,
+    pub stage: ApplicationStage,
+    // Set by make_offer; the offer lapses after this unix timestamp
+    pub offer_expires_at: i64,
+    // Escrow already paid to the freelancer ahead of final settlement
+    pub released_amount: u64,
+    // When submit_work last ran (unix timestamp, in seconds)
+    pub submitted_at: i64,
+    #[max_len(200)]
+    pub rejection_reason: String,
+    pub rejected_at: i64,
+    pub tranches_released: u8,
+    pub revision_count: u8,
+    #[max_len(300)]
+    pub revision_feedback: String, // client's latest revision request
+    // Registered user who referred the applicant, if any
+    pub referrer: Option<Pubkey>,
+    // Whitelisted vesting program the freelancer's payouts are deposited into
+    pub payout_vesting: Option<Pubkey>,
+    pub vested_amount: u64,
+    // Referral bounty held in escrow for the referrer to claim
+    pub referral_claimable: u64,
+    // Protocol fees withheld from this application's payouts
+    pub fees_paid: u64,
+    // The freelancer missed the deadline and the client reclaimed the escrow
+    pub defaulted: bool,
+    #[max_len(300)]
+    pub freelancer_review: String, // freelancer's review of the client
+    pub client_rating: u8,
+    // Client's 1-5 rating of the freelancer, set with client_review
+    pub freelancer_rating: u8,
+    // End date the freelancer asked to extend the job to; 0 when none is pending
+    pub requested_end_date: i64,
+    // Price the applicant counter-offered instead of the posted amount; 0 when none
+    pub proposed_amount: u64,
+    // Fee held in this application's ApplicationDeposit
+    pub application_fee: u64,
+    // Completion bond lamports held on this account
+    pub bond_posted: u64,
+    // Cumulative tips from the client, in the job's currency
+    pub tips_received: u64,
+    // Recipients sharing the freelancer's payouts; empty pays the freelancer
+    #[max_len(8)] // MAX_PAYOUT_SPLITS
+    pub payout_splits: Vec<PayoutSplit>,
+    // This hire's share of a team job's budget; 0 for single-hire jobs
+    pub share_bps: u16,
+    // Off-chain narration, replacing the on-chain one when set
+    pub metadata: Option<ContentRef>,
+    // Job approvers who have signed off the current submission
+    #[max_len(5)] // MAX_JOB_APPROVERS
+    pub approvals: Vec<Pubkey>,
+}
+
+// A document stored off-chain (Arweave, IPFS) and the SHA-256 of its content
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct ContentRef {
+    #[max_len(128)] // MAX_METADATA_URI_LEN
+    pub uri: String,
+    pub content_hash: [u8; 32],
+}
+
+impl ContentRef {
+    pub fn verify(&self, content_hash: &[u8; 32]) -> Result<()> {
+        require!(
+            self.content_hash == *content_hash,
+            ErrorCode::ContentHashMismatch
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct PayoutSplit {
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+}
+
+impl Application {
+    // Account size with the submission and client review at their current
+    // length and every other field at its maximum
+    pub fn space(&self) -> usize {
+        8 + Application::INIT_SPACE
+            - (MAX_SUBMISSION_LINK_LEN - self.submission_link.len())
+            - (MAX_NARRATION_LEN - self.narration.len())
+            - (MAX_REVIEW_LEN - self.client_review.len())
+    }
+
+    // Hired at some point, whether still working, in dispute or paid out
+    pub fn is_hired(&self) -> bool {
+        matches!(
+            self.status,
+            ApplicationStatus::Approved
+                | ApplicationStatus::Submitted
+                | ApplicationStatus::Paid
+                | ApplicationStatus::Disputed
+        )
+    }
+
+    pub fn require_bond(&self, job_post: &JobPost) -> Result<()> {
+        require!(
+            self.bond_posted >= job_post.bond_amount,
+            ErrorCode::BondNotPosted
+        );
+        Ok(())
+    }
+
+    pub fn set_status(&mut self, next: ApplicationStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(next),
+            ErrorCode::InvalidApplicationTransition
+        );
+        self.status = next;
+        Ok(())
+    }
+
+    // The part of the job's budget this hire is paid from
+    pub fn budget(&self, job_post: &JobPost) -> u64 {
+        if self.share_bps == 0 {
+            job_post.amount
+        } else {
+            bps_of(job_post.amount, self.share_bps)
+        }
+    }
+
+    pub fn unreleased_amount(&self, job_post: &JobPost) -> u64 {
+        self.budget(job_post).saturating_sub(self.released_amount)
+    }
+
+    // Untranched jobs release everything at once; the last tranche takes any remainder
+    pub fn next_tranche_amount(&self, job_post: &JobPost) -> u64 {
+        let remaining = self.unreleased_amount(job_post);
+        if job_post.tranche_count <= 1 || self.tranches_released + 1 >= job_post.tranche_count {
+            remaining
+        } else {
+            (self.budget(job_post) / u64::from(job_post.tranche_count)).min(remaining)
+        }
+    }
+}
+
+// Where the engagement stands, alongside the hiring pipeline's stage. Submitted
+// drops back to Approved when a revision or the next tranche is due.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum ApplicationStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Submitted,
+    Paid,
+    Withdrawn,
+    Disputed,
+}
+
+impl ApplicationStatus {
+    pub fn can_transition_to(&self, next: ApplicationStatus) -> bool {
+        use ApplicationStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Approved)
+                | (Pending, Rejected)
+                | (Pending, Withdrawn)
+                | (Rejected, Withdrawn)
+                | (Approved, Submitted)
+                | (Approved, Disputed)
+                | (Submitted, Approved)
+                | (Submitted, Paid)
+                | (Submitted, Disputed)
+                | (Disputed, Paid)
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum ApplicationStage {
+    Applied,
+    Screened,
+    Interviewed,
+    Offered,
+    Hired,
+    Rejected,
+}
+
+impl ApplicationStage {
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, ApplicationStage::Hired | ApplicationStage::Rejected)
+    }
+
+    // Stages only move forward through the funnel; any open application can be rejected
+    pub fn can_transition_to(&self, next: ApplicationStage) -> bool {
+        if self.is_terminal() {
+            return false;
+        }
+        match next {
+            ApplicationStage::Rejected => true,
+            ApplicationStage::Screened
+            | ApplicationStage::Interviewed
+            | ApplicationStage::Offered => (next as u8) > (*self as u8),
+            ApplicationStage::Applied | ApplicationStage::Hired => false,
+        }
+    }
+}
+
+// Two wallets that have been client and freelancer on the same job
+#[account]
+#[derive(InitSpace)]
+pub struct WorkRelationship {
+    // In byte order, which is also the seed order
+    pub parties: [Pubkey; 2],
+    pub hires: u32,
+    pub first_hired_at: i64,
+    pub bump: u8,
+}
+
+impl WorkRelationship {
+    pub fn ordered(a: Pubkey, b: Pubkey) -> [Pubkey; 2] {
+        if a <= b {
+            [a, b]
+        } else {
+            [b, a]
+        }
+    }
+
+    pub fn address(a: Pubkey, b: Pubkey) -> (Pubkey, u8) {
+        let parties = Self::ordered(a, b);
+        Pubkey::find_program_address(
+            &[b"relationship", parties[0].as_ref(), parties[1].as_ref()],
+            &ID,
+        )
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Invitation {
+    pub job_post: Pubkey,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+// Lamports an arbitrator has locked as a bond for good conduct; held by this PDA
+#[account]
+#[derive(InitSpace)]
+pub struct ArbitratorStake {
+    pub arbitrator: Pubkey,
+    pub staked: u64,
+    // Disputes currently assigned; the stake cannot be withdrawn while any remain
+    pub active_disputes: u16,
+    pub slashed_total: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub claimant: Pubkey,
+    pub respondent: Pubkey,
+    pub arbiter: Pubkey,
+    #[max_len(300)]
+    pub reason: String,
+    #[max_len(200)]
+    pub evidence_link: String, // respondent's evidence
+    pub opened_at: i64,
+    pub response_deadline: i64,
+    pub ruling_deadline: i64,
+    pub status: DisputeStatus,
+    pub ruling: Option<DisputeRuling>,
+    pub resolution: Option<DisputeResolution>,
+    // Each party may replace the arbiter once without giving a reason
+    pub claimant_challenged: bool,
+    pub respondent_challenged: bool,
+    // Whether work was awaiting review when the dispute opened; decides a timeout
+    pub work_submitted: bool,
+    // Lamports posted by the claimant, held by this account until settlement
+    pub bond: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum DisputeStatus {
+    AwaitingResponse,
+    AwaitingRuling,
+    Resolved,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum DisputeRuling {
+    ReleaseToFreelancer,
+    RefundClient,
+}
+
+// How a dispute reached its ruling
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum DisputeResolution {
+    Arbiter,
+    Default,
+    Timeout,
+}
+
+// Written once by resolve_dispute and never modified
+#[account]
+#[derive(InitSpace)]
+pub struct Ruling {
+    pub dispute: Pubkey,
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub arbiter: Pubkey,
+    pub ruling: DisputeRuling,
+    pub reason_code: RulingReason,
+    // Hash of the full ruling document stored off-chain
+    pub document_hash: [u8; 32],
+    pub ruled_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum RulingReason {
+    NonDelivery,
+    IncompleteWork,
+    QualityBelowSpec,
+    ScopeDisagreement,
+    NonPayment,
+    Other,
+}
+
+#[derive(Accounts)]
+pub struct InitializeArbitratorRegistry<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + ArbitratorRegistry::INIT_SPACE,
+        seeds = [b"arbitrator_registry"],
+        bump
+    )]
+    pub arbitrator_registry: Account<'info, ArbitratorRegistry>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::LpProgram>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(signer.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::LpProgram>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(signer.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GrantArbitratorRole<'info> {
+    #[account(
+        seeds = [b"arbitrator_registry"],
+        bump = arbitrator_registry.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub arbitrator_registry: Account<'info, ArbitratorRegistry>,
+    #[account(
+        mut,
+        seeds = [b"user", user_account.wallet.as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SlashArbitrator<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = fee_treasury @ ErrorCode::InvalidFeeTreasury
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"arbitrator", arbitrator_stake.arbitrator.as_ref()],
+        bump = arbitrator_stake.bump
+    )]
+    pub arbitrator_stake: Account<'info, ArbitratorStake>,
+    #[account(mut)]
+    /// CHECK: Treasury wallet named in the config
+    pub fee_treasury: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(issuer: Pubkey)]
+pub struct RegisterReputationIssuer<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ReputationIssuer::INIT_SPACE,
+        seeds = [b"reputation_issuer", issuer.as_ref()],
+        bump
+    )]
+    pub issuer_record: Account<'info, ReputationIssuer>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateReputationIssuer<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"reputation_issuer", issuer_record.issuer.as_ref()],
+        bump = issuer_record.bump
+    )]
+    pub issuer_record: Account<'info, ReputationIssuer>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(program: Pubkey)]
+pub struct RegisterVestingProgram<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VestingProgram::INIT_SPACE,
+        seeds = [b"vesting_program", program.as_ref()],
+        bump
+    )]
+    pub vesting_program_record: Account<'info, VestingProgram>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct RegisterPriceFeed<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PriceFeed::INIT_SPACE,
+        seeds = [b"price_feed", mint.as_ref()],
+        bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePrice<'info> {
+    #[account(
+        mut,
+        seeds = [b"price_feed", price_feed.mint.as_ref()],
+        bump = price_feed.bump,
+        has_one = oracle @ ErrorCode::Unauthorized
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    pub oracle: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVestingProgram<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"vesting_program", vesting_program_record.program.as_ref()],
+        bump = vesting_program_record.bump
+    )]
+    pub vesting_program_record: Account<'info, VestingProgram>,
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ActivityFeed {
+    // Index the next entry will be written to once the buffer is full
+    pub head: u16,
+    pub total_events: u64,
+    #[max_len(32)] // ACTIVITY_FEED_CAPACITY
+    pub entries: Vec<ActivityEntry>,
+    pub bump: u8,
+}
+
+impl ActivityFeed {
+    pub fn record(
+        &mut self,
+        kind: ActivityKind,
+        job_post: Pubkey,
+        job_id: u64,
+        amount: u64,
+        timestamp: i64,
+    ) {
+        let entry = ActivityEntry {
+            kind,
+            job_post,
+            job_id,
+            amount,
+            timestamp,
+        };
+        if self.entries.len() < ACTIVITY_FEED_CAPACITY {
+            self.entries.push(entry);
+        } else {
+            self.entries[self.head as usize] = entry;
+        }
+        self.head = ((self.head as usize + 1) % ACTIVITY_FEED_CAPACITY) as u16;
+        self.total_events += 1;
+    }
+}
+
+// A freelancer's most recent payouts and lifetime totals, so clients can check
+// their track record before hiring. Written on every approved submission and
+// every dispute released to the freelancer.
+#[account]
+#[derive(InitSpace)]
+pub struct WorkHistory {
+    pub wallet: Pubkey,
+    // Index the next entry will be written to once the buffer is full
+    pub head: u16,
+    pub total_payouts: u64,
+    pub completed_jobs: u32,
+    // Lamports from lamport-escrowed jobs only, as on Reputation
+    pub total_earned: u64,
+    #[max_len(16)] // WORK_HISTORY_CAPACITY
+    pub entries: Vec<WorkHistoryEntry>,
+    pub bump: u8,
+}
+
+impl WorkHistory {
+    // `completed` marks the payout that finished the freelancer's part of the job
+    pub fn record(
+        &mut self,
+        job_post: Pubkey,
+        mint: Option<Pubkey>,
+        amount: u64,
+        paid_at: i64,
+        completed: bool,
+    ) {
+        let entry = WorkHistoryEntry {
+            job_post,
+            mint,
+            amount,
+            paid_at,
+            completed,
+        };
+        if self.entries.len() < WORK_HISTORY_CAPACITY {
+            self.entries.push(entry);
+        } else {
+            self.entries[self.head as usize] = entry;
+        }
+        self.head = ((self.head as usize + 1) % WORK_HISTORY_CAPACITY) as u16;
+        self.total_payouts += 1;
+        if completed {
+            self.completed_jobs += 1;
+        }
+        if mint.is_none() {
+            self.total_earned = self.total_earned.saturating_add(amount);
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct WorkHistoryEntry {
+    pub job_post: Pubkey,
+    // None for lamport jobs; amounts are in this mint's units otherwise
+    pub mint: Option<Pubkey>,
+    // Net of protocol and arbiter fees
+    pub amount: u64,
+    pub paid_at: i64,
+    pub completed: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct ActivityEntry {
+    pub kind: ActivityKind,
+    pub job_post: Pubkey,
+    pub job_id: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum ActivityKind {
+    JobPosted,
+    JobFilled,
+    JobPaid,
+}
+
+// Marketplace-wide counters for dashboards. Volumes are lamports from
+// lamport-escrowed jobs only, as on Reputation.
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolStats {
+    pub total_users: u64,
+    pub total_jobs: u64,
+    // Lamports clients have deposited into job escrows, fees included
+    pub total_escrowed: u64,
+    // Lamports released to freelancers, net of protocol fees
+    pub total_paid_out: u64,
+    pub total_disputes: u64,
+    pub bump: u8,
+}
+
+impl ProtocolStats {
+    pub fn record_escrow(&mut self, amount: u64) {
+        self.total_escrowed = self.total_escrowed.saturating_add(amount);
+    }
+
+    pub fn record_payout(&mut self, amount: u64) {
+        self.total_paid_out = self.total_paid_out.saturating_add(amount);
+    }
+}
+
+// A client's job posts, in posting order, so frontends can page through them
+// without scanning program accounts. Cancelled posts are dropped.
+#[account]
+#[derive(InitSpace)]
+pub struct JobIndex {
+    pub client: Pubkey,
+    #[max_len(64)] // MAX_INDEXED_JOBS
+    pub jobs: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl JobIndex {
+    pub fn add(&mut self, job_post: Pubkey) -> Result<()> {
+        require!(self.jobs.len() < MAX_INDEXED_JOBS, ErrorCode::JobIndexFull);
+        self.jobs.push(job_post);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, job_post: &Pubkey) {
+        self.jobs.retain(|key| key != job_post);
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct JobCounter {
+    pub next_id: u64,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJobCounter<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + JobCounter::INIT_SPACE,
+        seeds = [b"job_counter"],
+        bump
+    )]
+    pub job_counter: Account<'info, JobCounter>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJobIndex<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + JobIndex::INIT_SPACE,
+        seeds = [b"job_index", signer.key().as_ref()],
+        bump
+    )]
+    pub job_index: Account<'info, JobIndex>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeActivityFeed<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + ActivityFeed::INIT_SPACE,
+        seeds = [b"activity_feed"],
+        bump
+    )]
+    pub activity_feed: Account<'info, ActivityFeed>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolStats<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + ProtocolStats::INIT_SPACE,
+        seeds = [b"protocol_stats"],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, role: UserRole, handle: String)]
+pub struct RegisterUser<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user", signer.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Reputation::INIT_SPACE,
+        seeds = [b"reputation", signer.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + WorkHistory::INIT_SPACE,
+        seeds = [b"work_history", signer.key().as_ref()],
+        bump
+    )]
+    pub work_history: Account<'info, WorkHistory>,
+    // Creation fails if the handle is already taken
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + UsernameRecord::INIT_SPACE,
+        seeds = [b"username", handle.as_bytes()],
+        bump
+    )]
+    pub username_record: Account<'info, UsernameRecord>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUserAccount<'info> {
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"user", signer.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"username", user_account.handle.as_bytes()],
+        bump = username_record.bump
+    )]
+    pub username_record: Account<'info, UsernameRecord>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshReputation<'info> {
+    #[account(
+        mut,
+        seeds = [b"reputation", reputation.wallet.as_ref()],
+        bump = reputation.bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateUserAccount<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", signer.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(referrer: Pubkey)]
+pub struct RegisterReferral<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", signer.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub signer: Signer<'info>,
+    // The referrer must be a registered user
+    #[account(seeds = [b"user", referrer.as_ref()], bump)]
+    pub referrer_account: Account<'info, UserAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAsBeneficiary<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", user_account.wallet.as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeWalletMigration<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", old_user_account.wallet.as_ref()],
+        bump,
+        close = new_wallet,
+        constraint = old_user_account.pending_wallet == Some(new_wallet.key())
+            @ ErrorCode::InvalidWalletMigration
+    )]
+    pub old_user_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        seeds = [b"reputation", old_user_account.wallet.as_ref()],
+        bump = old_reputation.bump,
+        close = new_wallet
+    )]
+    pub old_reputation: Account<'info, Reputation>,
+    #[account(
+        init,
+        payer = new_wallet,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user", new_wallet.key().as_ref()],
+        bump
+    )]
+    pub new_user_account: Account<'info, UserAccount>,
+    #[account(
+        init,
+        payer = new_wallet,
+        space = 8 + Reputation::INIT_SPACE,
+        seeds = [b"reputation", new_wallet.key().as_ref()],
+        bump
+    )]
+    pub new_reputation: Account<'info, Reputation>,
+    #[account(
+        mut,
+        seeds = [b"username", old_user_account.handle.as_bytes()],
+        bump = username_record.bump
+    )]
+    pub username_record: Account<'info, UsernameRecord>,
+    #[account(mut)]
+    pub new_wallet: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateApplication<'info> {
+    #[account(mut)]
+    pub application: Account<'info, Application>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.migrated_from == Some(application.applicant)
+            @ ErrorCode::Unauthorized
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateJobPost<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.migrated_from == Some(job_post.client)
+            @ ErrorCode::Unauthorized
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetWorkCapacity<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", signer.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFreelancerProfile<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + FreelancerProfile::INIT_SPACE,
+        seeds = [b"freelancer_profile", signer.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, FreelancerProfile>,
+    #[account(seeds = [b"user", signer.key().as_ref()], bump)]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFreelancerProfile<'info> {
+    #[account(
+        mut,
+        seeds = [b"freelancer_profile", signer.key().as_ref()],
+        bump = profile.bump
+    )]
+    pub profile: Account<'info, FreelancerProfile>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJobPost<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + JobPost::INIT_SPACE,
+        seeds = [
+            b"job_post",
+            signer.key().as_ref(),
+            &user_account.job_counter.to_le_bytes()
+        ],
+        bump
+    )]
+    pub job_post: Account<'info, JobPost>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump,
+        space = 8 + Escrow::INIT_SPACE
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(mut, seeds = [b"activity_feed"], bump = activity_feed.bump)]
+    pub activity_feed: Account<'info, ActivityFeed>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"job_counter"], bump = job_counter.bump)]
+    pub job_counter: Account<'info, JobCounter>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(
+        mut,
+        seeds = [b"job_index", signer.key().as_ref()],
+        bump = job_index.bump
+    )]
+    pub job_index: Account<'info, JobIndex>,
+}
+
+impl<'info> InitializeJobPost<'info> {
+    // Validates and records the job; the caller funds the escrow with amount
+    // plus the client-side fee
+    #[allow(clippy::too_many_arguments)]
+    fn create(
+        &mut self,
+        escrow_bump: u8,
+        title: String,
+        description: String,
+        amount: u64,
+        start_date: i64,
+        end_date: i64,
+        yield_recipient: YieldRecipient,
+        max_applicants: u32,
+        category: JobCategory,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        validate_job_title(&title)?;
+        validate_job_description(&description)?;
+        validate_tags(&tags, MAX_JOB_TAGS)?;
+        require!(amount > 0, ErrorCode::InvalidJobAmount);
+
+        // Only clients can post jobs
+        require!(
+            self.user_account.has_role(UserRole::Client),
+            ErrorCode::Unauthorized
+        );
+
+        let clock = Clock::get()?;
+        validate_job_dates(start_date, end_date, clock.unix_timestamp)?;
+
+        let job_post = &mut self.job_post;
+        job_post.client = self.user_account.wallet;
+        job_post.title = title;
+        job_post.description = description;
+        job_post.amount = amount;
+        job_post.status = JobStatus::Open;
+        job_post.escrow_bump = escrow_bump;
+        job_post.start_date = start_date;
+        job_post.end_date = end_date;
+        job_post.yield_recipient = yield_recipient;
+        job_post.client_fee = bps_of(amount, self.config.client_fee_bps);
+        job_post.review_window_secs = DEFAULT_REVIEW_WINDOW_SECS;
+        job_post.visibility = JobVisibility::Public;
+        job_post.max_applicants = max_applicants;
+        job_post.category = category;
+        job_post.tags = tags;
+        let escrow = &mut self.escrow;
+        escrow.job_post = job_post.key();
+        escrow.funder = job_post.client;
+        escrow.expected_amount = amount + job_post.client_fee;
+        escrow.mint = None;
+        escrow.status = EscrowStatus::Funded;
+        let user_account = &mut self.user_account;
+        job_post.client_job_index = user_account.job_counter;
+        user_account.job_counter += 1;
+        user_account.open_job_posts += 1;
+        self.job_index.add(job_post.key())?;
+
+        // Short, human-friendly reference for support and invoices
+        let job_counter = &mut self.job_counter;
+        job_post.job_id = job_counter.next_id;
+        job_counter.next_id += 1;
+        self.protocol_stats.total_jobs += 1;
+
+        // Large jobs are released in tranches to limit the impact of any one payout
+        let config = &self.config;
+        job_post.tranche_count =
+            if config.tranche_threshold > 0 && amount > config.tranche_threshold {
+                config.tranche_count
+            } else {
+                1
+            };
+
+        self.activity_feed.record(
+            ActivityKind::JobPosted,
+            job_post.key(),
+            job_post.job_id,
+            amount,
+            clock.unix_timestamp,
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ValidateJobParams<'info> {
+    /// CHECK: Prospective client; only its key and balance are read
+    pub client: UncheckedAccount<'info>,
+    #[account(seeds = [b"user", client.key().as_ref()], bump)]
+    pub client_account: Account<'info, UserAccount>,
+    // The job PDA the posting would create, to check the seed is unused
+    /// CHECK: Compared against the derived address in the handler
+    pub job_post: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+// Outcome of validate_job_params. Each *_error holds the code initialize_job_post
+// would fail with, or None when that check passes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct JobParamsReport {
+    pub title_error: Option<u32>,
+    pub description_error: Option<u32>,
+    pub dates_error: Option<u32>,
+    pub amount_error: Option<u32>,
+    pub job_post_address: Pubkey,
+    pub seed_available: bool,
+    pub client_fee: u64,
+    pub total_deposit: u64,
+    pub tranche_count: u8,
+    // The client's balance covers the deposit, before rent and transaction fees
+    pub can_fund: bool,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJobPostSpl<'info> {
+    pub job: InitializeJobPost<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = job.signer,
+        seeds = [b"escrow_vault", job.job_post.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = job.escrow,
+        token::token_program = token_program,
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = job.signer,
+        token::token_program = token_program,
+    )]
+    pub client_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(client: Pubkey, scope_hash: [u8; 32])]
+pub struct CreateQuote<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Quote::INIT_SPACE,
+        seeds = [b"quote", signer.key().as_ref(), client.as_ref(), scope_hash.as_ref()],
+        bump
+    )]
+    pub quote: Account<'info, Quote>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(freelancer: Pubkey)]
+pub struct CreateRetainer<'info> {
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Retainer::INIT_SPACE,
+        seeds = [b"retainer", client.key().as_ref(), freelancer.as_ref()],
+        bump
+    )]
+    pub retainer: Account<'info, Retainer>,
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"user", client.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(seeds = [b"user", freelancer.as_ref()], bump)]
+    pub freelancer_account: Account<'info, UserAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPeriodPayment<'info> {
+    #[account(
+        mut,
+        has_one = freelancer,
+        seeds = [b"retainer", retainer.client.as_ref(), freelancer.key().as_ref()],
+        bump = retainer.bump
+    )]
+    pub retainer: Account<'info, Retainer>,
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRetainer<'info> {
+    // Closing refunds the unearned periods along with the rent
+    #[account(
+        mut,
+        close = client,
+        has_one = client,
+        has_one = freelancer,
+        seeds = [b"retainer", client.key().as_ref(), freelancer.key().as_ref()],
+        bump = retainer.bump
+    )]
+    pub retainer: Account<'info, Retainer>,
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: Receives elapsed periods; pinned by has_one
+    pub freelancer: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CancelQuote<'info> {
+    #[account(mut, close = freelancer, has_one = freelancer)]
+    pub quote: Account<'info, Quote>,
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptQuote<'info> {
+    pub job: InitializeJobPost<'info>,
+    #[account(
+        mut,
+        close = freelancer,
+        has_one = freelancer,
+        constraint = quote.client == job.signer.key() @ ErrorCode::Unauthorized
+    )]
+    pub quote: Account<'info, Quote>,
+    #[account(
+        init,
+        payer = job.signer,
+        space = 8 + Application::INIT_SPACE,
+        seeds = [b"application", job.job_post.key().as_ref(), freelancer.key().as_ref()],
+        bump
+    )]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    /// CHECK: The quoting freelancer, checked against the quote; receives its rent back
+    pub freelancer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", freelancer.key().as_ref()],
+        bump
+    )]
+    pub freelancer_account: Account<'info, UserAccount>,
+    #[account(mut)]
+    /// CHECK: The client and freelancer's WorkRelationship, created on their first
+    /// hire; address checked in record_relationship
+    pub relationship: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdjustJobBudget<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct EditJobPost<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+// Edits that change the job's text, resizing the account to fit it
+#[derive(Accounts)]
+pub struct EditJobPostText<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> EditJobPostText<'info> {
+    fn fit_text(&self) -> Result<()> {
+        let client = self.client.to_account_info();
+        resize_account(
+            &self.job_post.to_account_info(),
+            self.job_post.space(),
+            &client,
+            &client,
+            &self.system_program,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct BoostJobPost<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReviewWindow<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct PriceCheckedJob<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(constraint = job_post.mint == Some(mint.key()) @ ErrorCode::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(seeds = [b"price_feed", mint.key().as_ref()], bump = price_feed.bump)]
+    pub price_feed: Account<'info, PriceFeed>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> PriceCheckedJob<'info> {
+    fn collateral_value(&self) -> Result<u64> {
+        self.price_feed
+            .usd_value(self.job_post.amount, self.mint.decimals)
+    }
+
+    fn meets_collateral_ratio(&self) -> Result<bool> {
+        let required = u128::from(self.job_post.agreed_usd_value)
+            * u128::from(self.config.collateral_ratio_bps.max(BPS_DENOMINATOR as u16))
+            / u128::from(BPS_DENOMINATOR);
+        Ok(u128::from(self.collateral_value()?) >= required)
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetUsdTerms<'info> {
+    pub job: PriceCheckedJob<'info>,
+    #[account(address = job.job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpCollateral<'info> {
+    pub job: PriceCheckedJob<'info>,
+    #[account(address = job.job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job.job_post.key().as_ref()],
+        bump = job.job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        mut,
+        seeds = [b"escrow_vault", job.job_post.key().as_ref()],
+        bump,
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = job.mint,
+        token::authority = client,
+        token::token_program = token_program,
+    )]
+    pub client_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeSchedule<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Schedule::INIT_SPACE,
+        seeds = [b"schedule", job_post.key().as_ref()],
+        bump
+    )]
+    pub schedule: Account<'info, Schedule>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJobWhitelist<'info> {
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + JobWhitelist::INIT_SPACE,
+        seeds = [b"whitelist", job_post.key().as_ref()],
+        bump
+    )]
+    pub whitelist: Account<'info, JobWhitelist>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateJobWhitelist<'info> {
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"whitelist", job_post.key().as_ref()],
+        bump = whitelist.bump
+    )]
+    pub whitelist: Account<'info, JobWhitelist>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CreateDelegate<'info> {
+    #[account(
+        init,
+        payer = principal,
+        space = 8 + Delegate::INIT_SPACE,
+        seeds = [b"delegate", principal.key().as_ref(), session_key.key().as_ref()],
+        bump
+    )]
+    pub delegate: Account<'info, Delegate>,
+    #[account(mut)]
+    pub principal: Signer<'info>,
+    /// CHECK: The key being authorized; it only ever signs
+    pub session_key: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    #[account(mut, close = principal, has_one = principal)]
+    pub delegate: Account<'info, Delegate>,
+    #[account(mut)]
+    pub principal: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateOrganization<'info> {
+    // One organization per creating wallet
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Organization::INIT_SPACE,
+        seeds = [b"organization", admin.key().as_ref()],
+        bump
+    )]
+    pub organization: Account<'info, Organization>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateOrganization<'info> {
+    #[account(
+        mut,
+        seeds = [b"organization", organization.creator.as_ref()],
+        bump = organization.bump,
+        constraint = organization.is_admin(&admin.key()) @ ErrorCode::NotOrganizationAdmin
+    )]
+    pub organization: Account<'info, Organization>,
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetJobOrganization<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"organization", organization.creator.as_ref()],
+        bump = organization.bump
+    )]
+    pub organization: Option<Account<'info, Organization>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimExpiredEscrow<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", application.applicant.as_ref()],
+        bump
+    )]
+    pub freelancer_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        seeds = [b"user", job_post.client.as_ref()],
+        bump
+    )]
+    pub client_account: Account<'info, UserAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> ReclaimExpiredEscrow<'info> {
+    // Returns whether the job is now closed; a defaulting team member leaves the
+    // rest of the team's engagement running
+    fn mark_defaulted(&mut self) -> Result<bool> {
+        let application = &mut self.application;
+        match application.status {
+            ApplicationStatus::Approved => {}
+            ApplicationStatus::Submitted => return err!(ErrorCode::WorkAlreadySubmitted),
+            ApplicationStatus::Paid => return err!(ErrorCode::AlreadyPaid),
+            ApplicationStatus::Disputed => return err!(ErrorCode::ApplicationDisputed),
+            _ => return err!(ErrorCode::ApplicationNotApproved),
+        }
+        require!(!application.defaulted, ErrorCode::ApplicationDefaulted);
+        let deadline = self.job_post.end_date + self.config.delivery_grace_secs;
+        require!(
+            Clock::get()?.unix_timestamp > deadline,
+            ErrorCode::DeliveryDeadlineNotPassed
+        );
+
+        application.defaulted = true;
+        pay_out_bond(application, &self.client.to_account_info())?;
+        let freelancer_account = &mut self.freelancer_account;
+        freelancer_account.active_jobs = freelancer_account.active_jobs.saturating_sub(1);
+        freelancer_account.pending_applications =
+            freelancer_account.pending_applications.saturating_sub(1);
+        if !self.job_post.settle_hire() {
+            return Ok(false);
+        }
+        self.job_post.transition(JobStatus::Cancelled)?;
+        self.escrow.status = EscrowStatus::Refunded;
+        let client_account = &mut self.client_account;
+        client_account.open_job_posts = client_account.open_job_posts.saturating_sub(1);
+        Ok(true)
+    }
+}
+
+#[derive(Accounts)]
+pub struct ReclaimExpiredEscrowSpl<'info> {
+    pub base: ReclaimExpiredEscrow<'info>,
+    #[account(constraint = base.job_post.mint == Some(mint.key()) @ ErrorCode::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"escrow_vault", base.job_post.key().as_ref()],
+        bump,
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = base.client,
+        token::token_program = token_program,
+    )]
+    pub client_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelJobPost<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", job_post.client.as_ref()],
+        bump
+    )]
+    pub client_account: Account<'info, UserAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"job_index", job_post.client.as_ref()],
+        bump = job_index.bump
+    )]
+    pub job_index: Account<'info, JobIndex>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCompletedJob<'info> {
+    #[account(mut, close = client)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump
+    )]
+    /// CHECK: Escrow, swept to the client; already closed if the final payout
+    /// left nothing in it
+    pub escrow: UncheckedAccount<'info>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"job_index", job_post.client.as_ref()],
+        bump = job_index.bump
+    )]
+    pub job_index: Account<'info, JobIndex>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    // Required for token jobs whose vault is still open
+    #[account(
+        mut,
+        seeds = [b"escrow_vault", job_post.key().as_ref()],
+        bump,
+    )]
+    pub escrow_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePaidApplication<'info> {
+    #[account(mut, close = freelancer, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CancelJobPostSpl<'info> {
+    pub job: CancelJobPost<'info>,
+    #[account(constraint = job.job_post.mint == Some(mint.key()) @ ErrorCode::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"escrow_vault", job.job_post.key().as_ref()],
+        bump,
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = job.client,
+        token::token_program = token_program,
+    )]
+    pub client_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJobPostLarge<'info> {
+    #[account(
+        init,
+        payer = client,
+        space = 8 + std::mem::size_of::<JobPostLarge>(),
+        seeds = [b"job_post_large", job_post.key().as_ref()],
+        bump
+    )]
+    pub job_post_large: AccountLoader<'info, JobPostLarge>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WriteJobPostLarge<'info> {
+    #[account(
+        mut,
+        seeds = [b"job_post_large", job_post.key().as_ref()],
+        bump
+    )]
+    pub job_post_large: AccountLoader<'info, JobPostLarge>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeJobExtension<'info> {
+    #[account(
+        init,
+        payer = client,
+        space = 8 + JobExtension::INIT_SPACE,
+        seeds = [b"job_extension", job_post.key().as_ref()],
+        bump
+    )]
+    pub extension: Account<'info, JobExtension>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateJobExtension<'info> {
+    #[account(
+        mut,
+        has_one = job_post,
+        seeds = [b"job_extension", job_post.key().as_ref()],
+        bump = extension.bump
+    )]
+    pub extension: Account<'info, JobExtension>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeApplicationExtension<'info> {
+    #[account(
+        init,
+        payer = applicant,
+        space = 8 + ApplicationExtension::INIT_SPACE,
+        seeds = [b"application_extension", application.key().as_ref()],
+        bump
+    )]
+    pub extension: Account<'info, ApplicationExtension>,
+    #[account(has_one = applicant @ ErrorCode::Unauthorized)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub applicant: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateApplicationExtension<'info> {
+    #[account(
+        mut,
+        has_one = application,
+        seeds = [b"application_extension", application.key().as_ref()],
+        bump = extension.bump
+    )]
+    pub extension: Account<'info, ApplicationExtension>,
+    #[account(has_one = applicant @ ErrorCode::Unauthorized)]
+    pub application: Account<'info, Application>,
+    pub applicant: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyToJob<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Application::INIT_SPACE,
+        seeds = [b"application", job_post.key().as_ref(), signer.key().as_ref()],
+        bump
+    )]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    // Required when the job is private
+    #[account(seeds = [b"whitelist", job_post.key().as_ref()], bump = whitelist.bump)]
+    pub whitelist: Option<Account<'info, JobWhitelist>>,
+    // Freelancers without a profile are treated as available
+    #[account(
+        seeds = [b"freelancer_profile", signer.key().as_ref()],
+        bump = freelancer_profile.bump
+    )]
+    pub freelancer_profile: Option<Account<'info, FreelancerProfile>>,
+    // Required when the job charges an application fee
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + ApplicationDeposit::INIT_SPACE,
+        seeds = [b"application_deposit", application.key().as_ref()],
+        bump
+    )]
+    pub deposit: Option<Account<'info, ApplicationDeposit>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawApplication<'info> {
+    #[account(
+        mut,
+        has_one = job_post,
+        constraint = application.applicant == signer.key() @ ErrorCode::Unauthorized,
+        close = signer
+    )]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(mut, seeds = [b"user", signer.key().as_ref()], bump)]
+    pub user_account: Account<'info, UserAccount>,
+    // Required when the application paid a fee
+    #[account(
+        mut,
+        close = signer,
+        has_one = application,
+        seeds = [b"application_deposit", application.key().as_ref()],
+        bump = deposit.bump
+    )]
+    pub deposit: Option<Account<'info, ApplicationDeposit>>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    /// CHECK: Receives the fee of an application withdrawn after the start date
+    pub client: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct PostCompletionBond<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleApplicationFee<'info> {
+    #[account(
+        mut,
+        close = applicant,
+        has_one = application,
+        seeds = [b"application_deposit", application.key().as_ref()],
+        bump = deposit.bump
+    )]
+    pub deposit: Account<'info, ApplicationDeposit>,
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = application.applicant @ ErrorCode::Unauthorized)]
+    /// CHECK: Receives the deposit's rent, and the fee unless it is forfeited
+    pub applicant: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetReferrer<'info> {
+    #[account(
+        mut,
+        has_one = job_post,
+        constraint = application.applicant == signer.key() @ ErrorCode::Unauthorized
+    )]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    // Only registered users can be named as referrers
+    #[account(seeds = [b"user", referrer_account.wallet.as_ref()], bump)]
+    pub referrer_account: Account<'info, UserAccount>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayoutVesting<'info> {
+    #[account(
+        mut,
+        has_one = job_post,
+        constraint = application.applicant == signer.key() @ ErrorCode::Unauthorized
+    )]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    // Omit to clear the vesting destination
+    #[account(
+        seeds = [b"vesting_program", vesting_program_record.program.as_ref()],
+        bump = vesting_program_record.bump
+    )]
+    pub vesting_program_record: Option<Account<'info, VestingProgram>>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayoutSplits<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SetApplicationMetadata<'info> {
+    #[account(mut)]
+    pub application: Account<'info, Application>,
+    #[account(address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyJobMetadata<'info> {
+    pub job_post: Account<'info, JobPost>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyApplicationMetadata<'info> {
+    pub application: Account<'info, Application>,
+}
+
+#[derive(Accounts)]
+pub struct FundReferralBounty<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveApplication<'info> {
+    #[account(mut, has_one = job_post @ ErrorCode::ApplicationJobMismatch)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // The principal's account when a delegate signs
+    #[account(
+        seeds = [b"user", acting_wallet(&signer, &delegate).as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        seeds = [b"user", application.applicant.as_ref()],
+        bump
+    )]
+    pub freelancer_account: Account<'info, UserAccount>,
+    #[account(mut, seeds = [b"activity_feed"], bump = activity_feed.bump)]
+    pub activity_feed: Account<'info, ActivityFeed>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    // Lets the organization's admins hire on organization jobs
+    #[account(
+        seeds = [b"organization", organization.creator.as_ref()],
+        bump = organization.bump
+    )]
+    pub organization: Option<Account<'info, Organization>>,
+    // Escrow and freelancer are only needed when the job pays upfront
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = is_job_client(&job_post, organization.as_ref(), &acting_wallet(&signer, &delegate)) @ ErrorCode::Unauthorized,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Option<Account<'info, Escrow>>,
+    #[account(mut, address = application.applicant)]
+    /// CHECK: Receives the upfront payment
+    pub freelancer: Option<UncheckedAccount<'info>>,
+    pub payout: PayoutRouting<'info>,
+    #[account(mut)]
+    /// CHECK: The client and freelancer's WorkRelationship, created on their first
+    /// hire; address checked in record_relationship
+    pub relationship: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    // Present when a session key hires for the client; jobs that pay upfront
+    // still need the client's own signature
+    #[account(
+        seeds = [b"delegate", delegate.principal.as_ref(), signer.key().as_ref()],
+        bump = delegate.bump
+    )]
+    pub delegate: Option<Account<'info, Delegate>>,
+}
+
+impl<'info> ApproveApplication<'info> {
+    // split_recipients: the wallet of every payout split recipient, when the job
+    // pays upfront
+    fn hire(&mut self, split_recipients: &[AccountInfo<'info>]) -> Result<()> {
+        if let Some(delegate) = &self.delegate {
+            delegate.authorize(DELEGATE_APPROVE_APPLICATION)?;
+            require!(
+                self.job_post.upfront_bps == 0,
+                ErrorCode::DelegateCannotMoveFunds
+            );
+        }
+        require!(
+            self.job_post.client == self.user_account.wallet
+                || is_job_client(
+                    &self.job_post,
+                    self.organization.as_ref(),
+                    &acting_wallet(&self.signer, &self.delegate)
+                ),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            self.user_account.has_role(UserRole::Client),
+            ErrorCode::Unauthorized
+        );
+        let team = self.job_post.is_team();
+        if team {
+            self.job_post.require_team_slot()?;
+        } else {
+            self.job_post.require_open()?;
+        }
+        require!(
+            self.job_post.collateral_call_at == 0,
+            ErrorCode::CollateralCallOutstanding
+        );
+        require!(
+            self.application.stage != ApplicationStage::Rejected,
+            ErrorCode::InvalidStageTransition
+        );
+        self.application.require_bond(&self.job_post)?;
+        record_relationship(
+            &self.relationship,
+            &self.signer.to_account_info(),
+            &self.system_program,
+            self.job_post.client,
+            self.application.applicant,
+        )?;
+
+        // Freelancers can cap how many in-progress jobs they hold at once
+        self.freelancer_account.reserve_job_slot()?;
+
+        let application = &mut self.application;
+        application.set_status(ApplicationStatus::Approved)?;
+        let previous_stage = application.stage;
+        application.stage = ApplicationStage::Hired;
+        if team {
+            application.share_bps = self.job_post.take_team_share();
+        }
+        // Later team hires join a job that is already under way
+        if self.job_post.status == JobStatus::Open {
+            self.job_post.transition(JobStatus::InProgress)?;
+        }
+        release_upfront(
+            &self.job_post,
+            application,
+            self.escrow.as_ref().map(|escrow| escrow.as_ref()),
+            self.freelancer
+                .as_ref()
+                .map(|freelancer| freelancer.as_ref()),
+            &self.freelancer_account,
+            &self.payout,
+            split_recipients,
+            &mut self.config,
+            &mut self.protocol_stats,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        emit!(ApplicationStageChanged {
+            application: application.key(),
+            job_post: application.job_post,
+            job_id: self.job_post.job_id,
+            from: previous_stage,
+            to: ApplicationStage::Hired,
+            timestamp: now,
+        });
+
+        self.activity_feed.record(
+            ActivityKind::JobFilled,
+            self.job_post.key(),
+            self.job_post.job_id,
+            self.job_post.amount,
+            now,
+        );
+
+        emit!(ApplicationApproved {
+            application: self.application.key(),
+            job_post: self.job_post.key(),
+            freelancer: self.application.applicant,
+            amount: self.job_post.amount,
+            timestamp: now,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ProposeCounterOffer<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptCounterOffer<'info> {
+    pub hire: ApproveApplication<'info>,
+    // The escrow moves funds, so the application and the paying signer are pinned
+    // to this job here
+    #[account(
+        mut,
+        seeds = [b"escrow", hire.job_post.key().as_ref()],
+        bump = hire.job_post.escrow_bump,
+        constraint = hire.signer.key() == hire.job_post.client @ ErrorCode::Unauthorized,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetApplicationStage<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct MakeOffer<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(mut, seeds = [b"activity_feed"], bump = activity_feed.bump)]
+    pub activity_feed: Account<'info, ActivityFeed>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    // Only needed when the job pays upfront
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Option<Account<'info, Escrow>>,
+    pub payout: PayoutRouting<'info>,
+    #[account(mut)]
+    /// CHECK: The client and freelancer's WorkRelationship, created on their first
+    /// hire; address checked in record_relationship
+    pub relationship: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireOffer<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct InviteMany<'info> {
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(freelancer: Pubkey)]
+pub struct InviteFreelancer<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Invitation::INIT_SPACE,
+        seeds = [b"invitation", job_post.key().as_ref(), freelancer.as_ref()],
+        bump
+    )]
+    pub invitation: Account<'info, Invitation>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(seeds = [b"user", freelancer.as_ref()], bump)]
+    pub freelancer_account: Account<'info, UserAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptInvitation<'info> {
+    #[account(
+        mut,
+        close = client,
+        has_one = job_post,
+        has_one = client,
+        has_one = freelancer
+    )]
+    pub invitation: Account<'info, Invitation>,
+    #[account(
+        init,
+        payer = freelancer,
+        space = 8 + Application::INIT_SPACE,
+        seeds = [b"application", job_post.key().as_ref(), freelancer.key().as_ref()],
+        bump
+    )]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut)]
+    /// CHECK: Receives the invitation's rent; pinned by has_one
+    pub client: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", freelancer.key().as_ref()],
+        bump,
+        constraint = freelancer_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub freelancer_account: Account<'info, UserAccount>,
+    #[account(mut, seeds = [b"activity_feed"], bump = activity_feed.bump)]
+    pub activity_feed: Account<'info, ActivityFeed>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+    // Only needed when the job pays upfront
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Option<Account<'info, Escrow>>,
+    pub payout: PayoutRouting<'info>,
+    #[account(mut)]
+    /// CHECK: The client and freelancer's WorkRelationship, created on their first
+    /// hire; address checked in record_relationship
+    pub relationship: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct IssueCredential<'info> {
+    #[account(
+        mut,
+        seeds = [b"reputation", signer.key().as_ref()],
+        bump = reputation.bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + ReputationCredential::INIT_SPACE,
+        seeds = [b"credential", signer.key().as_ref()],
+        bump
+    )]
+    pub credential: Account<'info, ReputationCredential>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncCredential<'info> {
+    #[account(
+        mut,
+        seeds = [b"reputation", credential.wallet.as_ref()],
+        bump = reputation.bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+    #[account(
+        mut,
+        seeds = [b"credential", credential.wallet.as_ref()],
+        bump = credential.bump
+    )]
+    pub credential: Account<'info, ReputationCredential>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct SnapshotReputation<'info> {
+    #[account(
+        mut,
+        seeds = [b"reputation", signer.key().as_ref()],
+        bump = reputation.bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + ReputationSnapshot::INIT_SPACE,
+        seeds = [
+            b"reputation_snapshot",
+            signer.key().as_ref(),
+            &reputation.snapshot_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub snapshot: Account<'info, ReputationSnapshot>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttestReputation<'info> {
+    #[account(
+        seeds = [b"reputation_issuer", signer.key().as_ref()],
+        bump = issuer_record.bump
+    )]
+    pub issuer_record: Account<'info, ReputationIssuer>,
+    #[account(
+        mut,
+        seeds = [b"reputation", reputation.wallet.as_ref()],
+        bump = reputation.bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + ReputationAttestation::INIT_SPACE,
+        seeds = [
+            b"reputation_attestation",
+            signer.key().as_ref(),
+            reputation.wallet.as_ref()
+        ],
+        bump
+    )]
+    pub attestation: Account<'info, ReputationAttestation>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshAttestation<'info> {
+    #[account(
+        seeds = [b"reputation_issuer", attestation.issuer.as_ref()],
+        bump = issuer_record.bump
+    )]
+    pub issuer_record: Account<'info, ReputationIssuer>,
+    #[account(
+        mut,
+        seeds = [b"reputation", attestation.subject.as_ref()],
+        bump = reputation.bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+    #[account(mut)]
+    pub attestation: Account<'info, ReputationAttestation>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> RefreshAttestation<'info> {
+    fn reweigh(&mut self) -> Result<()> {
+        let weight_bps = self.issuer_record.effective_weight_bps();
+        self.attestation.reweigh(&mut self.reputation, weight_bps);
+
+        msg!(
+            "Attestation weighted at {} bps: {} of {}",
+            weight_bps,
+            self.attestation.weighted_score,
+            self.attestation.score
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateAttestation<'info> {
+    pub refresh: RefreshAttestation<'info>,
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitWork<'info> {
+    #[account(mut, has_one = job_post @ ErrorCode::ApplicationJobMismatch)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundTrial<'info> {
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Trial::INIT_SPACE,
+        seeds = [b"trial", application.key().as_ref()],
+        bump
+    )]
+    pub trial: Account<'info, Trial>,
+    #[account(has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitTrial<'info> {
+    #[account(
+        mut,
+        has_one = application,
+        seeds = [b"trial", application.key().as_ref()],
+        bump = trial.bump
+    )]
+    pub trial: Account<'info, Trial>,
+    pub application: Account<'info, Application>,
+    #[account(address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveTrial<'info> {
+    // Closed to the client for its rent once the payment is out
+    #[account(
+        mut,
+        close = client,
+        has_one = application,
+        seeds = [b"trial", application.key().as_ref()],
+        bump = trial.bump
+    )]
+    pub trial: Account<'info, Trial>,
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    // Refunds the trial amount on conversion
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(mut, address = application.applicant @ ErrorCode::Unauthorized)]
+    /// CHECK: Freelancer
+    pub freelancer: AccountInfo<'info>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    /// CHECK: Client
+    pub client: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", application.applicant.as_ref()],
+        bump
+    )]
+    pub freelancer_account: Account<'info, UserAccount>,
+    pub payout: PayoutRouting<'info>,
+    #[account(mut, seeds = [b"activity_feed"], bump = activity_feed.bump)]
+    pub activity_feed: Account<'info, ActivityFeed>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    /// CHECK: The client and freelancer's WorkRelationship, created on their first
+    /// hire; address checked in record_relationship
+    pub relationship: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTrial<'info> {
+    // Closing returns the escrowed trial amount along with the rent
+    #[account(
+        mut,
+        close = client,
+        has_one = application,
+        seeds = [b"trial", application.key().as_ref()],
+        bump = trial.bump
+    )]
+    pub trial: Account<'info, Trial>,
+    #[account(has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeWorkSessionLog<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + WorkSessionLog::INIT_SPACE,
+        seeds = [b"work_sessions", application.key().as_ref()],
+        bump
+    )]
+    pub log: Account<'info, WorkSessionLog>,
+    #[account(has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTimeLog<'info> {
+    #[account(
+        init,
+        payer = freelancer,
+        space = 8 + TimeLog::INIT_SPACE,
+        seeds = [b"time_log", application.key().as_ref()],
+        bump
+    )]
+    pub log: Account<'info, TimeLog>,
+    #[account(has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LogTime<'info> {
+    #[account(
+        mut,
+        has_one = application,
+        seeds = [b"time_log", application.key().as_ref()],
+        bump = log.bump
+    )]
+    pub log: Account<'info, TimeLog>,
+    #[account(has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveTime<'info> {
+    #[account(
+        mut,
+        has_one = application,
+        seeds = [b"time_log", application.key().as_ref()],
+        bump = log.bump
+    )]
+    pub log: Account<'info, TimeLog>,
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = application.applicant)]
+    /// CHECK: Freelancer
+    pub freelancer: AccountInfo<'info>,
+    #[account(seeds = [b"user", application.applicant.as_ref()], bump)]
+    pub freelancer_account: Account<'info, UserAccount>,
+    pub payout: PayoutRouting<'info>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(seeds = [b"user", freelancer.key().as_ref()], bump)]
+    pub freelancer_account: Account<'info, UserAccount>,
+    pub payout: PayoutRouting<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct IssueCheckInNonce<'info> {
+    #[account(
+        mut,
+        seeds = [b"work_sessions", application.key().as_ref()],
+        bump = log.bump
+    )]
+    pub log: Account<'info, WorkSessionLog>,
+    #[account(has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CheckIn<'info> {
+    #[account(
+        mut,
+        seeds = [b"work_sessions", application.key().as_ref()],
+        bump = log.bump
+    )]
+    pub log: Account<'info, WorkSessionLog>,
+    #[account(constraint = application.applicant == signer.key() @ ErrorCode::Unauthorized)]
+    pub application: Account<'info, Application>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct AddMilestone<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Milestone::INIT_SPACE,
+        seeds = [b"milestone", job_post.key().as_ref(), &[job_post.milestone_count]],
+        bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveMilestone<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, has_one = job_post)]
+    pub milestone: Account<'info, Milestone>,
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = application.applicant)]
+    /// CHECK: Freelancer
+    pub freelancer: AccountInfo<'info>,
+    #[account(seeds = [b"user", application.applicant.as_ref()], bump)]
+    pub freelancer_account: Account<'info, UserAccount>,
+    pub payout: PayoutRouting<'info>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(seeds = [b"user", job_post.client.as_ref()], bump)]
+    pub client_account: Account<'info, UserAccount>,
+    // Required while the client's referrer is owed a share of the fees
+    #[account(
+        mut,
+        constraint = client_account.referred_by == Some(client_referrer.key()) @ ErrorCode::Unauthorized
+    )]
+    /// CHECK: Receives the client's referral share; pinned to referred_by
+    pub client_referrer: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct ReviewClient<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"reputation", job_post.client.as_ref()],
+        bump = client_reputation.bump
+    )]
+    pub client_reputation: Account<'info, Reputation>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct MintCompletionCertificate<'info> {
+    #[account(has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    // One certificate per application
+    #[account(
+        init,
+        payer = freelancer,
+        seeds = [b"certificate", application.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = certificate_authority,
+        mint::freeze_authority = certificate_authority,
+    )]
+    pub certificate_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = freelancer,
+        associated_token::mint = certificate_mint,
+        associated_token::authority = freelancer,
+    )]
+    pub freelancer_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(seeds = [b"certificate_authority"], bump)]
+    /// CHECK: Signing PDA; mint, update authority and verified creator of every certificate
+    pub certificate_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), certificate_mint.key().as_ref()],
+        bump,
+        seeds::program = TOKEN_METADATA_PROGRAM_ID
+    )]
+    /// CHECK: Created by the Token Metadata program
+    pub metadata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            certificate_mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = TOKEN_METADATA_PROGRAM_ID
+    )]
+    /// CHECK: Created by the Token Metadata program
+    pub master_edition: UncheckedAccount<'info>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> MintCompletionCertificate<'info> {
+    // On-chain metadata: the name carries the job title and the amount paid in
+    // the job currency's base units, and the client is listed as an unverified
+    // creator. The uri lets off-chain viewers render the same details.
+    fn certificate_data(&self) -> DataV2 {
+        let job_post = &self.job_post;
+        let amount = self.application.released_amount;
+        DataV2 {
+            name: certificate_name(&job_post.title, amount),
+            symbol: CERTIFICATE_SYMBOL.to_string(),
+            uri: format!(
+                "{}?job={}&client={}&amount={}",
+                self.config.certificate_base_uri,
+                job_post.key(),
+                job_post.client,
+                amount
+            ),
+            // No royalties
+            seller_fee_basis_points: 0,
+            creators: Some(vec![
+                Creator {
+                    address: self.certificate_authority.key(),
+                    verified: true,
+                    share: 0,
+                },
+                Creator {
+                    address: job_post.client,
+                    verified: false,
+                    share: 100,
+                },
+            ]),
+            collection: None,
+            uses: None,
+        }
+    }
+
+    // The metadata is immutable once created
+    fn create_metadata(&self, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let authority = self.certificate_authority.to_account_info();
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: self.metadata.to_account_info(),
+                mint: self.certificate_mint.to_account_info(),
+                mint_authority: authority.clone(),
+                payer: self.freelancer.to_account_info(),
+                update_authority: authority,
+                system_program: self.system_program.to_account_info(),
+                rent: self.rent.to_account_info(),
+            },
+            signer_seeds,
+        );
+        metadata::create_metadata_accounts_v3(
+            cpi_context,
+            self.certificate_data(),
+            false,
+            true,
+            None,
+        )
+    }
+
+    // A max supply of 0 hands the mint authority to the edition and fixes the
+    // supply at one
+    fn create_master_edition(&self, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let authority = self.certificate_authority.to_account_info();
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_metadata_program.to_account_info(),
+            CreateMasterEditionV3 {
+                edition: self.master_edition.to_account_info(),
+                mint: self.certificate_mint.to_account_info(),
+                update_authority: authority.clone(),
+                mint_authority: authority,
+                payer: self.freelancer.to_account_info(),
+                metadata: self.metadata.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                rent: self.rent.to_account_info(),
+            },
+            signer_seeds,
+        );
+        metadata::create_master_edition_v3(cpi_context, Some(0))
+    }
+}
+
+#[derive(Accounts)]
+pub struct TipFreelancer<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(mut, address = application.applicant)]
+    /// CHECK: Receives the tip
+    pub freelancer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"reputation", application.applicant.as_ref()],
+        bump = freelancer_reputation.bump
+    )]
+    pub freelancer_reputation: Account<'info, Reputation>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TipFreelancer<'info> {
+    fn require_tippable(&self, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidTip);
+        require!(
+            self.application.status == ApplicationStatus::Paid,
+            ErrorCode::ApplicationNotPaid
+        );
+        Ok(())
+    }
+
+    fn record_tip(&mut self, amount: u64) -> Result<()> {
+        let application = &mut self.application;
+        application.tips_received += amount;
+
+        emit!(FreelancerTipped {
+            application: application.key(),
+            job_post: application.job_post,
+            freelancer: application.applicant,
+            mint: self.job_post.mint,
+            amount,
+            tips_received: application.tips_received,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        msg!(
+            "Tipped {}, {} in tips so far",
+            amount,
+            application.tips_received
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct TipFreelancerSpl<'info> {
+    pub base: TipFreelancer<'info>,
+    #[account(constraint = base.job_post.mint == Some(mint.key()) @ ErrorCode::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = base.client,
+        token::token_program = token_program,
+    )]
+    pub client_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = base.application.applicant,
+        token::token_program = token_program,
+    )]
+    pub freelancer_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RequestExtension<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = application.applicant @ ErrorCode::Unauthorized)]
+    pub freelancer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveExtension<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRevision<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    // The client, or a key the client delegated revision requests to
+    #[account(
+        constraint = acting_wallet(&signer, &delegate) == job_post.client @ ErrorCode::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"delegate", delegate.principal.as_ref(), signer.key().as_ref()],
+        bump = delegate.bump
+    )]
+    pub delegate: Option<Account<'info, Delegate>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+// Routes a freelancer's lamport earnings: the referrer's share of the
+// freelancer fee, and the vesting program the freelancer locks payouts in.
+// Nested by every instruction that pays a freelancer out of escrow, and checked
+// against the freelancer's accounts in payee.
+#[derive(Accounts)]
+pub struct PayoutRouting<'info> {
+    // Required while the freelancer's referrer is owed a share of the fees
+    #[account(mut)]
+    /// CHECK: Receives the freelancer's referral share; checked against referred_by in payee
+    pub freelancer_referrer: Option<UncheckedAccount<'info>>,
+    // Required when the freelancer routes payouts into a vesting program
+    #[account(
+        seeds = [b"vesting_program", vesting_program_record.program.as_ref()],
+        bump = vesting_program_record.bump
+    )]
+    pub vesting_program_record: Option<Account<'info, VestingProgram>>,
+    #[account(mut)]
+    /// CHECK: Must be owned by the vesting program, checked in vesting_target
+    pub vesting_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: Must match the application's vesting program, checked in vesting_target
+    pub vesting_program: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> PayoutRouting<'info> {
+    // Where `application`'s payouts go; `split_recipients` are the wallets of
+    // its payout splits, passed as remaining accounts
+    fn payee<'a>(
+        &self,
+        application: &Application,
+        freelancer_account: &UserAccount,
+        freelancer: AccountInfo<'info>,
+        split_recipients: &'a [AccountInfo<'info>],
+    ) -> Result<FreelancerPayee<'a, 'info>> {
+        if let Some(referrer) = &self.freelancer_referrer {
+            require!(
+                freelancer_account.referred_by == Some(referrer.key()),
+                ErrorCode::Unauthorized
+            );
+        }
+        Ok(FreelancerPayee {
+            freelancer,
+            referrer: self.freelancer_referrer.clone(),
+            vesting: self.vesting_target(application)?,
+            splits: application.payout_splits.clone(),
+            split_recipients,
+        })
+    }
+
+    // A program that has since been deactivated falls back to the wallet
+    // instead of blocking payout
+    fn vesting_target(&self, application: &Application) -> Result<Option<VestingTarget<'info>>> {
+        let Some(program_id) = application.payout_vesting else {
+            return Ok(None);
+        };
+        let record = self
+            .vesting_program_record
+            .as_ref()
+            .filter(|record| record.program == program_id)
+            .ok_or(ErrorCode::VestingAccountsRequired)?;
+        if !record.active {
+            return Ok(None);
+        }
+        let program = self
+            .vesting_program
+            .as_ref()
+            .filter(|program| program.key() == program_id && program.executable)
+            .ok_or(ErrorCode::VestingAccountsRequired)?;
+        let account = self
+            .vesting_account
+            .as_ref()
+            .ok_or(ErrorCode::VestingAccountsRequired)?;
+        require!(
+            *account.owner == program_id,
+            ErrorCode::InvalidVestingAccount
+        );
+        Ok(Some(VestingTarget {
+            program_id,
+            deposit_discriminator: record.deposit_discriminator,
+            program: program.to_account_info(),
+            account: account.to_account_info(),
+        }))
+    }
+}
+
+#[derive(Accounts)]
+pub struct ApproveSubmission<'info> {
+    #[account(mut, has_one = job_post @ ErrorCode::ApplicationJobMismatch)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(seeds = [b"user", signer.key().as_ref()], bump)]
+    pub user_account: Account<'info, UserAccount>,
+    // Payouts can be cranked by anyone, so the recipient must be pinned
+    #[account(mut, address = application.applicant @ ErrorCode::PayeeMismatch)]
+    /// CHECK: Freelancer
+    pub freelancer: AccountInfo<'info>,
+    #[account(mut, address = job_post.client)]
+    /// CHECK: Client, receives yield rebates
+    pub client: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", application.applicant.as_ref()],
+        bump
+    )]
+    pub freelancer_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        seeds = [b"user", job_post.client.as_ref()],
+        bump
+    )]
+    pub client_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        seeds = [b"reputation", application.applicant.as_ref()],
+        bump = freelancer_reputation.bump
+    )]
+    pub freelancer_reputation: Account<'info, Reputation>,
+    #[account(
+        mut,
+        seeds = [b"reputation", job_post.client.as_ref()],
+        bump = client_reputation.bump
+    )]
+    pub client_reputation: Account<'info, Reputation>,
+    #[account(
+        mut,
+        seeds = [b"work_history", application.applicant.as_ref()],
+        bump = freelancer_work_history.bump
+    )]
+    pub freelancer_work_history: Account<'info, WorkHistory>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"activity_feed"], bump = activity_feed.bump)]
+    pub activity_feed: Account<'info, ActivityFeed>,
+    pub payout: PayoutRouting<'info>,
+    // Required once the job has a payment schedule
+    #[account(
+        mut,
+        seeds = [b"schedule", job_post.key().as_ref()],
+        bump = schedule.bump
+    )]
+    pub schedule: Option<Account<'info, Schedule>>,
+    pub system_program: Program<'info, System>,
+    // Lets the organization's admins approve work on organization jobs
+    #[account(
+        seeds = [b"organization", organization.creator.as_ref()],
+        bump = organization.bump
+    )]
+    pub organization: Option<Account<'info, Organization>>,
+    // Required while the client's referrer is owed a share of the fees
+    #[account(
+        mut,
+        constraint = client_account.referred_by == Some(client_referrer.key()) @ ErrorCode::Unauthorized
+    )]
+    /// CHECK: Receives the client's referral share; pinned to referred_by
+    pub client_referrer: Option<UncheckedAccount<'info>>,
+    // Kept in step with the freelancer's reputation when they have one
+    #[account(
+        mut,
+        seeds = [b"credential", application.applicant.as_ref()],
+        bump = freelancer_credential.bump
+    )]
+    pub freelancer_credential: Option<Account<'info, ReputationCredential>>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+impl<'info> ApproveSubmission<'info> {
+    fn authorize_client(&self) -> Result<()> {
+        require!(
+            self.job_post.client == self.user_account.wallet
+                || is_job_client(
+                    &self.job_post,
+                    self.organization.as_ref(),
+                    &self.signer.key()
+                ),
+            ErrorCode::Unauthorized
+        );
+        require!(
+            self.user_account.has_role(UserRole::Client),
+            ErrorCode::Unauthorized
+        );
+        Ok(())
+    }
+
+    // Jobs without an approver set are approved by the client alone. Otherwise
+    // each listed approver signs separately, and this reports whether the
+    // threshold has now been reached.
+    fn collect_approval(&mut self) -> Result<bool> {
+        if self.job_post.approvers.is_empty() {
+            self.authorize_client()?;
+            return Ok(true);
+        }
+        let approver = self.signer.key();
+        require!(
+            self.job_post.approvers.contains(&approver),
+            ErrorCode::NotAnApprover
+        );
+        require!(
+            self.application.status == ApplicationStatus::Submitted,
+            ErrorCode::WorkNotCompleted
+        );
+        let approvals = &mut self.application.approvals;
+        require!(!approvals.contains(&approver), ErrorCode::DuplicateApproval);
+        approvals.push(approver);
+        let reached = approvals.len() >= usize::from(self.job_post.approval_threshold);
+
+        emit!(SubmissionApprovalRecorded {
+            application: self.application.key(),
+            approver,
+            approvals: self.application.approvals.len() as u8,
+            threshold: self.job_post.approval_threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        if reached {
+            self.application.approvals.clear();
+        }
+        Ok(reached)
+    }
+
+    fn require_review_window_elapsed(&self) -> Result<()> {
+        require!(
+            self.application.status == ApplicationStatus::Submitted,
+            ErrorCode::WorkNotCompleted
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= self.application.submitted_at + self.job_post.review_window_secs,
+            ErrorCode::ReviewWindowOpen
+        );
+        Ok(())
+    }
+
+    fn release_lamports(&mut self, split_recipients: &[AccountInfo<'info>]) -> Result<()> {
+        let escrow = self.escrow.to_account_info();
+        let freelancer = self.payout.payee(
+            &self.application,
+            &self.freelancer_account,
+            self.freelancer.to_account_info(),
+            split_recipients,
+        )?;
+        let client = self.client.to_account_info();
+        // Protocol fees wait on the config account until claim_fees
+        let fee_vault = self.config.to_account_info();
+        let vested = Cell::new(0);
+        let accrued_fees = Cell::new(0);
+        let pay = |payee: Payee, amount: u64| {
+            if amount == 0 {
+                return Ok(());
+            }
+            let to = match payee {
+                Payee::Freelancer => {
+                    vested.set(vested.get() + freelancer.deposit(&escrow, amount)?);
+                    return Ok(());
+                }
+                Payee::Client => &client,
+                Payee::Treasury => {
+                    accrued_fees.set(accrued_fees.get() + amount);
+                    &fee_vault
+                }
+            };
+            transfer_from_escrow(&escrow, to, amount)
+        };
+        // Anything still held above the rent floor and pending claims is accrued yield
+        let accrued_yield = |reserved: u64| {
+            let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+            Ok(escrow
+                .lamports()
+                .saturating_sub(rent_floor)
+                .saturating_sub(reserved))
+        };
+        self.release(pay, accrued_yield)?;
+        self.application.vested_amount += vested.get();
+        self.config.accrued_fees += accrued_fees.get();
+        Ok(())
+    }
+
+    // Each release may update the review; the rating counts towards the
+    // freelancer's reputation once, when the job is finally paid. The
+    // application is resized to fit the review: the approver pays for growth and
+    // the applicant, who paid for the account, gets freed rent back.
+    fn record_review(&mut self, client_review: String, rating: u8) -> Result<()> {
+        require!((1..=MAX_RATING).contains(&rating), ErrorCode::InvalidRating);
+        require!(
+            client_review.len() <= MAX_REVIEW_LEN,
+            ErrorCode::ReviewTooLong
+        );
+        let application = &mut self.application;
+        application.client_review = client_review;
+        application.freelancer_rating = rating;
+        resize_account(
+            &application.to_account_info(),
+            application.space(),
+            &self.signer.to_account_info(),
+            &self.freelancer,
+            &self.system_program,
+        )
+    }
+
+    // Shared by the lamport and token variants: `pay` releases escrowed funds, with
+    // Payee::Treasury accruing to a claimable balance rather than being sent, and
+    // `accrued_yield` reports what the escrow holds beyond the budget, given the
+    // lamports it must keep back for pending claims
+    fn release(
+        &mut self,
+        pay: impl Fn(Payee, u64) -> Result<()>,
+        accrued_yield: impl Fn(u64) -> Result<u64>,
+    ) -> Result<()> {
+        // Only a hired applicant's work is paid for, whatever its status says
+        require!(
+            self.application.is_hired(),
+            ErrorCode::ApplicationNotApproved
+        );
+        match self.application.status {
+            ApplicationStatus::Submitted => {}
+            ApplicationStatus::Paid => return err!(ErrorCode::AlreadyPaid),
+            ApplicationStatus::Disputed => return err!(ErrorCode::ApplicationDisputed),
+            _ => return err!(ErrorCode::WorkNotCompleted),
+        }
+        require!(!self.application.defaulted, ErrorCode::ApplicationDefaulted);
+        require!(
+            self.job_post.status == JobStatus::InProgress,
+            ErrorCode::JobNotInProgress
+        );
+
+        let application = &mut self.application;
+
+        // Transfer funds from escrow to freelancer; tranched jobs release one
+        // tranche per approved submission
+        let tranche = application.next_tranche_amount(&self.job_post);
+        self.escrow
+            .require_covers(application.released_amount, tranche)?;
+        // Late work forfeits part of the tranche back to the client
+        let penalty = self
+            .job_post
+            .late_penalty(tranche, application.submitted_at);
+        let earned = tranche - penalty;
+        let lamport_job = self.job_post.mint.is_none();
+        let (fee, referral) =
+            freelancer_fee(&self.config, &self.freelancer_account, earned, lamport_job);
+        pay(Payee::Freelancer, earned - fee)?;
+        if lamport_job {
+            self.protocol_stats.record_payout(earned - fee);
+        }
+        pay(Payee::Treasury, fee - referral)?;
+        pay_referral(
+            &self.escrow.to_account_info(),
+            self.payout.freelancer_referrer.as_ref(),
+            &self.freelancer_account,
+            referral,
+        )?;
+        pay(Payee::Client, penalty)?;
+        application.released_amount += tranche;
+        application.fees_paid += fee;
+        if self.job_post.mint.is_none() {
+            self.freelancer_reputation.total_earned += earned;
+            self.client_reputation.total_spent += earned;
+        }
+        if self.job_post.has_schedule {
+            let schedule = self.schedule.as_mut().ok_or(ErrorCode::ScheduleRequired)?;
+            if let Some(period) = schedule
+                .periods
+                .get_mut(usize::from(application.tranches_released))
+            {
+                period.amount = tranche;
+                period.status = PeriodStatus::Released;
+                period.released_at = Clock::get()?.unix_timestamp;
+            }
+        }
+        application.tranches_released += 1;
+
+        let remaining = application.unreleased_amount(&self.job_post);
+        self.freelancer_work_history.record(
+            self.job_post.key(),
+            self.job_post.mint,
+            earned - fee,
+            Clock::get()?.unix_timestamp,
+            remaining == 0,
+        );
+        emit!(SubmissionApproved {
+            application: application.key(),
+            job_post: self.job_post.key(),
+            freelancer: application.applicant,
+            amount: tranche,
+            fee,
+            late_penalty: penalty,
+            tranche: application.tranches_released,
+            is_final: remaining == 0,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        if remaining > 0 {
+            // The next tranche needs a fresh submission
+            application.set_status(ApplicationStatus::Approved)?;
+            msg!(
+                "Tranche {} of {} approved, {} released",
+                application.tranches_released,
+                self.job_post.tranche_count,
+                tranche
+            );
+            return Ok(());
+        }
+        application.set_status(ApplicationStatus::Paid)?;
+        pay_out_bond(application, &self.freelancer)?;
+
+        // Team jobs complete with their last outstanding hire
+        let now = Clock::get()?.unix_timestamp;
+        if self.job_post.settle_hire() {
+            self.job_post.transition(JobStatus::Completed)?;
+            self.escrow.status = EscrowStatus::Released;
+            // Budget for team slots that were never filled goes back to the client
+            let unhired = self.job_post.unhired_amount();
+            let client_fee = self.job_post.client_fee;
+            self.escrow
+                .require_covers(application.released_amount, unhired + client_fee)?;
+            pay(Payee::Client, unhired)?;
+            let referral = if lamport_job {
+                self.client_account.referral_cut(&self.config, client_fee)
+            } else {
+                0
+            };
+            pay(Payee::Treasury, client_fee - referral)?;
+            pay_referral(
+                &self.escrow.to_account_info(),
+                self.client_referrer.as_ref(),
+                &self.client_account,
+                referral,
+            )?;
+
+            // The referral bounty stays in the escrow PDA, in lamports for both escrow
+            // kinds, until the referrer claims it; without a referrer it goes back to
+            // the client
+            let bounty = self.job_post.referral_bounty;
+            if application.referrer.is_some() {
+                application.referral_claimable = bounty;
+            } else {
+                let escrow = self.escrow.to_account_info();
+                let client = self.client.to_account_info();
+                transfer_from_escrow(&escrow, &client, bounty)?;
+            }
+
+            // Settle yield per the client's choice at funding
+            let accrued_yield = accrued_yield(application.referral_claimable)?;
+            match self.job_post.yield_recipient {
+                YieldRecipient::Freelancer => pay(Payee::Freelancer, accrued_yield)?,
+                YieldRecipient::Client => pay(Payee::Client, accrued_yield)?,
+                YieldRecipient::FeeOffset => {
+                    let rebate = accrued_yield.min(application.fees_paid);
+                    pay(Payee::Freelancer, rebate)?;
+                    pay(Payee::Client, accrued_yield - rebate)?;
+                }
+            }
+            // Only the rent is left, unless a referral bounty is waiting to be
+            // claimed; claim_referral_bounty closes the escrow then
+            if application.referral_claimable == 0 {
+                close_escrow(
+                    &self.job_post,
+                    &self.escrow.to_account_info(),
+                    &self.client.to_account_info(),
+                )?;
+            }
+
+            let client_account = &mut self.client_account;
+            client_account.open_job_posts = client_account.open_job_posts.saturating_sub(1);
+            client_account.record_referred_job(&self.config);
+            self.client_reputation.completed_jobs += 1;
+            self.activity_feed.record(
+                ActivityKind::JobPaid,
+                self.job_post.key(),
+                self.job_post.job_id,
+                self.job_post.amount,
+                now,
+            );
+        }
+
+        // The job is finished, so it no longer counts against the freelancer's capacity
+        let freelancer_account = &mut self.freelancer_account;
+        freelancer_account.active_jobs = freelancer_account.active_jobs.saturating_sub(1);
+        freelancer_account.pending_applications =
+            freelancer_account.pending_applications.saturating_sub(1);
+        freelancer_account.record_referred_job(&self.config);
+
+        let reputation = &mut self.freelancer_reputation;
+        reputation.apply_decay(&self.config, now);
+        reputation.score = reputation.score.saturating_add(REPUTATION_POINTS_PER_JOB);
+        reputation.completed_jobs += 1;
+        // Payouts released by timeout carry no rating
+        if application.freelancer_rating > 0 {
+            reputation.record_rating(application.freelancer_rating);
+        }
+        if let Some(credential) = self.freelancer_credential.as_mut() {
+            credential.sync(reputation, now);
+            emit!(CredentialUpdated {
+                wallet: credential.wallet,
+                completed_jobs: credential.completed_jobs,
+                average_rating_x100: credential.average_rating_x100,
+                score: credential.score,
+                timestamp: now,
+            });
+        }
+
+        msg!("Submission approved, funds transferred, and review recorded");
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ApproveSubmissionSpl<'info> {
+    pub base: ApproveSubmission<'info>,
+    #[account(constraint = base.job_post.mint == Some(mint.key()) @ ErrorCode::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"escrow_vault", base.job_post.key().as_ref()],
+        bump,
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    // Token payouts must land in accounts the recipients actually own
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = base.application.applicant,
+        token::token_program = token_program,
+    )]
+    pub freelancer_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = base.job_post.client,
+        token::token_program = token_program,
+    )]
+    pub client_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ApproveSubmissionSpl<'info> {
+    fn release_tokens(&mut self) -> Result<()> {
+        let job_key = self.base.job_post.key();
+        let escrow_bump = self.base.job_post.escrow_bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"escrow", job_key.as_ref(), &[escrow_bump]]];
+        let escrow = self.base.escrow.to_account_info();
+        let vault = self.escrow_vault.to_account_info();
+        let mint = self.mint.to_account_info();
+        let decimals = self.mint.decimals;
+        let token_program = self.token_program.to_account_info();
+        let freelancer = self.freelancer_token_account.to_account_info();
+        let client = self.client_token_account.to_account_info();
+        // Protocol fees stay in the vault until claim_token_fees
+        let previously_accrued = self.base.job_post.accrued_token_fees;
+        let accrued_fees = Cell::new(0);
+        let pay = |payee: Payee, amount: u64| {
+            if amount == 0 {
+                return Ok(());
+            }
+            let to = match payee {
+                Payee::Freelancer => freelancer.clone(),
+                Payee::Client => client.clone(),
+                Payee::Treasury => {
+                    accrued_fees.set(accrued_fees.get() + amount);
+                    return Ok(());
+                }
+            };
+            // The recipient receives the amount net of any transfer fee
+            let fee = transfer_fee(&mint, amount)?;
+            msg!(
+                "Releasing {}, {} received after transfer fees",
+                amount,
+                amount.saturating_sub(fee)
+            );
+            let cpi_context = CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferChecked {
+                    from: vault.clone(),
+                    mint: mint.clone(),
+                    to,
+                    authority: escrow.clone(),
+                },
+                signer_seeds,
+            );
+            token_interface::transfer_checked(cpi_context, amount, decimals)
+        };
+        // Tokens sent to the vault beyond the budget and unclaimed fees are accrued yield
+        let accrued_yield = |_reserved_lamports: u64| {
+            let vault = TokenAccount::try_deserialize(&mut &vault.try_borrow_data()?[..])?;
+            Ok(vault
+                .amount
+                .saturating_sub(previously_accrued + accrued_fees.get()))
+        };
+        self.base.release(pay, accrued_yield)?;
+        self.base.job_post.accrued_token_fees += accrued_fees.get();
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SweepExcessToClient<'info> {
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(has_one = job_post)]
+    pub application: Option<Account<'info, Application>>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    pub client: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterArbitrator<'info> {
+    #[account(
         init,
         payer = signer,
-        space = 8 + UserAccount::INIT_SPACE,
-        seeds = [b"user", signer.key().as_ref()],
+        space = 8 + ArbitratorStake::INIT_SPACE,
+        seeds = [b"arbitrator", signer.key().as_ref()],
         bump
     )]
+    pub arbitrator_stake: Account<'info, ArbitratorStake>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
     pub user_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        seeds = [b"arbitrator_registry"],
+        bump = arbitrator_registry.bump
+    )]
+    pub arbitrator_registry: Account<'info, ArbitratorRegistry>,
     #[account(mut)]
     pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(title: String, start_date: i64, end_date: i64)]
-pub struct InitializeJobPost<'info> {
+pub struct ManageArbitratorStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"arbitrator", signer.key().as_ref()],
+        bump = arbitrator_stake.bump
+    )]
+    pub arbitrator_stake: Account<'info, ArbitratorStake>,
+    #[account(
+        mut,
+        seeds = [b"arbitrator_registry"],
+        bump = arbitrator_registry.bump
+    )]
+    pub arbitrator_registry: Account<'info, ArbitratorRegistry>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = fee_treasury @ ErrorCode::InvalidFeeTreasury,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    /// CHECK: Treasury wallet named in the config
+    pub fee_treasury: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTokenFees<'info> {
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump
+    )]
+    /// CHECK: Escrow, authority over the vault; may be closed once the job is
+    /// paid out, which does not stop it signing
+    pub escrow: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow_vault", job_post.key().as_ref()],
+        bump,
+    )]
+    pub escrow_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(constraint = job_post.mint == Some(mint.key()) @ ErrorCode::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = config.fee_treasury,
+        token::token_program = token_program,
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralBounty<'info> {
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, constraint = application.referrer == Some(referrer.key()) @ ErrorCode::InvalidReferrer)]
+    pub referrer: Signer<'info>,
+    #[account(mut, address = job_post.client @ ErrorCode::Unauthorized)]
+    /// CHECK: Receives the escrow's rent once the bounty is out
+    pub client: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Dispute::INIT_SPACE,
+        seeds = [b"dispute", application.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(
+        mut,
+        constraint = job_post.organization.is_none() || organization.is_some()
+            @ ErrorCode::OrganizationRequired
+    )]
+    pub job_post: Account<'info, JobPost>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"user", signer.key().as_ref()],
+        bump,
+        constraint = user_account.has_accepted_terms(&config) @ ErrorCode::TermsNotAccepted
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        seeds = [b"arbitrator_registry"],
+        bump = arbitrator_registry.bump
+    )]
+    pub arbitrator_registry: Account<'info, ArbitratorRegistry>,
+    // Required on organization jobs, whose creator and admins cannot arbitrate
+    #[account(
+        seeds = [b"organization", organization.creator.as_ref()],
+        bump = organization.bump,
+        constraint = job_post.organization == Some(organization.key()) @ ErrorCode::InvalidOrganization
+    )]
+    pub organization: Option<Account<'info, Organization>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct RespondToDispute<'info> {
+    #[account(mut)]
+    pub dispute: Account<'info, Dispute>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ReassignArbiter<'info> {
+    #[account(mut, has_one = job_post)]
+    pub dispute: Account<'info, Dispute>,
+    #[account(
+        constraint = job_post.organization.is_none() || organization.is_some()
+            @ ErrorCode::OrganizationRequired
+    )]
+    pub job_post: Account<'info, JobPost>,
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"arbitrator_registry"],
+        bump = arbitrator_registry.bump
+    )]
+    pub arbitrator_registry: Account<'info, ArbitratorRegistry>,
+    #[account(
+        mut,
+        seeds = [b"arbitrator", dispute.arbiter.as_ref()],
+        bump = previous_arbiter_stake.bump
+    )]
+    pub previous_arbiter_stake: Account<'info, ArbitratorStake>,
+    // Required on organization jobs, whose creator and admins cannot arbitrate
+    #[account(
+        seeds = [b"organization", organization.creator.as_ref()],
+        bump = organization.bump,
+        constraint = job_post.organization == Some(organization.key()) @ ErrorCode::InvalidOrganization
+    )]
+    pub organization: Option<Account<'info, Organization>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> ReassignArbiter<'info> {
+    // The replacement is the registry's next eligible arbitrator other than the
+    // current one; candidates are passed as for open_dispute
+    fn reassign(
+        &mut self,
+        candidates: &'info [AccountInfo<'info>],
+        program_id: &Pubkey,
+        conflict_of_interest: bool,
+    ) -> Result<()> {
+        let dispute = &mut self.dispute;
+        require!(
+            dispute.status != DisputeStatus::Resolved,
+            ErrorCode::InvalidDisputeState
+        );
+
+        let new_arbiter = assign_arbiter(
+            &mut self.arbitrator_registry,
+            candidates,
+            program_id,
+            &self.config,
+            [dispute.claimant, dispute.respondent],
+            self.organization.as_deref(),
+            Some(dispute.arbiter),
+        )?;
+        self.previous_arbiter_stake.active_disputes = self
+            .previous_arbiter_stake
+            .active_disputes
+            .saturating_sub(1);
+
+        // A replacement arbiter always gets time to rule
+        let clock = Clock::get()?;
+        let previous_arbiter = dispute.arbiter;
+        dispute.arbiter = new_arbiter;
+        dispute.ruling_deadline = dispute
+            .ruling_deadline
+            .max(clock.unix_timestamp + ARBITER_REASSIGNMENT_WINDOW_SECS);
+
+        emit!(ArbiterReassigned {
+            dispute: dispute.key(),
+            previous_arbiter,
+            new_arbiter: dispute.arbiter,
+            conflict_of_interest,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!(
+            "Arbiter reassigned from {} to {}",
+            previous_arbiter,
+            dispute.arbiter
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ReportArbiterConflict<'info> {
+    pub reassignment: ReassignArbiter<'info>,
+    #[account(
+        constraint = evidence_application.job_post == evidence_job_post.key()
+            @ ErrorCode::InvalidConflictEvidence
+    )]
+    pub evidence_application: Account<'info, Application>,
+    pub evidence_job_post: Account<'info, JobPost>,
+}
+
+#[derive(Accounts)]
+pub struct SettleDispute<'info> {
+    #[account(
+        mut,
+        has_one = application,
+        seeds = [b"dispute", application.key().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    #[account(mut)]
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = application.applicant)]
+    /// CHECK: Freelancer
+    pub freelancer: AccountInfo<'info>,
+    #[account(mut, address = job_post.client)]
+    /// CHECK: Client
+    pub client: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", application.applicant.as_ref()],
+        bump
+    )]
+    pub freelancer_account: Account<'info, UserAccount>,
+    pub payout: PayoutRouting<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", job_post.client.as_ref()],
+        bump
+    )]
+    pub client_account: Account<'info, UserAccount>,
+    // Required while the client's referrer is owed a share of the fees
+    #[account(
+        mut,
+        constraint = client_account.referred_by == Some(client_referrer.key()) @ ErrorCode::Unauthorized
+    )]
+    /// CHECK: Receives the client's referral share; pinned to referred_by
+    pub client_referrer: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"reputation", application.applicant.as_ref()],
+        bump = freelancer_reputation.bump
+    )]
+    pub freelancer_reputation: Account<'info, Reputation>,
+    #[account(
+        mut,
+        seeds = [b"reputation", job_post.client.as_ref()],
+        bump = client_reputation.bump
+    )]
+    pub client_reputation: Account<'info, Reputation>,
+    #[account(
+        mut,
+        seeds = [b"work_history", application.applicant.as_ref()],
+        bump = freelancer_work_history.bump
+    )]
+    pub freelancer_work_history: Account<'info, WorkHistory>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"activity_feed"], bump = activity_feed.bump)]
+    pub activity_feed: Account<'info, ActivityFeed>,
+    #[account(mut, address = dispute.arbiter)]
+    /// CHECK: Assigned arbiter, receives the arbitration fee
+    pub arbiter: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"arbitrator", dispute.arbiter.as_ref()],
+        bump = arbiter_stake.bump
+    )]
+    pub arbiter_stake: Account<'info, ArbitratorStake>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    // Token jobs pay out of the vault, so these are required there
+    #[account(constraint = job_post.mint == Some(mint.key()) @ ErrorCode::MintMismatch)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"escrow_vault", job_post.key().as_ref()],
+        bump,
+    )]
+    pub escrow_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = freelancer_token_account.owner == application.applicant
+            @ ErrorCode::InvalidTokenAccount
+    )]
+    pub freelancer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = client_token_account.owner == job_post.client
+            @ ErrorCode::InvalidTokenAccount
+    )]
+    pub client_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = arbiter_token_account.owner == dispute.arbiter
+            @ ErrorCode::InvalidTokenAccount
+    )]
+    pub arbiter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+impl<'info> SettleDispute<'info> {
+    // The client's open job only closes with the job's last outstanding hire
+    fn finish_engagement(&mut self, job_finished: bool) {
+        let freelancer_account = &mut self.freelancer_account;
+        freelancer_account.active_jobs = freelancer_account.active_jobs.saturating_sub(1);
+        freelancer_account.pending_applications =
+            freelancer_account.pending_applications.saturating_sub(1);
+        if job_finished {
+            let client_account = &mut self.client_account;
+            client_account.open_job_posts = client_account.open_job_posts.saturating_sub(1);
+        }
+    }
+
+    // Pays out of escrow in the job's currency: lamports to `to`, or tokens
+    // from the vault to `token_account` on token jobs
+    fn pay_out(
+        &self,
+        to: &AccountInfo<'info>,
+        token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<()> {
+        let escrow = self.escrow.to_account_info();
+        if self.job_post.mint.is_none() {
+            return transfer_from_escrow(&escrow, to, amount);
+        }
+        if amount == 0 {
+            return Ok(());
+        }
+        let (Some(mint), Some(vault), Some(token_account), Some(token_program)) = (
+            &self.mint,
+            &self.escrow_vault,
+            token_account,
+            &self.token_program,
+        ) else {
+            return err!(ErrorCode::TokenAccountsRequired);
+        };
+        transfer_from_vault(
+            &self.job_post,
+            &escrow,
+            vault,
+            mint,
+            token_account,
+            token_program,
+            amount,
+        )
+    }
+
+    // Pays the freelancer's award net of the freelancer fee: through their payout
+    // routing on lamport jobs, or out of the vault with the fee left accruing
+    // there on token jobs. Returns the fee.
+    fn pay_freelancer(
+        &mut self,
+        earned: u64,
+        split_recipients: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        if self.job_post.mint.is_none() {
+            let payee = self.payout.payee(
+                &self.application,
+                &self.freelancer_account,
+                self.freelancer.to_account_info(),
+                split_recipients,
+            )?;
+            let paid = payee.pay_earnings(
+                &self.escrow.to_account_info(),
+                earned,
+                &mut self.application,
+                &self.freelancer_account,
+                &mut self.config,
+            )?;
+            return Ok(earned - paid);
+        }
+        let (fee, _) = freelancer_fee(&self.config, &self.freelancer_account, earned, false);
+        self.pay_out(
+            &self.freelancer,
+            self.freelancer_token_account.as_ref(),
+            earned - fee,
+        )?;
+        self.job_post.accrued_token_fees += fee;
+        self.application.fees_paid += fee;
+        Ok(fee)
+    }
+
+    // A completed job earns the protocol the client-side fee, as approval does;
+    // lamport jobs accrue it on the config account less the referrer's share
+    fn collect_client_fee(&mut self) -> Result<()> {
+        let client_fee = self.job_post.client_fee;
+        if self.job_post.mint.is_some() {
+            self.job_post.accrued_token_fees += client_fee;
+            return Ok(());
+        }
+        let referral = self.client_account.referral_cut(&self.config, client_fee);
+        let escrow = self.escrow.to_account_info();
+        transfer_from_escrow(
+            &escrow,
+            &self.config.to_account_info(),
+            client_fee - referral,
+        )?;
+        self.config.accrued_fees += client_fee - referral;
+        pay_referral(
+            &escrow,
+            self.client_referrer.as_ref(),
+            &self.client_account,
+            referral,
+        )?;
+        self.client_account.record_referred_job(&self.config);
+        Ok(())
+    }
+
+    // split_recipients: the wallet of every payout split recipient, when the
+    // freelancer is paid
+    fn settle(
+        &mut self,
+        ruling: DisputeRuling,
+        resolution: DisputeResolution,
+        split_recipients: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let remaining = self.application.unreleased_amount(&self.job_post);
+        self.escrow
+            .require_covers(self.application.released_amount, remaining)?;
+        let lamport_job = self.job_post.mint.is_none();
+        // Whatever a token job's vault holds beyond unclaimed fees goes back
+        // to the client with the job; lamport escrows keep theirs for
+        // sweep_excess_to_client
+        let vault_surplus = match &self.escrow_vault {
+            Some(vault) if !lamport_job => vault
+                .amount
+                .saturating_sub(self.job_post.accrued_token_fees + remaining),
+            _ => 0,
+        };
+
+        // Only an actual ruling earns the arbiter a cut; defaults and timeouts do not
+        let arbiter_fee = if resolution == DisputeResolution::Arbiter {
+            bps_of(remaining, self.config.arbitrator_fee_bps)
+        } else {
+            0
+        };
+        self.pay_out(
+            &self.arbiter,
+            self.arbiter_token_account.as_ref(),
+            arbiter_fee,
+        )?;
+        let earned = remaining - arbiter_fee;
+        let fee = match ruling {
+            DisputeRuling::ReleaseToFreelancer => {
+                let fee = self.pay_freelancer(earned, split_recipients)?;
+                self.freelancer_account.record_referred_job(&self.config);
+                fee
+            }
+            DisputeRuling::RefundClient => {
+                self.pay_out(&self.client, self.client_token_account.as_ref(), earned)?;
+                0
+            }
+        };
+        if ruling == DisputeRuling::ReleaseToFreelancer && lamport_job {
+            self.protocol_stats.record_payout(earned - fee);
+        }
+        self.arbiter_stake.active_disputes = self.arbiter_stake.active_disputes.saturating_sub(1);
+
+        self.application.set_status(ApplicationStatus::Paid)?;
+        // Only a missed deadline forfeits the bond, whatever the ruling
+        pay_out_bond(&mut self.application, &self.freelancer)?;
+
+        // A team job carries on past one hire's dispute and completes with its
+        // last outstanding hire, as it would through approval
+        let team = self.job_post.is_team();
+        if team {
+            self.job_post.open_disputes = self.job_post.open_disputes.saturating_sub(1);
+        }
+        let job_finished = self.job_post.settle_hire();
+        if team && job_finished {
+            self.job_post.transition(JobStatus::Completed)?;
+            // Budget for team slots that were never filled goes back to the client
+            let unhired = self.job_post.unhired_amount();
+            self.escrow.require_covers(
+                self.application.budget(&self.job_post),
+                unhired + self.job_post.client_fee,
+            )?;
+            self.pay_out(&self.client, self.client_token_account.as_ref(), unhired)?;
+            self.collect_client_fee()?;
+        } else if job_finished {
+            self.job_post.transition(match ruling {
+                DisputeRuling::ReleaseToFreelancer => JobStatus::Completed,
+                DisputeRuling::RefundClient => JobStatus::Cancelled,
+            })?;
+            // A refunded job's client-side fee goes back with the vault surplus
+            let client_fee = if self.job_post.status == JobStatus::Completed {
+                self.escrow.require_covers(
+                    self.application.budget(&self.job_post),
+                    self.job_post.client_fee,
+                )?;
+                self.collect_client_fee()?;
+                self.job_post.client_fee
+            } else {
+                0
+            };
+            self.pay_out(
+                &self.client,
+                self.client_token_account.as_ref(),
+                vault_surplus.saturating_sub(client_fee),
+            )?;
+        }
+        if job_finished {
+            self.escrow.status = match self.job_post.status {
+                JobStatus::Completed => EscrowStatus::Released,
+                _ => EscrowStatus::Refunded,
+            };
+        }
+        self.finish_engagement(job_finished);
+
+        // The losing side takes a strike
+        let now = Clock::get()?.unix_timestamp;
+        let loser = match ruling {
+            DisputeRuling::ReleaseToFreelancer => &mut self.client_reputation,
+            DisputeRuling::RefundClient => &mut self.freelancer_reputation,
+        };
+        loser.apply_decay(&self.config, now);
+        loser.strike_points = loser.strike_points.saturating_add(STRIKE_POINTS);
+        self.freelancer_reputation.dispute_count += 1;
+        self.client_reputation.dispute_count += 1;
+
+        if ruling == DisputeRuling::ReleaseToFreelancer {
+            if lamport_job {
+                self.freelancer_reputation.total_earned += remaining;
+                self.client_reputation.total_spent += remaining;
+            }
+            self.freelancer_work_history.record(
+                self.job_post.key(),
+                self.job_post.mint,
+                remaining - arbiter_fee,
+                now,
+                true,
+            );
+            self.activity_feed.record(
+                ActivityKind::JobPaid,
+                self.job_post.key(),
+                self.job_post.job_id,
+                remaining,
+                now,
+            );
+        }
+
+        // The bond goes back to a claimant who prevails and to the respondent otherwise
+        let client_won = ruling == DisputeRuling::RefundClient;
+        let bond_to_client = client_won == (self.dispute.claimant == self.job_post.client);
+        let bond_destination = if bond_to_client {
+            self.client.to_account_info()
+        } else {
+            self.freelancer.to_account_info()
+        };
+        let dispute_info = self.dispute.to_account_info();
+        transfer_from_escrow(&dispute_info, &bond_destination, self.dispute.bond)?;
+
+        let dispute = &mut self.dispute;
+        dispute.status = DisputeStatus::Resolved;
+        dispute.ruling = Some(ruling);
+        dispute.resolution = Some(resolution);
+
+        emit!(DisputeResolved {
+            dispute: dispute.key(),
+            application: dispute.application,
+            ruling,
+            resolution,
+            timestamp: now,
+        });
+
+        msg!("Dispute resolved by {:?}: {:?}", resolution, ruling);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct OrderInterimRelease<'info> {
+    #[account(has_one = application)]
+    pub dispute: Account<'info, Dispute>,
+    #[account(mut, has_one = job_post)]
+    pub application: Account<'info, Application>,
+    pub job_post: Account<'info, JobPost>,
+    #[account(
+        mut,
+        seeds = [b"escrow", job_post.key().as_ref()],
+        bump = job_post.escrow_bump,
+        constraint = escrow.status == EscrowStatus::Funded @ ErrorCode::EscrowNotFunded
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, address = application.applicant)]
+    /// CHECK: Freelancer
+    pub freelancer: AccountInfo<'info>,
+    pub signer: Signer<'info>,
     #[account(
-        init,
-        payer = signer,
-        space = 8 + JobPost::INIT_SPACE,
-        seeds = [b"job_post", signer.key().as_ref(), title.as_bytes()],
-        bump
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::ProgramPaused
     )]
-    pub job_post: Account<'info, JobPost>,
-
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"protocol_stats"], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    // Token jobs pay out of the vault, so these are required there
+    #[account(constraint = job_post.mint == Some(mint.key()) @ ErrorCode::MintMismatch)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
     #[account(
-        init,
-        payer = signer,
-        seeds = [b"escrow", job_post.key().as_ref()],
+        mut,
+        seeds = [b"escrow_vault", job_post.key().as_ref()],
         bump,
-        space = 8
     )]
-    /// CHECK: Escrow account
-    pub escrow: UncheckedAccount<'info>,
-
-    #[account(mut)]
-    pub signer: Signer<'info>,
-    pub user_account: Account<'info, UserAccount>,
-    pub system_program: Program<'info, System>,
+    pub escrow_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = freelancer_token_account.owner == application.applicant
+            @ ErrorCode::InvalidTokenAccount
+    )]
+    pub freelancer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 #[derive(Accounts)]
-pub struct ApplyToJob<'info> {
+pub struct ResolveDispute<'info> {
+    pub settlement: SettleDispute<'info>,
     #[account(
         init,
         payer = signer,
-        space = 8 + Application::INIT_SPACE,
-        seeds = [b"application", job_post.key().as_ref(), signer.key().as_ref()],
+        space = 8 + Ruling::INIT_SPACE,
+        seeds = [b"ruling", settlement.dispute.key().as_ref()],
         bump
     )]
-    pub application: Account<'info, Application>,
+    pub ruling: Account<'info, Ruling>,
     #[account(mut)]
     pub signer: Signer<'info>,
-    pub user_account: Account<'info, UserAccount>,
-    pub job_post: Account<'info, JobPost>,
     pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)]
-pub struct ApproveApplication<'info> {
-    #[account(mut)]
-    pub application: Account<'info, Application>,
-    #[account(mut)]
-    pub job_post: Account<'info, JobPost>,
-    #[account(mut)]
-    pub signer: Signer<'info>,
-    pub user_account: Account<'info, UserAccount>,
+#[event]
+pub struct UserRegistered {
+    pub wallet: Pubkey,
+    pub handle: String,
+    pub role: UserRole,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct SubmitWork<'info> {
-    #[account(mut)]
-    pub application: Account<'info, Application>,
-    #[account(mut)]
-    pub signer: Signer<'info>,
-    pub user_account: Account<'info, UserAccount>,
-    pub job_post: Account<'info, JobPost>,
+#[event]
+pub struct AvailabilityChanged {
+    pub wallet: Pubkey,
+    pub available: bool,
+    pub hourly_rate: u64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct ApproveSubmission<'info> {
-    #[account(mut)]
-    pub application: Account<'info, Application>,
-    #[account(mut)]
-    pub job_post: Account<'info, JobPost>,
-    #[account(
-        mut,
-        seeds = [b"escrow", job_post.key().as_ref()],
-        bump = job_post.escrow_bump
-    )]
-    /// CHECK: Escrow
-    pub escrow: UncheckedAccount<'info>,
-    #[account(mut)]
-    pub signer: Signer<'info>,
-    pub user_account: Account<'info, UserAccount>,
-    #[account(mut)]
-    /// CHECK: Freelancer
-    pub freelancer: AccountInfo<'info>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct JobPosted {
+    pub job_post: Pubkey,
+    pub client: Pubkey,
+    pub job_id: u64,
+    pub amount: u64,
+    // None for lamport-escrowed jobs
+    pub mint: Option<Pubkey>,
+    pub category: JobCategory,
+    pub start_date: i64,
+    pub end_date: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MetadataUpdated {
+    // The JobPost or Application now pointing at the document
+    pub account: Pubkey,
+    pub content_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobEdited {
+    pub job_post: Pubkey,
+    pub client: Pubkey,
+    pub start_date: i64,
+    pub end_date: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobBudgetIncreased {
+    pub job_post: Pubkey,
+    pub additional: u64,
+    // Budget after the increase
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobBudgetDecreased {
+    pub job_post: Pubkey,
+    // Lamports returned to the client, including the released client-side fee
+    pub refund: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobCancelled {
+    pub job_post: Pubkey,
+    pub client: Pubkey,
+    pub refund: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobArchived {
+    pub job_post: Pubkey,
+    pub client: Pubkey,
+    pub job_id: u64,
+    pub status: JobStatus,
+    pub amount: u64,
+    pub escrow_swept: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApplicationArchived {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub applicant: Pubkey,
+    pub released_amount: u64,
+    pub freelancer_rating: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApproversSet {
+    pub job_post: Pubkey,
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubmissionApprovalRecorded {
+    pub application: Pubkey,
+    pub approver: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowClosed {
+    pub job_post: Pubkey,
+    pub client: Pubkey,
+    pub swept: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowReclaimed {
+    pub job_post: Pubkey,
+    pub application: Pubkey,
+    pub freelancer: Pubkey,
+    pub refund: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApplicationSubmitted {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub applicant: Pubkey,
+    pub expected_end_date: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApplicationWithdrawn {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub applicant: Pubkey,
+    pub timestamp: i64,
+}
+
+// Emitted for every hire: direct approval, accepted offer, converted trial or accepted quote
+#[event]
+pub struct ApplicationApproved {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub freelancer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CounterOfferProposed {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub posted_amount: u64,
+    pub proposed_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RetainerCreated {
+    pub retainer: Pubkey,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub period_amount: u64,
+    pub period_secs: i64,
+    pub periods: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RetainerPaymentClaimed {
+    pub retainer: Pubkey,
+    pub periods: u16,
+    pub amount: u64,
+    pub periods_claimed: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RetainerCancelled {
+    pub retainer: Pubkey,
+    pub paid_to_freelancer: u64,
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct QuoteCreated {
+    pub quote: Pubkey,
+    pub freelancer: Pubkey,
+    pub client: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct QuoteCancelled {
+    pub quote: Pubkey,
+    pub freelancer: Pubkey,
+    pub client: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrialFunded {
+    pub trial: Pubkey,
+    pub application: Pubkey,
+    pub amount: u64,
+    pub deadline: i64,
+    pub convert_on_approval: bool,
+}
+
+#[event]
+pub struct TrialSubmitted {
+    pub trial: Pubkey,
+    pub application: Pubkey,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct TrialApproved {
+    pub trial: Pubkey,
+    pub application: Pubkey,
+    pub amount: u64,
+    pub converted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrialCancelled {
+    pub trial: Pubkey,
+    pub application: Pubkey,
+    pub refund: u64,
+}
+
+#[event]
+pub struct WorkSubmitted {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub freelancer: Pubkey,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct RevisionRequested {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub revision_count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExtensionRequested {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub current_end_date: i64,
+    pub requested_end_date: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExtensionApproved {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub previous_end_date: i64,
+    pub end_date: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TimeLogged {
+    pub log: Pubkey,
+    pub application: Pubkey,
+    pub hours: u16,
+    pub logged_hours: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpfrontPaymentReleased {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub job_post: Pubkey,
+    pub application: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub total_released: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TimeApproved {
+    pub log: Pubkey,
+    pub application: Pubkey,
+    pub hours: u32,
+    pub amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneApproved {
+    pub milestone: Pubkey,
+    pub application: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+    pub fee: u64,
+    pub client_fee: u64,
+    pub timestamp: i64,
+}
+
+// One per released tranche, whether approved by the client or claimed after the
+// review window; amount is the gross tranche before the freelancer fee
+#[event]
+pub struct DelegateCreated {
+    pub principal: Pubkey,
+    pub session_key: Pubkey,
+    pub scope: u8,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DelegateRevoked {
+    pub principal: Pubkey,
+    pub session_key: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrganizationCreated {
+    pub organization: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PayoutSplitsSet {
+    pub application: Pubkey,
+    pub splits: Vec<PayoutSplit>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CredentialUpdated {
+    pub wallet: Pubkey,
+    pub completed_jobs: u32,
+    pub average_rating_x100: u64,
+    pub score: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CompletionCertificateMinted {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub freelancer: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FreelancerTipped {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub freelancer: Pubkey,
+    // None for lamport tips
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub tips_received: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubmissionApproved {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub freelancer: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    // Refunded to the client for late delivery
+    pub late_penalty: u64,
+    pub tranche: u8,
+    pub is_final: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClientReviewed {
+    pub application: Pubkey,
+    pub client: Pubkey,
+    pub rating: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobBoosted {
+    pub job_post: Pubkey,
+    pub fee: u64,
+    pub featured_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesClaimed {
+    // None for lamport fees
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralRegistered {
+    pub user: Pubkey,
+    pub referrer: Pubkey,
+    pub timestamp: i64,
+}
+
+// A referrer's share of the protocol fee on a referred user's job
+#[event]
+pub struct ReferralFeePaid {
+    pub referrer: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralBountyClaimed {
+    pub application: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollateralCalled {
+    pub job_post: Pubkey,
+    pub collateral_value: u64,
+    pub agreed_usd_value: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CheckInNonceIssued {
+    pub application: Pubkey,
+    pub nonce: [u8; 32],
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct CheckInRecorded {
+    pub application: Pubkey,
+    pub sequence: u32,
+    pub evidence_hash: [u8; 32],
+    pub checked_in_at: i64,
+}
+
+#[event]
+pub struct PayoutVested {
+    pub beneficiary: Pubkey,
+    pub vesting_program: Pubkey,
+    pub vesting_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ApplicationStageChanged {
+    pub application: Pubkey,
+    pub job_post: Pubkey,
+    pub job_id: u64,
+    pub from: ApplicationStage,
+    pub to: ApplicationStage,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FreelancerInvited {
+    pub invitation: Pubkey,
+    pub job_post: Pubkey,
+    pub freelancer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub dispute: Pubkey,
+    pub application: Pubkey,
+    pub claimant: Pubkey,
+    pub respondent: Pubkey,
+    pub arbiter: Pubkey,
+    pub response_deadline: i64,
+    pub ruling_deadline: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub dispute: Pubkey,
+    pub application: Pubkey,
+    pub ruling: DisputeRuling,
+    pub resolution: DisputeResolution,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ArbitratorSlashed {
+    pub arbitrator: Pubkey,
+    pub amount: u64,
+    pub remaining_stake: u64,
+    pub evidence_hash: [u8; 32],
+}
+
+#[event]
+pub struct ArbiterReassigned {
+    pub dispute: Pubkey,
+    pub previous_arbiter: Pubkey,
+    pub new_arbiter: Pubkey,
+    pub conflict_of_interest: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InterimReleaseOrdered {
+    pub dispute: Pubkey,
+    pub application: Pubkey,
+    pub amount: u64,
+    pub total_released: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WalletMigrationInitiated {
+    pub old_wallet: Pubkey,
+    pub new_wallet: Pubkey,
+    pub available_at: i64,
+}
+
+#[event]
+pub struct WalletMigrated {
+    pub old_wallet: Pubkey,
+    pub new_wallet: Pubkey,
+    pub timestamp: i64,
+}
+
+// Lets counterparties on a job learn that the other side changed wallets
+#[event]
+pub struct EngagementMigrated {
+    pub engagement: Pubkey,
+    pub job_post: Pubkey,
+    pub old_wallet: Pubkey,
+    pub new_wallet: Pubkey,
 }
 
 #[error_code]
@@ -361,10 +12630,355 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("This job has already been filled")]
     JobAlreadyFilled,
+    #[msg("This job has been cancelled")]
+    JobCancelled,
     #[msg("Application has not been approved yet")]
     ApplicationNotApproved,
+    #[msg("Application has already been approved")]
+    ApplicationAlreadyApproved,
     #[msg("Work has not been completed yet")]
     WorkNotCompleted,
     #[msg("Invalid dates provided")]
     InvalidDates,
+    #[msg("Freelancer has reached their active job capacity")]
+    FreelancerAtCapacity,
+    #[msg("Invalid application stage transition")]
+    InvalidStageTransition,
+    #[msg("There is no pending offer on this application")]
+    NoPendingOffer,
+    #[msg("The offer has expired")]
+    OfferExpired,
+    #[msg("The offer has not expired yet")]
+    OfferNotExpired,
+    #[msg("Invitation account does not match the invitee")]
+    InvalidInvitationAccounts,
+    #[msg("This freelancer has already been invited to the job")]
+    InvitationAlreadyExists,
+    #[msg("Escrow balance is insufficient for this transfer")]
+    InsufficientEscrowFunds,
+    #[msg("Escrow for this application has already been paid out")]
+    AlreadyPaid,
+    #[msg("Payout is blocked by an open dispute")]
+    ApplicationDisputed,
+    #[msg("Arbiter must be a registered arbitrator who is not a party to the job")]
+    InvalidArbiter,
+    #[msg("The dispute is not in the right state for this action")]
+    InvalidDisputeState,
+    #[msg("The dispute deadline for this action has passed")]
+    DisputeDeadlinePassed,
+    #[msg("The dispute deadline has not been reached yet")]
+    DisputeDeadlineNotReached,
+    #[msg("The arbitrator registry is full")]
+    ArbitratorRegistryFull,
+    #[msg("No eligible arbitrator among the candidates passed")]
+    NoEligibleArbiter,
+    #[msg("Arbiter candidates must be passed in registry order")]
+    InvalidArbiterCandidates,
+    #[msg("Only the authority can grant the arbitrator role")]
+    ArbitratorRoleRestricted,
+    #[msg("This party has already used their arbiter challenge")]
+    ChallengeAlreadyUsed,
+    #[msg("Evidence does not show a relationship between the arbiter and a party")]
+    InvalidConflictEvidence,
+    #[msg("Relationship account does not match the client and freelancer")]
+    InvalidRelationshipAccount,
+    #[msg("Organization does not match the job")]
+    InvalidOrganization,
+    #[msg("Organization jobs need their organization to assign an arbiter")]
+    OrganizationRequired,
+    #[msg("Release amount must be positive and leave part of the escrow frozen")]
+    InvalidReleaseAmount,
+    #[msg("Invalid config parameters")]
+    InvalidConfig,
+    #[msg("The window for opening a dispute has closed")]
+    DisputeWindowClosed,
+    #[msg("This reputation issuer is not active")]
+    InactiveReputationIssuer,
+    #[msg("Payout destination cannot be an executable or token account")]
+    InvalidPayoutDestination,
+    #[msg("This instruction cannot be invoked through CPI")]
+    CpiNotAllowed,
+    #[msg("Wrong number of remaining accounts")]
+    InvalidRemainingAccountsCount,
+    #[msg("An account was passed more than once in remaining accounts")]
+    DuplicateRemainingAccount,
+    #[msg("A remaining account must be writable")]
+    RemainingAccountNotWritable,
+    #[msg("A remaining account has the wrong owner")]
+    RemainingAccountOwnerMismatch,
+    #[msg("The job is filled, so the hired application must be provided")]
+    ApprovedApplicationRequired,
+    #[msg("Escrow surplus is reserved for the freelancer until payout")]
+    ExcessReservedForFreelancer,
+    #[msg("Escrow holds no excess funds")]
+    NoExcessFunds,
+    #[msg("Invalid wallet migration")]
+    InvalidWalletMigration,
+    #[msg("The wallet migration delay has not elapsed yet")]
+    WalletMigrationPending,
+    #[msg("Invalid beneficiary settings")]
+    InvalidBeneficiary,
+    #[msg("Extension key or value exceeds the allowed size")]
+    InvalidExtensionField,
+    #[msg("Extension has no room for more fields")]
+    ExtensionFull,
+    #[msg("Extension field not found")]
+    ExtensionFieldNotFound,
+    #[msg("Revision limit reached; open a dispute instead")]
+    RevisionLimitReached,
+    #[msg("Milestone is invalid or has already been released")]
+    InvalidMilestone,
+    #[msg("Text must not be empty")]
+    EmptyText,
+    #[msg("Text must not start or end with whitespace")]
+    SurroundingWhitespace,
+    #[msg("Text must not contain control or invisible formatting characters")]
+    ControlCharacters,
+    #[msg("Text must not mix Latin, Greek and Cyrillic letters")]
+    MixedScripts,
+    #[msg("Text consists only of characters that imitate Latin letters")]
+    ConfusableText,
+    #[msg("The account owner has been active within their inactivity horizon")]
+    OwnerStillActive,
+    #[msg("This job is escrowed in an SPL token; use the token instructions")]
+    TokenEscrowedJob,
+    #[msg("Mint does not match the job's escrowed token")]
+    MintMismatch,
+    #[msg("Referrer must be a registered user other than the applicant or client")]
+    InvalidReferrer,
+    #[msg("Bounty amount must be greater than zero")]
+    InvalidBounty,
+    #[msg("Could not compute the mint's transfer fee")]
+    InvalidTransferFee,
+    #[msg("Account is not the configured fee treasury")]
+    InvalidFeeTreasury,
+    #[msg("The program is paused")]
+    ProgramPaused,
+    #[msg("Vesting program is not active")]
+    VestingProgramInactive,
+    #[msg("The vesting program, its record and the vesting account must be provided")]
+    VestingAccountsRequired,
+    #[msg("Vesting account is not owned by the vesting program")]
+    InvalidVestingAccount,
+    #[msg("Token jobs need the mint, vault, token accounts and token program")]
+    TokenAccountsRequired,
+    #[msg("Token account is not owned by the expected wallet")]
+    InvalidTokenAccount,
+    #[msg("Nonce does not match the outstanding check-in challenge")]
+    InvalidCheckInNonce,
+    #[msg("The check-in window for this nonce has closed")]
+    CheckInNonceExpired,
+    #[msg("Arbiter does not have the minimum stake")]
+    InsufficientArbitratorStake,
+    #[msg("Stake amount must be positive and no more than the staked balance")]
+    InvalidStakeAmount,
+    #[msg("Stake cannot be withdrawn while disputes are assigned")]
+    ArbitratorHasDisputes,
+    #[msg("The client's review window has not elapsed")]
+    ReviewWindowOpen,
+    #[msg("Review window is below the minimum")]
+    InvalidReviewWindow,
+    #[msg("There is nothing to claim")]
+    NothingToClaim,
+    #[msg("This job's payment schedule must be provided")]
+    ScheduleRequired,
+    #[msg("Work has already been submitted")]
+    WorkAlreadySubmitted,
+    #[msg("The job's end date and grace period have not passed")]
+    DeliveryDeadlineNotPassed,
+    #[msg("The freelancer defaulted on this application")]
+    ApplicationDefaulted,
+    #[msg("Roles must be a non-empty combination of the known role flags")]
+    InvalidRoles,
+    #[msg("A wallet cannot be both client and freelancer on the same job")]
+    SelfDealing,
+    #[msg("Close or settle open jobs and applications first")]
+    UserAccountInUse,
+    #[msg("Name is longer than 50 bytes")]
+    NameTooLong,
+    #[msg("The application cannot move to that status from its current one")]
+    InvalidApplicationTransition,
+    #[msg("Retainers need a positive period amount, period length and period count")]
+    InvalidRetainer,
+    #[msg("Hourly terms need both a rate and a maximum number of hours")]
+    InvalidHourlyTerms,
+    #[msg("This job is not an hourly contract")]
+    NotHourly,
+    #[msg("Certificate base uri is longer than 64 bytes")]
+    CertificateUriTooLong,
+    #[msg("A referrer has already been registered for this user")]
+    ReferralAlreadyRegistered,
+    #[msg("The referrer's account must be provided to pay their fee share")]
+    ReferrerAccountRequired,
+    #[msg("A job must allow at least one hire")]
+    InvalidMaxHires,
+    #[msg("All of this job's hire slots are filled")]
+    TeamFull,
+    #[msg("Team jobs do not support this")]
+    TeamJob,
+    #[msg("Signer is not an admin of this organization")]
+    NotOrganizationAdmin,
+    #[msg("The organization already has the maximum number of admins")]
+    OrganizationAdminsFull,
+    #[msg("An organization must keep at least one admin")]
+    LastOrganizationAdmin,
+    #[msg("Payout splits need up to 8 distinct recipients whose shares sum to 10000 bps")]
+    InvalidPayoutSplits,
+    #[msg("Payouts cannot be both split and routed to a vesting program")]
+    SplitsWithVesting,
+    #[msg("A payout split recipient's account was not provided")]
+    SplitRecipientMissing,
+    #[msg("Tip must be greater than zero")]
+    InvalidTip,
+    #[msg("The application has not been paid out")]
+    ApplicationNotPaid,
+    #[msg("Late penalty cannot exceed 10000 bps per day")]
+    InvalidLatePenalty,
+    #[msg("Upfront share cannot exceed 10000 bps")]
+    InvalidUpfrontBps,
+    #[msg("The job's escrow and the freelancer must be provided for the upfront payment")]
+    EscrowRequired,
+    #[msg("This job does not stream its escrow")]
+    NotStreaming,
+    #[msg("Streaming jobs cannot also be hourly or use milestones")]
+    StreamingJob,
+    #[msg("Memo is longer than 100 bytes")]
+    MemoTooLong,
+    #[msg("The time log is full; the client must approve the logged hours first")]
+    TimeLogFull,
+    #[msg("Logged hours would exceed the job's maximum")]
+    HoursExceedMax,
+    #[msg("The applicant has not posted the job's completion bond")]
+    BondNotPosted,
+    #[msg("The completion bond is already posted")]
+    BondAlreadyPosted,
+    #[msg("This job charges an application fee; pass the deposit account")]
+    ApplicationFeeRequired,
+    #[msg("The application fee stays deposited until a hire, cancellation or rejection")]
+    ApplicationFeeLocked,
+    #[msg("The application has not been rejected")]
+    ApplicationNotRejected,
+    #[msg("This job has reached its maximum number of applicants")]
+    ApplicationsClosed,
+    #[msg("This job is private and the applicant is not on its whitelist")]
+    NotWhitelisted,
+    #[msg("The job's whitelist is full")]
+    WhitelistFull,
+    #[msg("The applicant has not proposed a counter-offer")]
+    NoCounterOffer,
+    #[msg("No deadline extension has been requested")]
+    NoExtensionRequested,
+    #[msg("The remaining budget would fall below the minimum job amount")]
+    BudgetBelowMinimum,
+    #[msg("The job can no longer be edited because it has applications")]
+    JobHasApplications,
+    #[msg("Dates cannot change once a payment schedule is set")]
+    DatesLockedBySchedule,
+    #[msg("The job cannot move to that status from its current one")]
+    InvalidJobTransition,
+    #[msg("The job has no hired freelancer working on it")]
+    JobNotInProgress,
+    #[msg("The job is already completed")]
+    JobCompleted,
+    #[msg("Title is longer than 100 bytes")]
+    TitleTooLong,
+    #[msg("Description is longer than 500 bytes")]
+    DescriptionTooLong,
+    #[msg("Job amount must be positive")]
+    InvalidJobAmount,
+    #[msg("The job post account does not match the client's next job")]
+    InvalidJobAddress,
+    #[msg("Prices and USD values must be positive")]
+    InvalidPrice,
+    #[msg("The price feed has not been updated recently enough")]
+    StalePrice,
+    #[msg("The escrow is worth less than the required collateral")]
+    InsufficientCollateral,
+    #[msg("The escrow still covers the agreed value")]
+    CollateralSufficient,
+    #[msg("This job is not denominated in USD")]
+    NotUsdDenominated,
+    #[msg("The job's collateral must be topped up first")]
+    CollateralCallOutstanding,
+    #[msg("Trials must be positive and fit within the job budget when converting")]
+    InvalidTrial,
+    #[msg("The trial is not in the right state for this")]
+    InvalidTrialStatus,
+    #[msg("The trial deadline has passed")]
+    TrialExpired,
+    #[msg("The trial deadline has not passed yet")]
+    TrialNotExpired,
+    #[msg("Quotes must be for a positive amount")]
+    InvalidQuote,
+    #[msg("Ratings must be between 1 and 5")]
+    InvalidRating,
+    #[msg("This has already been reviewed")]
+    AlreadyReviewed,
+    #[msg("The application has not been paid yet")]
+    NotPaid,
+    #[msg("Accept the current protocol terms first")]
+    TermsNotAccepted,
+    #[msg("That is not the current terms version")]
+    TermsVersionMismatch,
+    #[msg("The client's job index is full")]
+    JobIndexFull,
+    #[msg("Too many tags; profiles take 10 skills and jobs 5 tags")]
+    TooManyTags,
+    #[msg("Tags are 1-32 lowercase letters, digits or inner hyphens")]
+    InvalidTag,
+    #[msg("Each tag can only be listed once")]
+    DuplicateTag,
+    #[msg("The freelancer has marked themselves unavailable")]
+    FreelancerUnavailable,
+    #[msg("Handles are 3-32 lowercase letters, digits or underscores")]
+    InvalidHandle,
+    #[msg("Boosting job posts is not enabled")]
+    BoostingDisabled,
+    #[msg("Boosts last between one second and 30 days")]
+    InvalidBoostDuration,
+    #[msg("Metadata URIs are ar://, ipfs:// or https:// links of at most 128 bytes")]
+    InvalidMetadataUri,
+    #[msg("No off-chain metadata is set")]
+    NoMetadata,
+    #[msg("The content does not match the recorded hash")]
+    ContentHashMismatch,
+    #[msg("Writes must start within the description written so far")]
+    DescriptionGap,
+    #[msg("Submission links are at most 200 bytes and narrations 300")]
+    SubmissionTooLong,
+    #[msg("Reviews are at most 300 bytes")]
+    ReviewTooLong,
+    #[msg("Only completed or cancelled jobs can be closed")]
+    JobNotFinished,
+    #[msg("Claim the job's token fees before closing it")]
+    TokenFeesUnclaimed,
+    #[msg("Settle the application fee before closing the application")]
+    ApplicationFeeUnsettled,
+    #[msg("The referral bounty must be claimed before closing the application")]
+    ReferralBountyUnclaimed,
+    #[msg("The escrow has already been released or refunded")]
+    EscrowNotFunded,
+    #[msg("Payouts can only go to the application's applicant")]
+    PayeeMismatch,
+    #[msg("The application belongs to a different job")]
+    ApplicationJobMismatch,
+    #[msg("Approver sets hold up to 5 distinct keys and a threshold of 1 to their count")]
+    InvalidApprovers,
+    #[msg("Only the job's approvers can approve its submissions")]
+    NotAnApprover,
+    #[msg("This approver has already approved the submission")]
+    DuplicateApproval,
+    #[msg("Delegate scopes must be a non-empty set of DELEGATE_* flags for another key")]
+    InvalidDelegateScope,
+    #[msg("The delegate is not authorized for this action")]
+    DelegateNotAuthorized,
+    #[msg("The delegate has expired")]
+    DelegateExpired,
+    #[msg("Delegates cannot take actions that move funds")]
+    DelegateCannotMoveFunds,
+    #[msg("The payout exceeds what the escrow was funded for")]
+    EscrowOverdrawn,
 }
+
+#[cfg(test)]
+mod tests;
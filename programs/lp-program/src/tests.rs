@@ -0,0 +1,227 @@
+use super::*;
+
+mod admin;
+mod application_fees;
+mod bonds;
+mod boosts;
+mod certificates;
+mod counter_offers;
+mod delegates;
+mod disputes;
+mod harness;
+mod hourly;
+mod invitations;
+mod job_edits;
+mod market;
+mod milestones;
+mod payouts;
+mod profiles;
+mod protocol_fees;
+mod retainers;
+mod streaming;
+
+fn error_code<T>(result: Result<T>) -> u32 {
+    match result {
+        Err(Error::AnchorError(error)) => error.error_code_number,
+        Err(error) => panic!("unexpected error: {error}"),
+        Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+fn application_stage_transitions() {
+    use ApplicationStage::*;
+    let all = [Applied, Screened, Interviewed, Offered, Hired, Rejected];
+    for next in all {
+        assert!(!Hired.can_transition_to(next));
+        assert!(!Rejected.can_transition_to(next));
+        assert!(!next.can_transition_to(Applied));
+        assert!(!next.can_transition_to(Hired));
+    }
+    assert!(Applied.can_transition_to(Offered));
+    assert!(Screened.can_transition_to(Interviewed));
+    assert!(Offered.can_transition_to(Rejected));
+    assert!(!Interviewed.can_transition_to(Screened));
+}
+
+#[test]
+fn display_text_accepts_single_script_names() {
+    assert!(validate_display_text("Ada Lovelace").is_ok());
+    assert!(validate_display_text("Иван Петров").is_ok());
+    assert!(validate_display_text("Ζωή").is_ok());
+}
+
+#[test]
+fn display_text_rejects_impersonation() {
+    let cases = [
+        ("", ErrorCode::EmptyText),
+        (" Ada", ErrorCode::SurroundingWhitespace),
+        ("Ada\n", ErrorCode::SurroundingWhitespace),
+        ("Ad\u{0007}a", ErrorCode::ControlCharacters),
+        ("Ad\u{200B}a", ErrorCode::ControlCharacters),
+        ("P\u{0430}ypal", ErrorCode::MixedScripts),
+        ("\u{0430}\u{0440}\u{0435}", ErrorCode::ConfusableText),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(
+            error_code(validate_display_text(text)),
+            u32::from(expected),
+            "{text:?}"
+        );
+    }
+}
+
+#[test]
+fn bps_of_rounds_down_without_overflow() {
+    assert_eq!(bps_of(10_000, 250), 250);
+    assert_eq!(bps_of(999, 100), 9);
+    assert_eq!(bps_of(1_000, 0), 0);
+    assert_eq!(bps_of(u64::MAX, BPS_DENOMINATOR as u16), u64::MAX);
+}
+
+#[test]
+fn job_status_transitions() {
+    use JobStatus::*;
+    let all = [Open, Filled, InProgress, Completed, Cancelled, Disputed];
+    for next in all {
+        assert!(!Completed.can_transition_to(next));
+        assert!(!Cancelled.can_transition_to(next));
+    }
+    assert!(Open.can_transition_to(InProgress));
+    assert!(Filled.can_transition_to(Open));
+    assert!(InProgress.can_transition_to(Disputed));
+    assert!(Disputed.can_transition_to(Completed));
+    assert!(Disputed.can_transition_to(Cancelled));
+    assert!(!Open.can_transition_to(Completed));
+    assert!(!Disputed.can_transition_to(InProgress));
+    assert!(!InProgress.can_transition_to(Open));
+}
+
+#[test]
+fn application_status_transitions() {
+    use ApplicationStatus::*;
+    let all = [
+        Pending, Approved, Rejected, Submitted, Paid, Withdrawn, Disputed,
+    ];
+    for next in all {
+        assert!(!Paid.can_transition_to(next));
+        assert!(!Withdrawn.can_transition_to(next));
+    }
+    assert!(Pending.can_transition_to(Approved));
+    assert!(Rejected.can_transition_to(Withdrawn));
+    // The next tranche sends a paid-out submission back to Approved
+    assert!(Submitted.can_transition_to(Approved));
+    assert!(Submitted.can_transition_to(Disputed));
+    assert!(Disputed.can_transition_to(Paid));
+    assert!(!Pending.can_transition_to(Submitted));
+    assert!(!Approved.can_transition_to(Paid));
+    assert!(!Disputed.can_transition_to(Approved));
+}
+
+#[test]
+fn job_index_lists_jobs_until_cancelled() {
+    let mut index = JobIndex {
+        client: Pubkey::new_unique(),
+        jobs: Vec::new(),
+        bump: 0,
+    };
+    let jobs: Vec<Pubkey> = (0..MAX_INDEXED_JOBS)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    for job in &jobs {
+        index.add(*job).unwrap();
+    }
+    assert_eq!(
+        error_code(index.add(Pubkey::new_unique())),
+        u32::from(ErrorCode::JobIndexFull)
+    );
+    index.remove(&jobs[1]);
+    assert_eq!(index.jobs.len(), MAX_INDEXED_JOBS - 1);
+    assert_eq!(index.jobs[1], jobs[2]);
+    index.add(jobs[1]).unwrap();
+}
+
+#[test]
+fn tags_are_validated() {
+    let tags = |tags: &[&str]| tags.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    assert!(validate_tags(&tags(&["rust", "ui-design", "web3"]), MAX_SKILLS).is_ok());
+    assert!(validate_tags(&[], MAX_SKILLS).is_ok());
+    let cases = [
+        (tags(&["Rust"]), ErrorCode::InvalidTag),
+        (tags(&[""]), ErrorCode::InvalidTag),
+        (tags(&["-rust"]), ErrorCode::InvalidTag),
+        (tags(&["ui design"]), ErrorCode::InvalidTag),
+        (tags(&[&"a".repeat(MAX_TAG_LEN + 1)]), ErrorCode::InvalidTag),
+        (tags(&["rust", "go", "rust"]), ErrorCode::DuplicateTag),
+        (tags(&["a"; MAX_SKILLS + 1]), ErrorCode::TooManyTags),
+    ];
+    for (list, expected) in cases {
+        assert_eq!(
+            error_code(validate_tags(&list, MAX_SKILLS)),
+            u32::from(expected),
+            "{list:?}"
+        );
+    }
+    let six = tags(&["a", "b", "c", "d", "e", "f"]);
+    assert!(validate_tags(&six, MAX_SKILLS).is_ok());
+    assert_eq!(
+        error_code(validate_tags(&six, MAX_JOB_TAGS)),
+        u32::from(ErrorCode::TooManyTags)
+    );
+}
+
+#[test]
+fn metadata_uris_and_hashes_are_checked() {
+    assert!(validate_metadata_uri("ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U").is_ok());
+    assert!(validate_metadata_uri(
+        "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+    )
+    .is_ok());
+    let long = format!("https://{}", "a".repeat(MAX_METADATA_URI_LEN));
+    for uri in [
+        "",
+        "ipfs://",
+        "ftp://example.com/job.md",
+        "example.com",
+        &long,
+    ] {
+        assert_eq!(
+            error_code(validate_metadata_uri(uri)),
+            u32::from(ErrorCode::InvalidMetadataUri),
+            "{uri:?}"
+        );
+    }
+
+    let content = ContentRef {
+        uri: "ar://job".to_string(),
+        content_hash: [7; 32],
+    };
+    assert!(content.verify(&[7; 32]).is_ok());
+    assert_eq!(
+        error_code(content.verify(&[8; 32])),
+        u32::from(ErrorCode::ContentHashMismatch)
+    );
+}
+
+#[test]
+fn large_descriptions_are_written_in_chunks() {
+    let mut large = JobPostLarge {
+        job_post: Pubkey::new_unique(),
+        description_len: 0,
+        description: [0; MAX_LARGE_DESCRIPTION_LEN],
+    };
+    large.write(0, &[b'a'; 1000]).unwrap();
+    large.write(1000, &[b'b'; 1000]).unwrap();
+    assert_eq!(large.text().len(), 2000);
+    assert_eq!(
+        error_code(large.write(2001, b"c")),
+        u32::from(ErrorCode::DescriptionGap)
+    );
+    assert_eq!(
+        error_code(large.write(MAX_LARGE_DESCRIPTION_LEN - 1, b"cc")),
+        u32::from(ErrorCode::DescriptionTooLong)
+    );
+    // Rewriting from an earlier offset truncates what followed
+    large.write(10, b"end").unwrap();
+    assert_eq!(large.text(), b"aaaaaaaaaaend");
+}
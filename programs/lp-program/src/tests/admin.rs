@@ -0,0 +1,69 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+#[test]
+fn admin_instructions_cannot_be_invoked_through_cpi() {
+    let mut svm = Svm::new();
+    let authority = svm.wallet();
+    svm.set_upgrade_authority(authority);
+    let initialize = instruction(
+        accounts::InitializeArbitratorRegistry {
+            arbitrator_registry: registry(),
+            signer: authority,
+            program: crate::ID,
+            program_data: Svm::program_data_address(),
+            system_program: system_program::ID,
+        },
+        instruction::InitializeArbitratorRegistry {},
+    );
+    assert_eq!(
+        failure(svm.send_as_cpi(initialize.clone())),
+        u32::from(ErrorCode::CpiNotAllowed)
+    );
+    assert!(svm.get(&registry()).is_none());
+    svm.send(initialize).unwrap();
+
+    let mut market = Market::new();
+    let wallet = market.register(UserRole::Client);
+    let grant = instruction(
+        accounts::GrantArbitratorRole {
+            arbitrator_registry: registry(),
+            user_account: user(wallet),
+            authority: market.authority,
+        },
+        instruction::GrantArbitratorRole {},
+    );
+    assert_eq!(
+        failure(market.svm.send_as_cpi(grant.clone())),
+        u32::from(ErrorCode::CpiNotAllowed)
+    );
+    market.svm.send(grant).unwrap();
+    assert!(market
+        .svm
+        .account::<UserAccount>(&user(wallet))
+        .has_role(UserRole::Arbitrator));
+}
+
+#[test]
+fn pausing_freezes_state_changes_until_resumed() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+
+    market.set_pause(true);
+    assert!(market.svm.account::<Config>(&config()).paused);
+    assert_eq!(
+        failure(market.try_apply(&job, freelancer)),
+        u32::from(ErrorCode::ProgramPaused)
+    );
+    assert!(market.svm.get(&application(&job, freelancer)).is_none());
+
+    market.set_pause(false);
+    let application = market.apply(&job, freelancer);
+    assert_eq!(
+        market.svm.account::<Application>(&application).applicant,
+        freelancer
+    );
+}
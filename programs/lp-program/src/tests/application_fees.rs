@@ -0,0 +1,77 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+const FEE: u64 = SOL / 10;
+
+// A job charging FEE to apply, and an application that paid it
+fn paid_application() -> (Market, Job, Pubkey, Pubkey) {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    market.set_application_fee(&job, FEE);
+    let application = market.apply(&job, freelancer);
+    (market, job, freelancer, application)
+}
+
+#[test]
+fn application_fee_is_held_in_a_deposit() {
+    let (market, _, _, application) = paid_application();
+    let deposit = deposit(application);
+    let rent = Rent::default().minimum_balance(market.svm.data_len(&deposit));
+    assert_eq!(market.svm.lamports(&deposit), FEE + rent);
+    assert_eq!(
+        market
+            .svm
+            .account::<Application>(&application)
+            .application_fee,
+        FEE
+    );
+}
+
+#[test]
+fn hired_applicant_is_refunded_the_fee() {
+    let (mut market, job, freelancer, application) = paid_application();
+    assert_eq!(
+        failure(market.refund_application_fee(&job, application, freelancer)),
+        u32::from(ErrorCode::ApplicationFeeLocked)
+    );
+
+    market.hire(&job, application);
+    let held = market.svm.lamports(&deposit(application));
+    let before = market.svm.lamports(&freelancer);
+    market
+        .refund_application_fee(&job, application, freelancer)
+        .unwrap();
+    assert_eq!(market.svm.lamports(&freelancer) - before, held);
+    assert!(market.svm.get(&deposit(application)).is_none());
+    assert_eq!(
+        market
+            .svm
+            .account::<Application>(&application)
+            .application_fee,
+        0
+    );
+}
+
+#[test]
+fn rejected_applicant_forfeits_the_fee_but_keeps_the_rent() {
+    let (mut market, job, freelancer, application) = paid_application();
+    assert_eq!(
+        failure(market.forfeit_application_fee(&job, application)),
+        u32::from(ErrorCode::ApplicationNotRejected)
+    );
+
+    market.reject(&job, application);
+    let held = market.svm.lamports(&deposit(application));
+    let client_before = market.svm.lamports(&job.client);
+    let freelancer_before = market.svm.lamports(&freelancer);
+    market.forfeit_application_fee(&job, application).unwrap();
+    assert_eq!(market.svm.lamports(&job.client) - client_before, FEE);
+    assert_eq!(
+        market.svm.lamports(&freelancer) - freelancer_before,
+        held - FEE
+    );
+    assert!(market.svm.get(&deposit(application)).is_none());
+}
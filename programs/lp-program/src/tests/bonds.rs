@@ -0,0 +1,98 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+const BOND: u64 = SOL / 4;
+
+// The application's rent for its current size, plus whatever bond it holds
+fn application_balance(market: &Market, application: Pubkey, bond: u64) -> u64 {
+    Rent::default().minimum_balance(market.svm.data_len(&application)) + bond
+}
+
+#[test]
+fn bond_is_returned_with_the_payout_and_survives_resizes() {
+    let mut market = Market::new();
+    market.set_max_revisions(1);
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    market.set_completion_bond(&job, BOND);
+    let application = market.apply(&job, freelancer);
+    let accounts = market.hire_accounts(&job, application);
+    assert_eq!(
+        failure(
+            market
+                .svm
+                .send(instruction(accounts, instruction::ApproveApplication {}))
+        ),
+        u32::from(ErrorCode::BondNotPosted)
+    );
+    market.post_bond(&job, application);
+    market.hire(&job, application);
+    assert_eq!(
+        market.svm.lamports(&application),
+        application_balance(&market, application, BOND)
+    );
+
+    // Growing and then shrinking the submission moves only rent
+    let long_link = format!("https://example.com/{}", "w".repeat(150));
+    market.submit_link(&job, application, &long_link);
+    assert_eq!(
+        market.svm.lamports(&application),
+        application_balance(&market, application, BOND)
+    );
+    market.request_revision(&job, application);
+    market.submit(&job, application);
+    assert_eq!(
+        market.svm.lamports(&application),
+        application_balance(&market, application, BOND)
+    );
+
+    let balance = market.svm.lamports(&freelancer);
+    let accounts = market.approval_accounts(&job, application);
+    market
+        .review(accounts, &"Thorough and on time. ".repeat(10), 5)
+        .unwrap();
+    let paid = market.svm.account::<Application>(&application);
+    assert_eq!(paid.status, ApplicationStatus::Paid);
+    assert_eq!(paid.bond_posted, 0);
+    assert_eq!(market.svm.lamports(&freelancer), balance + SOL + BOND);
+    assert_eq!(
+        market.svm.lamports(&application),
+        application_balance(&market, application, 0)
+    );
+}
+
+#[test]
+fn defaulting_forfeits_the_bond_to_the_client() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    market.set_completion_bond(&job, BOND);
+    let application = market.apply(&job, freelancer);
+    market.post_bond(&job, application);
+    market.hire(&job, application);
+
+    assert_eq!(
+        failure(market.reclaim(&job, application)),
+        u32::from(ErrorCode::DeliveryDeadlineNotPassed)
+    );
+    let end_date = market.svm.account::<JobPost>(&job.post).end_date;
+    market.svm.advance(end_date - market.svm.now() + 1);
+    let (client_balance, freelancer_balance) = (
+        market.svm.lamports(&client),
+        market.svm.lamports(&freelancer),
+    );
+    market.reclaim(&job, application).unwrap();
+
+    let defaulted = market.svm.account::<Application>(&application);
+    assert!(defaulted.defaulted);
+    assert_eq!(defaulted.bond_posted, 0);
+    assert_eq!(market.svm.lamports(&client), client_balance + SOL + BOND);
+    assert_eq!(market.svm.lamports(&freelancer), freelancer_balance);
+    assert_eq!(
+        market.svm.lamports(&application),
+        application_balance(&market, application, 0)
+    );
+}
@@ -0,0 +1,42 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+const DAY: i64 = SECONDS_PER_DAY;
+
+#[test]
+fn boosts_are_paid_per_started_day_and_stack() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let job = market.post_job(client, SOL);
+    assert_eq!(
+        failure(market.boost(&job, DAY)),
+        u32::from(ErrorCode::BoostingDisabled)
+    );
+
+    market.send(
+        accounts::UpdateConfig {
+            config: config(),
+            authority: market.authority,
+        },
+        instruction::SetBoostFee {
+            boost_fee_per_day: 1_000_000,
+        },
+    );
+    let balance = market.svm.lamports(&client);
+    market.boost(&job, DAY + 1).unwrap();
+    market.boost(&job, DAY).unwrap();
+    assert_eq!(market.svm.lamports(&client), balance - 3_000_000);
+    assert_eq!(
+        market.svm.account::<Config>(&config()).accrued_fees,
+        3_000_000
+    );
+    assert_eq!(
+        market.svm.account::<JobPost>(&job.post).featured_until,
+        market.svm.now() + 2 * DAY + 1
+    );
+    assert_eq!(
+        failure(market.boost(&job, 31 * DAY)),
+        u32::from(ErrorCode::InvalidBoostDuration)
+    );
+}
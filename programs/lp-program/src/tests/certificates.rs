@@ -0,0 +1,105 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::metadata::mpl_token_metadata::instructions::{
+    CreateMasterEditionV3InstructionArgs, CreateMetadataAccountV3InstructionArgs,
+};
+use anchor_spl::metadata::mpl_token_metadata::types::Creator;
+use anchor_spl::token::spl_token;
+
+fn metadata_pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &TOKEN_METADATA_PROGRAM_ID).0
+}
+
+#[test]
+fn certificates_record_the_job_amount_and_client_in_their_metadata() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    market.submit(&job, application);
+    market.approve(&job, application).unwrap();
+    market.send(
+        accounts::UpdateConfig {
+            config: config(),
+            authority: market.authority,
+        },
+        instruction::SetCertificateBaseUri {
+            base_uri: "https://lp.example/certificates".to_string(),
+        },
+    );
+
+    let mint = pda(&[b"certificate", application.as_ref()]);
+    let authority = pda(&[b"certificate_authority"]);
+    let metadata_seeds = [
+        b"metadata".as_ref(),
+        TOKEN_METADATA_PROGRAM_ID.as_ref(),
+        mint.as_ref(),
+    ];
+    let metadata = metadata_pda(&metadata_seeds);
+    let master_edition = metadata_pda(&[&metadata_seeds[..], &[b"edition"]].concat());
+    let token_account = get_associated_token_address(&freelancer, &mint);
+    take_metadata_cpis();
+    market.send(
+        accounts::MintCompletionCertificate {
+            application,
+            job_post: job.post,
+            freelancer,
+            certificate_mint: mint,
+            freelancer_token_account: token_account,
+            certificate_authority: authority,
+            metadata,
+            master_edition,
+            token_metadata_program: TOKEN_METADATA_PROGRAM_ID,
+            config: config(),
+            token_program: spl_token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+            rent: anchor_lang::solana_program::sysvar::rent::ID,
+        },
+        instruction::MintCompletionCertificate {},
+    );
+    assert_eq!(market.svm.token_balance(&token_account), 1);
+
+    let cpis = take_metadata_cpis();
+    assert_eq!(cpis.len(), 2);
+    let (discriminator, args) = cpis[0].data.split_first().unwrap();
+    assert_eq!(*discriminator, 33);
+    let args = CreateMetadataAccountV3InstructionArgs::try_from_slice(args).unwrap();
+    assert_eq!(cpis[0].accounts[0].pubkey, metadata);
+    assert!(!args.is_mutable);
+    assert_eq!(args.data.name, format!("Landing page #{SOL}"));
+    assert_eq!(args.data.symbol, CERTIFICATE_SYMBOL);
+    assert_eq!(
+        args.data.creators.unwrap(),
+        [
+            Creator {
+                address: authority,
+                verified: true,
+                share: 0,
+            },
+            Creator {
+                address: client,
+                verified: false,
+                share: 100,
+            },
+        ]
+    );
+
+    let (discriminator, args) = cpis[1].data.split_first().unwrap();
+    assert_eq!(*discriminator, 17);
+    let args = CreateMasterEditionV3InstructionArgs::try_from_slice(args).unwrap();
+    assert_eq!(cpis[1].accounts[0].pubkey, master_edition);
+    assert_eq!(args.max_supply, Some(0));
+}
+
+#[test]
+fn certificate_names_keep_the_amount_within_the_limit() {
+    let name = certificate_name(&"Long title ".repeat(5), 123_456_789);
+    assert_eq!(name.len(), MAX_CERTIFICATE_NAME_LEN);
+    assert!(name.ends_with(" #123456789"));
+    assert_eq!(certificate_name("Logo", 5), "Logo #5");
+}
@@ -0,0 +1,72 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+fn relationship_rent(market: &Market, client: Pubkey, freelancer: Pubkey) -> u64 {
+    let relationship = WorkRelationship::address(client, freelancer).0;
+    Rent::default().minimum_balance(market.svm.data_len(&relationship))
+}
+
+#[test]
+fn accepting_a_higher_counter_offer_tops_up_the_escrow() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let application = market.apply(&job, freelancer);
+    market
+        .propose_counter_offer(&job, application, 3 * SOL / 2)
+        .unwrap();
+
+    let client_before = market.svm.lamports(&client);
+    let escrow_before = market.svm.lamports(&job.escrow);
+    market.accept_counter_offer(&job, application).unwrap();
+
+    assert_eq!(market.svm.lamports(&job.escrow) - escrow_before, SOL / 2);
+    assert_eq!(
+        client_before - market.svm.lamports(&client),
+        SOL / 2 + relationship_rent(&market, client, freelancer)
+    );
+    assert_eq!(market.svm.account::<JobPost>(&job.post).amount, 3 * SOL / 2);
+    let application = market.svm.account::<Application>(&application);
+    assert_eq!(application.status, ApplicationStatus::Approved);
+    assert_eq!(application.proposed_amount, 0);
+}
+
+#[test]
+fn accepting_a_lower_counter_offer_refunds_the_client() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let application = market.apply(&job, freelancer);
+    market
+        .propose_counter_offer(&job, application, 3 * SOL / 5)
+        .unwrap();
+
+    let client_before = market.svm.lamports(&client);
+    let escrow_before = market.svm.lamports(&job.escrow);
+    market.accept_counter_offer(&job, application).unwrap();
+
+    assert_eq!(
+        escrow_before - market.svm.lamports(&job.escrow),
+        2 * SOL / 5
+    );
+    assert_eq!(
+        market.svm.lamports(&client) - client_before,
+        2 * SOL / 5 - relationship_rent(&market, client, freelancer)
+    );
+}
+
+#[test]
+fn accepting_requires_a_proposal() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let application = market.apply(&job, freelancer);
+    assert_eq!(
+        failure(market.accept_counter_offer(&job, application)),
+        u32::from(ErrorCode::NoCounterOffer)
+    );
+}
@@ -0,0 +1,65 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+const HOUR: i64 = 60 * 60;
+
+#[test]
+fn session_keys_hire_within_scope_until_expiry() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let application = market.apply(&job, freelancer);
+
+    let revisions_only =
+        market.create_delegate(client, DELEGATE_REQUEST_REVISION, market.svm.now() + HOUR);
+    assert_eq!(
+        failure(market.delegated_hire(&job, application, revisions_only)),
+        u32::from(ErrorCode::DelegateNotAuthorized)
+    );
+
+    let session_key = market.create_delegate(
+        client,
+        DELEGATE_APPROVE_APPLICATION,
+        market.svm.now() + HOUR,
+    );
+    market.svm.advance(HOUR);
+    assert_eq!(
+        failure(market.delegated_hire(&job, application, session_key)),
+        u32::from(ErrorCode::DelegateExpired)
+    );
+
+    let session_key = market.create_delegate(client, DELEGATE_SCOPES, market.svm.now() + HOUR);
+    market
+        .delegated_hire(&job, application, session_key)
+        .unwrap();
+    assert!(market.svm.account::<Application>(&application).is_hired());
+}
+
+#[test]
+fn revoked_session_keys_stop_working() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let application = market.apply(&job, freelancer);
+    let session_key = market.create_delegate(client, DELEGATE_SCOPES, market.svm.now() + HOUR);
+
+    let record = delegate(client, session_key);
+    let balance = market.svm.lamports(&client);
+    let rent = market.svm.lamports(&record);
+    market.send(
+        accounts::RevokeDelegate {
+            delegate: record,
+            principal: client,
+        },
+        instruction::RevokeDelegate {},
+    );
+    assert!(market.svm.get(&record).is_none());
+    assert_eq!(market.svm.lamports(&client), balance + rent);
+    assert_eq!(
+        failure(market.delegated_hire(&job, application, session_key)),
+        anchor_lang::error::ErrorCode::AccountNotInitialized as u32
+    );
+}
@@ -0,0 +1,296 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+// A token job with a hired freelancer and a dispute awaiting its ruling
+struct TokenDispute {
+    market: Market,
+    job: Job,
+    application: Pubkey,
+    payees: Payees,
+}
+
+fn token_dispute() -> TokenDispute {
+    token_dispute_charging(0, 0)
+}
+
+fn token_dispute_charging(client_fee_bps: u16, freelancer_fee_bps: u16) -> TokenDispute {
+    let mut market = Market::new();
+    market.set_fee(client_fee_bps, freelancer_fee_bps);
+    market.set_arbitration_policy(0, 500);
+    let arbiter = market.arbitrator(SOL);
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+
+    let mint = market.svm.create_mint(6);
+    let client_tokens = market.svm.token_account(mint, client, 5_000);
+    let job = market.post_token_job(client, mint, client_tokens, 1_000);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+
+    let dispute = market.open_dispute(&job, application, client);
+    market.respond(dispute, freelancer);
+    let payees = Payees {
+        freelancer: market.svm.token_account(mint, freelancer, 0),
+        client: client_tokens,
+        arbiter: market.svm.token_account(mint, arbiter, 0),
+    };
+    TokenDispute {
+        market,
+        job,
+        application,
+        payees,
+    }
+}
+
+#[test]
+fn token_dispute_releases_the_vault_to_the_freelancer() {
+    let TokenDispute {
+        mut market,
+        job,
+        application,
+        payees,
+    } = token_dispute();
+    market
+        .resolve(
+            &job,
+            application,
+            DisputeRuling::ReleaseToFreelancer,
+            Some(&payees),
+        )
+        .unwrap();
+
+    let svm = &market.svm;
+    assert_eq!(svm.token_balance(&payees.freelancer), 950);
+    assert_eq!(svm.token_balance(&payees.arbiter), 50);
+    assert_eq!(svm.token_balance(&payees.client), 4_000);
+    assert_eq!(svm.token_balance(&job.vault.unwrap()), 0);
+    assert_eq!(
+        svm.account::<JobPost>(&job.post).status,
+        JobStatus::Completed
+    );
+    let paid = svm.account::<Application>(&application);
+    assert_eq!(paid.status, ApplicationStatus::Paid);
+    // Lifetime totals are kept in lamports only
+    assert_eq!(
+        svm.account::<Reputation>(&reputation(paid.applicant))
+            .total_earned,
+        0
+    );
+    let history = svm.account::<WorkHistory>(&work_history(paid.applicant));
+    assert_eq!(history.completed_jobs, 1);
+    assert_eq!(history.total_earned, 0);
+    assert_eq!(
+        history.entries,
+        [WorkHistoryEntry {
+            job_post: job.post,
+            mint: job.mint,
+            amount: 950,
+            paid_at: svm.now(),
+            completed: true,
+        }]
+    );
+    assert!(svm.account::<Dispute>(&dispute(application)).status == DisputeStatus::Resolved);
+}
+
+#[test]
+fn token_dispute_refunds_the_client() {
+    let TokenDispute {
+        mut market,
+        job,
+        application,
+        payees,
+    } = token_dispute();
+    market
+        .resolve(
+            &job,
+            application,
+            DisputeRuling::RefundClient,
+            Some(&payees),
+        )
+        .unwrap();
+
+    let svm = &market.svm;
+    assert_eq!(svm.token_balance(&payees.client), 4_950);
+    assert_eq!(svm.token_balance(&payees.arbiter), 50);
+    assert_eq!(svm.token_balance(&payees.freelancer), 0);
+    assert_eq!(svm.token_balance(&job.vault.unwrap()), 0);
+    assert_eq!(
+        svm.account::<JobPost>(&job.post).status,
+        JobStatus::Cancelled
+    );
+    assert_eq!(
+        svm.account::<Escrow>(&job.escrow).status,
+        EscrowStatus::Refunded
+    );
+    assert!(svm.account::<Dispute>(&dispute(application)).status == DisputeStatus::Resolved);
+}
+
+#[test]
+fn token_dispute_release_accrues_both_fee_sides_in_the_vault() {
+    let TokenDispute {
+        mut market,
+        job,
+        application,
+        payees,
+    } = token_dispute_charging(200, 500);
+    market
+        .resolve(
+            &job,
+            application,
+            DisputeRuling::ReleaseToFreelancer,
+            Some(&payees),
+        )
+        .unwrap();
+
+    // 950 after the arbiter's cut, less 5% rounded down; the client paid 2% on top
+    let svm = &market.svm;
+    assert_eq!(svm.token_balance(&payees.freelancer), 903);
+    assert_eq!(svm.token_balance(&payees.arbiter), 50);
+    assert_eq!(svm.token_balance(&payees.client), 3_980);
+    assert_eq!(svm.token_balance(&job.vault.unwrap()), 47 + 20);
+    assert_eq!(
+        svm.account::<JobPost>(&job.post).accrued_token_fees,
+        47 + 20
+    );
+    assert_eq!(svm.account::<Application>(&application).fees_paid, 47);
+}
+
+#[test]
+fn lamport_dispute_release_charges_both_fee_sides() {
+    let mut market = Market::new();
+    market.set_fee(200, 500);
+    market.arbitrator(SOL);
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    let dispute = market.open_dispute(&job, application, client);
+    market.respond(dispute, freelancer);
+
+    let (balance, config_balance) = (
+        market.svm.lamports(&freelancer),
+        market.svm.lamports(&config()),
+    );
+    market
+        .resolve(&job, application, DisputeRuling::ReleaseToFreelancer, None)
+        .unwrap();
+    let fees = SOL / 20 + SOL / 50;
+    assert_eq!(market.svm.lamports(&freelancer), balance + SOL - SOL / 20);
+    assert_eq!(market.svm.lamports(&config()), config_balance + fees);
+    assert_eq!(market.svm.account::<Config>(&config()).accrued_fees, fees);
+
+    // The client-side fee has been collected, so there is nothing left to sweep
+    assert_eq!(
+        failure(market.sweep(&job, Some(application))),
+        u32::from(ErrorCode::NoExcessFunds)
+    );
+}
+
+#[test]
+fn token_dispute_requires_token_accounts() {
+    let TokenDispute {
+        mut market,
+        job,
+        application,
+        ..
+    } = token_dispute();
+    assert_eq!(
+        failure(market.resolve(&job, application, DisputeRuling::ReleaseToFreelancer, None)),
+        u32::from(ErrorCode::TokenAccountsRequired)
+    );
+}
+
+// Each team hire is disputed on its own; the job completes with the last one
+#[test]
+fn team_disputes_settle_per_hire() {
+    let mut market = Market::new();
+    market.arbitrator(SOL);
+    let client = market.register(UserRole::Client);
+    let job = market.post_job(client, 4 * SOL);
+    market.set_max_hires(&job, 4);
+    let hires: Vec<_> = (0..2)
+        .map(|_| {
+            let freelancer = market.register(UserRole::Freelancer);
+            (freelancer, market.apply(&job, freelancer))
+        })
+        .collect();
+    for &(freelancer, application) in &hires {
+        market.hire(&job, application);
+        let dispute = market.open_dispute(&job, application, client);
+        market.respond(dispute, freelancer);
+    }
+    let job_post = market.svm.account::<JobPost>(&job.post);
+    assert_eq!(job_post.status, JobStatus::InProgress);
+    assert_eq!(job_post.open_disputes, 2);
+
+    let client_before = market.svm.lamports(&client);
+    let (_, refunded) = hires[0];
+    market
+        .resolve(&job, refunded, DisputeRuling::RefundClient, None)
+        .unwrap();
+    assert_eq!(market.svm.lamports(&client) - client_before, SOL);
+    let job_post = market.svm.account::<JobPost>(&job.post);
+    assert_eq!(job_post.status, JobStatus::InProgress);
+    assert_eq!(job_post.open_disputes, 1);
+
+    // The last hire's settlement also returns the two slots never filled
+    let client_before = market.svm.lamports(&client);
+    let (freelancer, released) = hires[1];
+    let freelancer_before = market.svm.lamports(&freelancer);
+    market
+        .resolve(&job, released, DisputeRuling::ReleaseToFreelancer, None)
+        .unwrap();
+    assert_eq!(market.svm.lamports(&freelancer) - freelancer_before, SOL);
+    assert_eq!(market.svm.lamports(&client) - client_before, 2 * SOL);
+    let job_post = market.svm.account::<JobPost>(&job.post);
+    assert_eq!(job_post.status, JobStatus::Completed);
+    assert_eq!(job_post.open_disputes, 0);
+}
+
+#[test]
+fn settled_token_job_closes_after_its_application() {
+    let TokenDispute {
+        mut market,
+        job,
+        application,
+        payees,
+    } = token_dispute();
+    market
+        .resolve(
+            &job,
+            application,
+            DisputeRuling::ReleaseToFreelancer,
+            Some(&payees),
+        )
+        .unwrap();
+    assert_eq!(
+        failure(market.close_job(&job)),
+        u32::from(ErrorCode::JobHasApplications)
+    );
+
+    let freelancer = market.svm.account::<Application>(&application).applicant;
+    let rent = market.svm.lamports(&application);
+    let balance = market.svm.lamports(&freelancer);
+    market.close_application(&job, application).unwrap();
+    assert!(market.svm.get(&application).is_none());
+    assert_eq!(market.svm.lamports(&freelancer), balance + rent);
+
+    let vault = job.vault.unwrap();
+    let reclaimed = [job.post, job.escrow, vault]
+        .iter()
+        .map(|key| market.svm.lamports(key))
+        .sum::<u64>();
+    let balance = market.svm.lamports(&job.client);
+    market.close_job(&job).unwrap();
+    for key in [job.post, job.escrow, vault] {
+        assert!(market.svm.get(&key).is_none());
+    }
+    assert_eq!(market.svm.lamports(&job.client), balance + reclaimed);
+    assert!(market
+        .svm
+        .account::<JobIndex>(&job_index(job.client))
+        .jobs
+        .is_empty());
+}
@@ -0,0 +1,480 @@
+// Runs instructions through the program's entrypoint against accounts held in
+// memory. System, token and associated token CPIs execute in place, Token
+// Metadata CPIs are only recorded, and each test sets its own clock, which is
+// enough to follow funds through whole job flows.
+
+use crate::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::entrypoint::{self, ProgramResult, MAX_PERMITTED_DATA_INCREASE};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::spl_associated_token_account;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Once;
+
+pub const SOL: u64 = 1_000_000_000;
+
+thread_local! {
+    static NOW: Cell<i64> = const { Cell::new(0) };
+    static STACK_HEIGHT: Cell<usize> = const { Cell::new(TRANSACTION_LEVEL_STACK_HEIGHT) };
+    static CALLEE: Cell<Pubkey> = const { Cell::new(Pubkey::new_from_array([0; 32])) };
+    static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+    static METADATA_CPIS: RefCell<Vec<Instruction>> = const { RefCell::new(Vec::new()) };
+}
+
+// The stubs are process-wide, so the state they serve is kept per test thread
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            unix_timestamp: NOW.get(),
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        0
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        STACK_HEIGHT.get() as u64
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_in_place(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA.set(Some((CALLEE.get(), data.to_vec())));
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with_borrow(Clone::clone)
+    }
+}
+
+fn install_stubs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+}
+
+// Privileges carry over from the caller, plus signatures for the PDAs it signs for
+fn invoke_in_place(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let caller = CALLEE.get();
+    let signed: Vec<Pubkey> = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller).unwrap())
+        .collect();
+    let mut accounts = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if meta.is_signer && !info.is_signer && !signed.contains(&meta.pubkey) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if meta.is_writable && !info.is_writable {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut info = info.clone();
+        info.is_signer = meta.is_signer;
+        info.is_writable = meta.is_writable;
+        accounts.push(info);
+    }
+
+    CALLEE.set(instruction.program_id);
+    let program_id = &instruction.program_id;
+    let result = if *program_id == system_program::ID {
+        process_system(&accounts, &instruction.data)
+    } else if *program_id == spl_token::ID {
+        spl_token::processor::Processor::process(program_id, &accounts, &instruction.data)
+    } else if *program_id == spl_token_2022::ID {
+        spl_token_2022::processor::Processor::process(program_id, &accounts, &instruction.data)
+    } else if *program_id == spl_associated_token_account::ID {
+        spl_associated_token_account::processor::process_instruction(
+            program_id,
+            &accounts,
+            &instruction.data,
+        )
+    } else if *program_id == TOKEN_METADATA_PROGRAM_ID {
+        METADATA_CPIS.with_borrow_mut(|cpis| cpis.push(instruction.clone()));
+        Ok(())
+    } else {
+        panic!("no processor for CPI into {program_id}")
+    };
+    CALLEE.set(caller);
+    result
+}
+
+// Token Metadata instructions invoked since the last call
+pub fn take_metadata_cpis() -> Vec<Instruction> {
+    METADATA_CPIS.take()
+}
+
+// The system instructions the program uses: CreateAccount, Assign, Transfer
+// and Allocate
+fn process_system(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let key_at = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+    let signed = |info: &AccountInfo| {
+        if info.is_signer {
+            Ok(())
+        } else {
+            Err(ProgramError::MissingRequiredSignature)
+        }
+    };
+    match u32::from_le_bytes(data[..4].try_into().unwrap()) {
+        0 => {
+            let (from, to) = (&accounts[0], &accounts[1]);
+            signed(to)?;
+            if to.lamports() > 0 || !to.data_is_empty() {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            move_lamports(from, to, u64_at(4))?;
+            to.realloc(u64_at(12) as usize, true)?;
+            to.assign(&key_at(20));
+            Ok(())
+        }
+        1 => {
+            signed(&accounts[0])?;
+            system_owned(&accounts[0], false)?;
+            accounts[0].assign(&key_at(4));
+            Ok(())
+        }
+        2 => move_lamports(&accounts[0], &accounts[1], u64_at(4)),
+        8 => {
+            signed(&accounts[0])?;
+            system_owned(&accounts[0], true)?;
+            accounts[0].realloc(u64_at(4) as usize, true)?;
+            Ok(())
+        }
+        tag => panic!("unsupported system instruction {tag}"),
+    }
+}
+
+// Assign and Allocate only apply to accounts nothing has claimed yet; Assign
+// follows Allocate, so only Allocate needs the data to be empty
+fn system_owned(info: &AccountInfo, unallocated: bool) -> ProgramResult {
+    if *info.owner != system_program::ID || (unallocated && !info.data_is_empty()) {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    Ok(())
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *from.owner != system_program::ID || !from.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let balance = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **from.try_borrow_mut_lamports()? = balance;
+    **to.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
+
+#[derive(Clone, Default, PartialEq)]
+pub struct Stored {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+pub struct Svm {
+    accounts: HashMap<Pubkey, Stored>,
+    now: i64,
+}
+
+impl Svm {
+    pub fn new() -> Self {
+        install_stubs();
+        let mut svm = Svm {
+            accounts: HashMap::new(),
+            now: 1_700_000_000,
+        };
+        for (program, loader) in [
+            (system_program::ID, Pubkey::default()),
+            (spl_token::ID, bpf_loader_upgradeable::ID),
+            (spl_token_2022::ID, bpf_loader_upgradeable::ID),
+            (spl_associated_token_account::ID, bpf_loader_upgradeable::ID),
+            (TOKEN_METADATA_PROGRAM_ID, bpf_loader_upgradeable::ID),
+        ] {
+            svm.set(
+                program,
+                Stored {
+                    lamports: 1,
+                    owner: loader,
+                    executable: true,
+                    ..Stored::default()
+                },
+            );
+        }
+        let program_data = Self::program_data_address();
+        let mut program = 2u32.to_le_bytes().to_vec();
+        program.extend_from_slice(program_data.as_ref());
+        svm.set(
+            crate::ID,
+            Stored {
+                lamports: 1,
+                data: program,
+                owner: bpf_loader_upgradeable::ID,
+                executable: true,
+            },
+        );
+        let rent = Rent::default();
+        let mut data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+        data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+        data.push(rent.burn_percent);
+        svm.set(
+            sysvar::rent::ID,
+            Stored {
+                lamports: 1,
+                data,
+                owner: sysvar::ID,
+                executable: false,
+            },
+        );
+        svm
+    }
+
+    pub fn program_data_address() -> Pubkey {
+        Pubkey::find_program_address(&[crate::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+    }
+
+    // Records `authority` as the program's upgrade authority
+    pub fn set_upgrade_authority(&mut self, authority: Pubkey) {
+        let mut data = 3u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(authority.as_ref());
+        self.set(
+            Self::program_data_address(),
+            Stored {
+                lamports: 1,
+                data,
+                owner: bpf_loader_upgradeable::ID,
+                executable: false,
+            },
+        );
+    }
+
+    pub fn now(&self) -> i64 {
+        self.now
+    }
+
+    pub fn advance(&mut self, secs: i64) {
+        self.now += secs;
+    }
+
+    pub fn set(&mut self, key: Pubkey, account: Stored) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn get(&self, key: &Pubkey) -> Option<&Stored> {
+        self.accounts.get(key)
+    }
+
+    pub fn airdrop(&mut self, key: Pubkey, lamports: u64) {
+        self.accounts.entry(key).or_default().lamports += lamports;
+    }
+
+    pub fn wallet(&mut self) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.airdrop(key, 100 * SOL);
+        key
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn data_len(&self, key: &Pubkey) -> usize {
+        self.accounts
+            .get(key)
+            .map_or(0, |account| account.data.len())
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.accounts.get(key).expect("account does not exist");
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let mint = spl_token_2022::state::Mint {
+            decimals,
+            is_initialized: true,
+            ..Default::default()
+        };
+        self.set_token_state(key, mint);
+        key
+    }
+
+    pub fn token_account(&mut self, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let account = spl_token_2022::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token_2022::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        self.set_token_state(key, account);
+        key
+    }
+
+    fn set_token_state<T: Pack>(&mut self, key: Pubkey, state: T) {
+        let mut data = vec![0; T::LEN];
+        state.pack_into_slice(&mut data);
+        self.set(
+            key,
+            Stored {
+                lamports: Rent::default().minimum_balance(T::LEN),
+                data,
+                owner: spl_token_2022::ID,
+                executable: false,
+            },
+        );
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        let account = self
+            .accounts
+            .get(key)
+            .expect("token account does not exist");
+        spl_token_2022::state::Account::unpack_from_slice(&account.data[..165])
+            .unwrap()
+            .amount
+    }
+
+    // Executes an instruction as if another program had invoked it, one level
+    // below the transaction
+    pub fn send_as_cpi(&mut self, instruction: Instruction) -> ProgramResult {
+        STACK_HEIGHT.set(TRANSACTION_LEVEL_STACK_HEIGHT + 1);
+        let result = self.send(instruction);
+        STACK_HEIGHT.set(TRANSACTION_LEVEL_STACK_HEIGHT);
+        result
+    }
+
+    // Executes one instruction as its own transaction; accounts are only
+    // updated when it succeeds
+    pub fn send(&mut self, instruction: Instruction) -> ProgramResult {
+        NOW.set(self.now);
+        let metas = &instruction.accounts;
+        let mut input = Vec::new();
+        input.extend_from_slice(&(metas.len() as u64).to_le_bytes());
+        let mut unique = Vec::new();
+        for (index, meta) in metas.iter().enumerate() {
+            if let Some(first) = metas[..index].iter().position(|m| m.pubkey == meta.pubkey) {
+                input.push(first as u8);
+                input.extend_from_slice(&[0; 7]);
+                continue;
+            }
+            unique.push(index);
+            let same = || metas.iter().filter(|m| m.pubkey == meta.pubkey);
+            let account = self.accounts.get(&meta.pubkey).cloned().unwrap_or_default();
+            input.push(u8::MAX);
+            input.push(same().any(|m| m.is_signer) as u8);
+            input.push(same().any(|m| m.is_writable) as u8);
+            input.push(account.executable as u8);
+            input.extend_from_slice(&[0; 4]);
+            input.extend_from_slice(meta.pubkey.as_ref());
+            input.extend_from_slice(account.owner.as_ref());
+            input.extend_from_slice(&account.lamports.to_le_bytes());
+            input.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            input.extend_from_slice(&account.data);
+            input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            input.resize(input.len().next_multiple_of(8), 0);
+            input.extend_from_slice(&u64::MAX.to_le_bytes());
+        }
+        input.extend_from_slice(&(instruction.data.len() as u64).to_le_bytes());
+        input.extend_from_slice(&instruction.data);
+        input.extend_from_slice(crate::ID.as_ref());
+
+        // deserialize expects the runtime's 8-byte alignment
+        let mut aligned = vec![0u64; input.len().div_ceil(8)];
+        let bytes = aligned.as_mut_ptr() as *mut u8;
+        unsafe { std::ptr::copy_nonoverlapping(input.as_ptr(), bytes, input.len()) };
+        let (program_id, infos, data) = unsafe { entrypoint::deserialize(bytes) };
+        CALLEE.set(crate::ID);
+        RETURN_DATA.set(None);
+        crate::entry(program_id, &infos, data)?;
+
+        let before: u64 = unique
+            .iter()
+            .map(|&index| self.lamports(&metas[index].pubkey))
+            .sum();
+        let after: u64 = unique.iter().map(|&index| infos[index].lamports()).sum();
+        assert_eq!(before, after, "instruction created or destroyed lamports");
+        for &index in &unique {
+            let info = &infos[index];
+            let account = Stored {
+                lamports: info.lamports(),
+                data: info.data.borrow().to_vec(),
+                owner: *info.owner,
+                executable: info.executable,
+            };
+            let previous = self.accounts.get(info.key).cloned().unwrap_or_default();
+            if account == previous {
+                continue;
+            }
+            assert!(info.is_writable, "{} changed but is read-only", info.key);
+            if account.lamports == 0 {
+                self.accounts.remove(info.key);
+            } else {
+                self.accounts.insert(*info.key, account);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+// Error code of a failed instruction
+pub fn failure(result: ProgramResult) -> u32 {
+    match result {
+        Err(ProgramError::Custom(code)) => code,
+        other => panic!("expected a program error, got {other:?}"),
+    }
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &crate::ID).0
+}
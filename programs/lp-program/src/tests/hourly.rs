@@ -0,0 +1,53 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+#[test]
+fn approved_hours_pay_the_freelancer_net_of_the_protocol_fee() {
+    let mut market = Market::new();
+    let fee_treasury = market.set_fee(0, 500);
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    market.set_hourly_terms(&job, SOL / 10, 10);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    market.log_time(&job, application, 3);
+
+    let (balance, config_balance) = (
+        market.svm.lamports(&freelancer),
+        market.svm.lamports(&config()),
+    );
+    market.approve_time(&job, application).unwrap();
+    let fee = 3 * SOL / 10 / 20;
+    assert_eq!(
+        market.svm.lamports(&freelancer),
+        balance + 3 * SOL / 10 - fee
+    );
+    assert_eq!(market.svm.lamports(&config()), config_balance + fee);
+    assert_eq!(market.svm.account::<Config>(&config()).accrued_fees, fee);
+    let billed = market.svm.account::<Application>(&application);
+    assert_eq!(billed.released_amount, 3 * SOL / 10);
+    assert_eq!(billed.fees_paid, fee);
+
+    // The final approval only charges the fee on what the hours left unreleased
+    market.submit(&job, application);
+    let balance = market.svm.lamports(&freelancer);
+    market.approve(&job, application).unwrap();
+    assert_eq!(
+        market.svm.lamports(&freelancer),
+        balance + 7 * SOL / 10 - 7 * SOL / 10 / 20
+    );
+    assert_eq!(
+        market.svm.account::<Config>(&config()).accrued_fees,
+        SOL / 20
+    );
+
+    let treasury_balance = market.svm.lamports(&fee_treasury);
+    market.claim_fees().unwrap();
+    assert_eq!(
+        market.svm.lamports(&fee_treasury),
+        treasury_balance + SOL / 20
+    );
+    assert_eq!(market.svm.account::<Config>(&config()).accrued_fees, 0);
+}
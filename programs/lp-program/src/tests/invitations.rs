@@ -0,0 +1,49 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+fn invitation(job: &Job, freelancer: Pubkey) -> Pubkey {
+    pda(&[b"invitation", job.post.as_ref(), freelancer.as_ref()])
+}
+
+#[test]
+fn prefunded_invitation_addresses_do_not_block_invites() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let shortlist = [
+        market.register(UserRole::Freelancer),
+        market.register(UserRole::Freelancer),
+    ];
+    let job = market.post_job(client, SOL);
+    // Someone front-runs the campaign by sending a lamport to one invitation
+    let griefed = invitation(&job, shortlist[1]);
+    market.svm.airdrop(griefed, 1);
+
+    let mut ix = instruction(
+        accounts::InviteMany {
+            job_post: job.post,
+            signer: client,
+            user_account: user(client),
+            config: config(),
+            system_program: system_program::ID,
+        },
+        instruction::InviteMany {},
+    );
+    for freelancer in shortlist {
+        ix.accounts.extend([
+            AccountMeta::new_readonly(user(freelancer), false),
+            AccountMeta::new(invitation(&job, freelancer), false),
+        ]);
+    }
+    market.svm.send(ix).unwrap();
+
+    let rent = Rent::default().minimum_balance(8 + Invitation::INIT_SPACE);
+    for freelancer in shortlist {
+        let key = invitation(&job, freelancer);
+        let record = market.svm.account::<Invitation>(&key);
+        assert_eq!(record.freelancer, freelancer);
+        assert_eq!(record.job_post, job.post);
+        assert_eq!(market.svm.get(&key).unwrap().owner, crate::ID);
+        assert_eq!(market.svm.lamports(&key), rent);
+    }
+}
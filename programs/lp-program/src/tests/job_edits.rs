@@ -0,0 +1,31 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+#[test]
+fn edits_resize_the_job_post_to_its_text() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let job = market.post_job(client, SOL);
+    let rent = Rent::default();
+    let full = market.svm.data_len(&job.post);
+    assert_eq!(full, 8 + JobPost::INIT_SPACE);
+
+    let balance = market.svm.lamports(&client);
+    market.edit_job(&job, "Logo", "Draw a logo");
+    let short = 8 + JobPost::INIT_SPACE - (MAX_TITLE_LEN - 4) - (MAX_DESCRIPTION_LEN - 11);
+    assert_eq!(market.svm.data_len(&job.post), short);
+    let refund = rent.minimum_balance(full) - rent.minimum_balance(short);
+    assert_eq!(market.svm.lamports(&client), balance + refund);
+
+    let description = "d".repeat(MAX_DESCRIPTION_LEN);
+    market.edit_job(&job, "Logo", &description);
+    let long = short + MAX_DESCRIPTION_LEN - 11;
+    assert_eq!(market.svm.data_len(&job.post), long);
+    let post = market.svm.account::<JobPost>(&job.post);
+    assert_eq!(post.description, description);
+    assert_eq!(
+        market.svm.lamports(&client),
+        balance + refund - (rent.minimum_balance(long) - rent.minimum_balance(short))
+    );
+}
@@ -0,0 +1,1179 @@
+// A protocol deployment on the harness, with one helper per instruction the
+// flow tests go through. Account lists live here so the tests only name the
+// parties and amounts involved.
+
+use super::harness::*;
+use crate::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::token_2022::spl_token_2022;
+
+pub const DISPUTE_WINDOW_SECS: i64 = 7 * 24 * 60 * 60;
+
+pub fn user(wallet: Pubkey) -> Pubkey {
+    pda(&[b"user", wallet.as_ref()])
+}
+
+pub fn username(handle: &str) -> Pubkey {
+    pda(&[b"username", handle.as_bytes()])
+}
+
+pub fn reputation(wallet: Pubkey) -> Pubkey {
+    pda(&[b"reputation", wallet.as_ref()])
+}
+
+pub fn work_history(wallet: Pubkey) -> Pubkey {
+    pda(&[b"work_history", wallet.as_ref()])
+}
+
+pub fn freelancer_profile(wallet: Pubkey) -> Pubkey {
+    pda(&[b"freelancer_profile", wallet.as_ref()])
+}
+
+pub fn delegate(principal: Pubkey, session_key: Pubkey) -> Pubkey {
+    pda(&[b"delegate", principal.as_ref(), session_key.as_ref()])
+}
+
+pub fn config() -> Pubkey {
+    pda(&[b"config"])
+}
+
+pub fn activity_feed() -> Pubkey {
+    pda(&[b"activity_feed"])
+}
+
+pub fn protocol_stats() -> Pubkey {
+    pda(&[b"protocol_stats"])
+}
+
+pub fn job_index(client: Pubkey) -> Pubkey {
+    pda(&[b"job_index", client.as_ref()])
+}
+
+pub fn registry() -> Pubkey {
+    pda(&[b"arbitrator_registry"])
+}
+
+pub fn application(job: &Job, freelancer: Pubkey) -> Pubkey {
+    pda(&[b"application", job.post.as_ref(), freelancer.as_ref()])
+}
+
+pub fn deposit(application: Pubkey) -> Pubkey {
+    pda(&[b"application_deposit", application.as_ref()])
+}
+
+pub fn dispute(application: Pubkey) -> Pubkey {
+    pda(&[b"dispute", application.as_ref()])
+}
+
+pub fn retainer(client: Pubkey, freelancer: Pubkey) -> Pubkey {
+    pda(&[b"retainer", client.as_ref(), freelancer.as_ref()])
+}
+
+// Payout routing for a freelancer with no referrer and no vesting program
+pub fn direct_payout() -> accounts::PayoutRouting {
+    accounts::PayoutRouting {
+        freelancer_referrer: None,
+        vesting_program_record: None,
+        vesting_account: None,
+        vesting_program: None,
+    }
+}
+
+pub struct Job {
+    pub client: Pubkey,
+    pub post: Pubkey,
+    pub escrow: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub vault: Option<Pubkey>,
+}
+
+// Token accounts a token job's settlement pays into
+pub struct Payees {
+    pub freelancer: Pubkey,
+    pub client: Pubkey,
+    pub arbiter: Pubkey,
+}
+
+pub struct Market {
+    pub svm: Svm,
+    pub authority: Pubkey,
+    users: u32,
+}
+
+impl Market {
+    pub fn new() -> Self {
+        let mut svm = Svm::new();
+        let authority = svm.wallet();
+        svm.set_upgrade_authority(authority);
+        let mut market = Market {
+            svm,
+            authority,
+            users: 0,
+        };
+        market.send(
+            accounts::InitializeConfig {
+                config: config(),
+                signer: authority,
+                program: crate::ID,
+                program_data: Svm::program_data_address(),
+                system_program: system_program::ID,
+            },
+            instruction::InitializeConfig {
+                dispute_window_secs: DISPUTE_WINDOW_SECS,
+            },
+        );
+        market.send(
+            accounts::InitializeArbitratorRegistry {
+                arbitrator_registry: registry(),
+                signer: authority,
+                program: crate::ID,
+                program_data: Svm::program_data_address(),
+                system_program: system_program::ID,
+            },
+            instruction::InitializeArbitratorRegistry {},
+        );
+        market.send(
+            accounts::InitializeActivityFeed {
+                activity_feed: activity_feed(),
+                signer: authority,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeActivityFeed {},
+        );
+        market.send(
+            accounts::InitializeJobCounter {
+                job_counter: pda(&[b"job_counter"]),
+                signer: authority,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeJobCounter {},
+        );
+        market.send(
+            accounts::InitializeProtocolStats {
+                protocol_stats: protocol_stats(),
+                signer: authority,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeProtocolStats {},
+        );
+        market
+    }
+
+    // Sends an instruction that is expected to succeed
+    pub fn send(&mut self, accounts: impl ToAccountMetas, args: impl InstructionData) {
+        self.svm.send(instruction(accounts, args)).unwrap();
+    }
+
+    pub fn register(&mut self, role: UserRole) -> Pubkey {
+        let wallet = self.svm.wallet();
+        let handle = format!("user_{}", self.users + 1);
+        self.try_register(wallet, role, &handle).unwrap();
+        wallet
+    }
+
+    pub fn try_register(&mut self, wallet: Pubkey, role: UserRole, handle: &str) -> ProgramResult {
+        self.users += 1;
+        self.svm.send(instruction(
+            accounts::RegisterUser {
+                user_account: user(wallet),
+                reputation: reputation(wallet),
+                work_history: work_history(wallet),
+                username_record: username(handle),
+                signer: wallet,
+                config: config(),
+                system_program: system_program::ID,
+                protocol_stats: protocol_stats(),
+            },
+            instruction::RegisterUser {
+                name: format!("User {}", self.users),
+                role,
+                handle: handle.to_string(),
+            },
+        ))?;
+        self.svm.send(instruction(
+            accounts::InitializeJobIndex {
+                job_index: job_index(wallet),
+                signer: wallet,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeJobIndex {},
+        ))
+    }
+
+    pub fn close_user(&mut self, wallet: Pubkey) -> ProgramResult {
+        let handle = self.svm.account::<UserAccount>(&user(wallet)).handle;
+        self.svm.send(instruction(
+            accounts::CloseUserAccount {
+                user_account: user(wallet),
+                username_record: username(&handle),
+                signer: wallet,
+                config: config(),
+            },
+            instruction::CloseUserAccount {},
+        ))
+    }
+
+    // A vetted arbitrator with `stake` lamports staked
+    pub fn arbitrator(&mut self, stake: u64) -> Pubkey {
+        let wallet = self.register(UserRole::Client);
+        self.send(
+            accounts::GrantArbitratorRole {
+                arbitrator_registry: registry(),
+                user_account: user(wallet),
+                authority: self.authority,
+            },
+            instruction::GrantArbitratorRole {},
+        );
+        self.send(
+            accounts::RegisterArbitrator {
+                arbitrator_stake: pda(&[b"arbitrator", wallet.as_ref()]),
+                user_account: user(wallet),
+                arbitrator_registry: registry(),
+                signer: wallet,
+                config: config(),
+                system_program: system_program::ID,
+            },
+            instruction::RegisterArbitrator { stake },
+        );
+        wallet
+    }
+
+    pub fn set_arbitration_policy(&mut self, min_arbitrator_stake: u64, arbitrator_fee_bps: u16) {
+        self.send(
+            accounts::UpdateConfig {
+                config: config(),
+                authority: self.authority,
+            },
+            instruction::SetArbitrationPolicy {
+                min_arbitrator_stake,
+                arbitrator_fee_bps,
+            },
+        );
+    }
+
+    // Sets both protocol fee rates and returns the new treasury wallet
+    pub fn set_fee(&mut self, client_fee_bps: u16, freelancer_fee_bps: u16) -> Pubkey {
+        let fee_treasury = self.svm.wallet();
+        self.send(
+            accounts::UpdateConfig {
+                config: config(),
+                authority: self.authority,
+            },
+            instruction::SetFee {
+                client_fee_bps,
+                freelancer_fee_bps,
+                fee_treasury,
+            },
+        );
+        fee_treasury
+    }
+
+    pub fn claim_fees(&mut self) -> ProgramResult {
+        let fee_treasury = self.svm.account::<Config>(&config()).fee_treasury;
+        self.svm.send(instruction(
+            accounts::ClaimFees {
+                config: config(),
+                fee_treasury,
+            },
+            instruction::ClaimFees {},
+        ))
+    }
+
+    pub fn set_pause(&mut self, paused: bool) {
+        self.send(
+            accounts::UpdateConfig {
+                config: config(),
+                authority: self.authority,
+            },
+            instruction::SetPause { paused },
+        );
+    }
+
+    pub fn set_max_revisions(&mut self, max_revisions: u8) {
+        self.send(
+            accounts::UpdateConfig {
+                config: config(),
+                authority: self.authority,
+            },
+            instruction::SetMaxRevisions { max_revisions },
+        );
+    }
+
+    fn job_accounts(&self, client: Pubkey) -> (accounts::InitializeJobPost, Job) {
+        let counter = self.svm.account::<UserAccount>(&user(client)).job_counter;
+        let post = pda(&[b"job_post", client.as_ref(), &counter.to_le_bytes()]);
+        let job = Job {
+            client,
+            post,
+            escrow: pda(&[b"escrow", post.as_ref()]),
+            mint: None,
+            vault: None,
+        };
+        let accounts = accounts::InitializeJobPost {
+            signer: client,
+            user_account: user(client),
+            job_post: job.post,
+            escrow: job.escrow,
+            activity_feed: activity_feed(),
+            config: config(),
+            job_counter: pda(&[b"job_counter"]),
+            system_program: system_program::ID,
+            protocol_stats: protocol_stats(),
+            job_index: job_index(client),
+        };
+        (accounts, job)
+    }
+
+    fn job_args(&self, amount: u64) -> instruction::InitializeJobPost {
+        let now = self.svm.now();
+        instruction::InitializeJobPost {
+            title: "Landing page".to_string(),
+            description: "Build a landing page".to_string(),
+            amount,
+            start_date: now + 60,
+            end_date: now + 30 * 24 * 60 * 60,
+            yield_recipient: YieldRecipient::Client,
+            max_applicants: 0,
+            category: JobCategory::Development,
+            tags: vec!["web".to_string()],
+        }
+    }
+
+    pub fn post_job(&mut self, client: Pubkey, amount: u64) -> Job {
+        let (accounts, job) = self.job_accounts(client);
+        let args = self.job_args(amount);
+        self.send(accounts, args);
+        job
+    }
+
+    // Funded from `client_tokens`, a token account of `mint` the client owns
+    pub fn post_token_job(
+        &mut self,
+        client: Pubkey,
+        mint: Pubkey,
+        client_tokens: Pubkey,
+        amount: u64,
+    ) -> Job {
+        let (job_accounts, mut job) = self.job_accounts(client);
+        let vault = pda(&[b"escrow_vault", job.post.as_ref()]);
+        let args = self.job_args(amount);
+        self.send(
+            accounts::InitializeJobPostSpl {
+                job: job_accounts,
+                mint,
+                escrow_vault: vault,
+                client_token_account: client_tokens,
+                token_program: spl_token_2022::ID,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeJobPostSpl {
+                title: args.title,
+                description: args.description,
+                amount: args.amount,
+                start_date: args.start_date,
+                end_date: args.end_date,
+                yield_recipient: args.yield_recipient,
+                max_applicants: args.max_applicants,
+                category: args.category,
+                tags: args.tags,
+            },
+        );
+        job.mint = Some(mint);
+        job.vault = Some(vault);
+        job
+    }
+
+    pub fn set_max_hires(&mut self, job: &Job, max_hires: u8) {
+        self.send(
+            accounts::EditJobPost {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+            },
+            instruction::SetMaxHires { max_hires },
+        );
+    }
+
+    pub fn set_approvers(&mut self, job: &Job, approvers: Vec<Pubkey>, threshold: u8) {
+        self.send(
+            accounts::EditJobPost {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+            },
+            instruction::SetJobApprovers {
+                approvers,
+                threshold,
+            },
+        );
+    }
+
+    pub fn set_late_penalty(&mut self, job: &Job, late_penalty_bps_per_day: u16) {
+        self.send(
+            accounts::EditJobPost {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+            },
+            instruction::SetLatePenalty {
+                late_penalty_bps_per_day,
+            },
+        );
+    }
+
+    pub fn set_upfront_bps(&mut self, job: &Job, upfront_bps: u16) {
+        self.send(
+            accounts::EditJobPost {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+            },
+            instruction::SetUpfrontBps { upfront_bps },
+        );
+    }
+
+    pub fn set_hourly_terms(&mut self, job: &Job, hourly_rate: u64, max_hours: u32) {
+        self.send(
+            accounts::EditJobPost {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+            },
+            instruction::SetHourlyTerms {
+                hourly_rate,
+                max_hours,
+            },
+        );
+    }
+
+    pub fn set_streaming(&mut self, job: &Job, streaming: bool) {
+        self.send(
+            accounts::EditJobPost {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+            },
+            instruction::SetStreaming { streaming },
+        );
+    }
+
+    pub fn edit_job(&mut self, job: &Job, title: &str, description: &str) {
+        let post = self.svm.account::<JobPost>(&job.post);
+        self.send(
+            accounts::EditJobPostText {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+                system_program: system_program::ID,
+            },
+            instruction::EditJobPost {
+                title: title.to_string(),
+                description: description.to_string(),
+                start_date: post.start_date,
+                end_date: post.end_date,
+            },
+        );
+    }
+
+    pub fn boost(&mut self, job: &Job, duration_secs: i64) -> ProgramResult {
+        self.svm.send(instruction(
+            accounts::BoostJobPost {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+                system_program: system_program::ID,
+            },
+            instruction::BoostJobPost { duration_secs },
+        ))
+    }
+
+    pub fn add_milestone(&mut self, job: &Job, amount: u64) -> Pubkey {
+        let index = self.svm.account::<JobPost>(&job.post).milestone_count;
+        let milestone = pda(&[b"milestone", job.post.as_ref(), &[index]]);
+        self.send(
+            accounts::AddMilestone {
+                job_post: job.post,
+                milestone,
+                escrow: job.escrow,
+                client: job.client,
+                config: config(),
+                system_program: system_program::ID,
+                protocol_stats: protocol_stats(),
+            },
+            instruction::AddMilestone {
+                title: format!("Milestone {}", index + 1),
+                amount,
+                deadline: self.svm.now() + 14 * 24 * 60 * 60,
+            },
+        );
+        milestone
+    }
+
+    pub fn approve_milestone(
+        &mut self,
+        job: &Job,
+        application: Pubkey,
+        milestone: Pubkey,
+    ) -> ProgramResult {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        self.svm.send(instruction(
+            accounts::ApproveMilestone {
+                job_post: job.post,
+                milestone,
+                application,
+                escrow: job.escrow,
+                freelancer,
+                freelancer_account: user(freelancer),
+                payout: direct_payout(),
+                client: job.client,
+                client_account: user(job.client),
+                client_referrer: None,
+                config: config(),
+                protocol_stats: protocol_stats(),
+            },
+            instruction::ApproveMilestone {},
+        ))
+    }
+
+    pub fn cancel_job(&mut self, job: &Job) {
+        self.send(
+            accounts::CancelJobPost {
+                job_post: job.post,
+                escrow: job.escrow,
+                client: job.client,
+                client_account: user(job.client),
+                config: config(),
+                job_index: job_index(job.client),
+            },
+            instruction::CancelJobPost {},
+        );
+    }
+
+    pub fn sweep(&mut self, job: &Job, application: Option<Pubkey>) -> ProgramResult {
+        self.svm.send(instruction(
+            accounts::SweepExcessToClient {
+                job_post: job.post,
+                escrow: job.escrow,
+                application,
+                client: job.client,
+                config: config(),
+            },
+            instruction::SweepExcessToClient {},
+        ))
+    }
+
+    pub fn close_job(&mut self, job: &Job) -> ProgramResult {
+        self.svm.send(instruction(
+            accounts::CloseCompletedJob {
+                job_post: job.post,
+                escrow: job.escrow,
+                client: job.client,
+                job_index: job_index(job.client),
+                config: config(),
+                escrow_vault: job.vault,
+                token_program: job.mint.map(|_| spl_token_2022::ID),
+            },
+            instruction::CloseCompletedJob {},
+        ))
+    }
+
+    pub fn close_application(&mut self, job: &Job, application: Pubkey) -> ProgramResult {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        self.svm.send(instruction(
+            accounts::ClosePaidApplication {
+                application,
+                job_post: job.post,
+                freelancer,
+                config: config(),
+            },
+            instruction::ClosePaidApplication {},
+        ))
+    }
+
+    pub fn apply(&mut self, job: &Job, freelancer: Pubkey) -> Pubkey {
+        self.try_apply(job, freelancer).unwrap();
+        application(job, freelancer)
+    }
+
+    pub fn try_apply(&mut self, job: &Job, freelancer: Pubkey) -> ProgramResult {
+        let application = application(job, freelancer);
+        let fee = self.svm.account::<JobPost>(&job.post).application_fee;
+        let profile = freelancer_profile(freelancer);
+        self.svm.send(instruction(
+            accounts::ApplyToJob {
+                application,
+                signer: freelancer,
+                user_account: user(freelancer),
+                job_post: job.post,
+                whitelist: None,
+                freelancer_profile: self.svm.get(&profile).map(|_| profile),
+                deposit: (fee > 0).then(|| deposit(application)),
+                config: config(),
+                system_program: system_program::ID,
+            },
+            instruction::ApplyToJob {
+                resume_link: "https://example.com/resume".to_string(),
+                expected_end_date: self.svm.now() + 30 * 24 * 60 * 60,
+            },
+        ))
+    }
+
+    pub fn set_availability(&mut self, freelancer: Pubkey, available: bool, hourly_rate: u64) {
+        let profile = freelancer_profile(freelancer);
+        if self.svm.get(&profile).is_none() {
+            self.send(
+                accounts::InitializeFreelancerProfile {
+                    profile,
+                    user_account: user(freelancer),
+                    signer: freelancer,
+                    config: config(),
+                    system_program: system_program::ID,
+                },
+                instruction::InitializeFreelancerProfile {},
+            );
+        }
+        self.send(
+            accounts::UpdateFreelancerProfile {
+                profile,
+                signer: freelancer,
+                config: config(),
+            },
+            instruction::SetAvailability {
+                available,
+                hourly_rate,
+            },
+        );
+    }
+
+    pub fn hire_accounts(&self, job: &Job, application: Pubkey) -> accounts::ApproveApplication {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        accounts::ApproveApplication {
+            application,
+            job_post: job.post,
+            signer: job.client,
+            user_account: user(job.client),
+            freelancer_account: user(freelancer),
+            activity_feed: activity_feed(),
+            config: config(),
+            organization: None,
+            escrow: None,
+            freelancer: None,
+            payout: direct_payout(),
+            relationship: WorkRelationship::address(job.client, freelancer).0,
+            system_program: system_program::ID,
+            protocol_stats: protocol_stats(),
+            delegate: None,
+        }
+    }
+
+    pub fn hire(&mut self, job: &Job, application: Pubkey) {
+        let accounts = self.hire_accounts(job, application);
+        self.send(accounts, instruction::ApproveApplication {});
+    }
+
+    pub fn submit(&mut self, job: &Job, application: Pubkey) {
+        self.submit_link(job, application, "https://example.com/work");
+    }
+
+    pub fn submit_link(&mut self, job: &Job, application: Pubkey, link: &str) {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        self.send(
+            accounts::SubmitWork {
+                application,
+                signer: freelancer,
+                user_account: user(freelancer),
+                job_post: job.post,
+                config: config(),
+                system_program: system_program::ID,
+            },
+            instruction::SubmitWork {
+                submission_link: link.to_string(),
+                narration: String::new(),
+            },
+        );
+    }
+
+    pub fn request_revision(&mut self, job: &Job, application: Pubkey) {
+        self.send(
+            accounts::RequestRevision {
+                application,
+                job_post: job.post,
+                signer: job.client,
+                delegate: None,
+                config: config(),
+            },
+            instruction::RequestRevision {
+                feedback: "Needs another pass".to_string(),
+            },
+        );
+    }
+
+    pub fn set_payout_splits(
+        &mut self,
+        job: &Job,
+        application: Pubkey,
+        splits: Vec<PayoutSplit>,
+    ) -> ProgramResult {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        self.svm.send(instruction(
+            accounts::SetPayoutSplits {
+                application,
+                job_post: job.post,
+                freelancer,
+                config: config(),
+            },
+            instruction::SetPayoutSplits { splits },
+        ))
+    }
+
+    pub fn claim_vested(&mut self, job: &Job, application: Pubkey) -> ProgramResult {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        self.svm.send(instruction(
+            accounts::ClaimVested {
+                application,
+                job_post: job.post,
+                escrow: job.escrow,
+                freelancer,
+                freelancer_account: user(freelancer),
+                payout: direct_payout(),
+                config: config(),
+                protocol_stats: protocol_stats(),
+            },
+            instruction::ClaimVested {},
+        ))
+    }
+
+    // Logs `hours` as the hired freelancer, opening the time log on first use
+    pub fn log_time(&mut self, job: &Job, application: Pubkey, hours: u16) {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        let log = pda(&[b"time_log", application.as_ref()]);
+        if self.svm.get(&log).is_none() {
+            self.send(
+                accounts::InitializeTimeLog {
+                    log,
+                    application,
+                    job_post: job.post,
+                    freelancer,
+                    config: config(),
+                    system_program: system_program::ID,
+                },
+                instruction::InitializeTimeLog {},
+            );
+        }
+        self.send(
+            accounts::LogTime {
+                log,
+                application,
+                job_post: job.post,
+                freelancer,
+                config: config(),
+            },
+            instruction::LogTime {
+                hours,
+                memo: "Implementation".to_string(),
+            },
+        );
+    }
+
+    pub fn approve_time(&mut self, job: &Job, application: Pubkey) -> ProgramResult {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        self.svm.send(instruction(
+            accounts::ApproveTime {
+                log: pda(&[b"time_log", application.as_ref()]),
+                application,
+                job_post: job.post,
+                escrow: job.escrow,
+                freelancer,
+                freelancer_account: user(freelancer),
+                payout: direct_payout(),
+                client: job.client,
+                config: config(),
+                protocol_stats: protocol_stats(),
+            },
+            instruction::ApproveTime {},
+        ))
+    }
+
+    pub fn approval_accounts(&self, job: &Job, application: Pubkey) -> accounts::ApproveSubmission {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        accounts::ApproveSubmission {
+            application,
+            job_post: job.post,
+            escrow: job.escrow,
+            signer: job.client,
+            user_account: user(job.client),
+            freelancer,
+            client: job.client,
+            freelancer_account: user(freelancer),
+            client_account: user(job.client),
+            freelancer_reputation: reputation(freelancer),
+            client_reputation: reputation(job.client),
+            freelancer_work_history: work_history(freelancer),
+            config: config(),
+            activity_feed: activity_feed(),
+            payout: direct_payout(),
+            schedule: None,
+            system_program: system_program::ID,
+            organization: None,
+            client_referrer: None,
+            freelancer_credential: None,
+            protocol_stats: protocol_stats(),
+        }
+    }
+
+    // Approves the latest submission as the client, with a rating and no review
+    pub fn approve(&mut self, job: &Job, application: Pubkey) -> ProgramResult {
+        let accounts = self.approval_accounts(job, application);
+        self.approve_with(accounts)
+    }
+
+    pub fn approve_with(&mut self, accounts: accounts::ApproveSubmission) -> ProgramResult {
+        self.review(accounts, "", 5)
+    }
+
+    pub fn review(
+        &mut self,
+        accounts: accounts::ApproveSubmission,
+        client_review: &str,
+        rating: u8,
+    ) -> ProgramResult {
+        self.svm.send(instruction(
+            accounts,
+            instruction::ApproveSubmission {
+                client_review: client_review.to_string(),
+                rating,
+            },
+        ))
+    }
+
+    // Authorizes a fresh session key for `principal`
+    pub fn create_delegate(&mut self, principal: Pubkey, scope: u8, expires_at: i64) -> Pubkey {
+        let session_key = self.svm.wallet();
+        self.send(
+            accounts::CreateDelegate {
+                delegate: delegate(principal, session_key),
+                principal,
+                session_key,
+                config: config(),
+                system_program: system_program::ID,
+            },
+            instruction::CreateDelegate { scope, expires_at },
+        );
+        session_key
+    }
+
+    // Hires as the principal's session key
+    pub fn delegated_hire(
+        &mut self,
+        job: &Job,
+        application: Pubkey,
+        session_key: Pubkey,
+    ) -> ProgramResult {
+        let mut accounts = self.hire_accounts(job, application);
+        accounts.signer = session_key;
+        accounts.delegate = Some(delegate(job.client, session_key));
+        self.svm
+            .send(instruction(accounts, instruction::ApproveApplication {}))
+    }
+
+    pub fn set_application_fee(&mut self, job: &Job, application_fee: u64) {
+        self.send(
+            accounts::EditJobPost {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+            },
+            instruction::SetApplicationFee { application_fee },
+        );
+    }
+
+    pub fn set_completion_bond(&mut self, job: &Job, bond_amount: u64) {
+        self.send(
+            accounts::EditJobPost {
+                job_post: job.post,
+                client: job.client,
+                config: config(),
+            },
+            instruction::SetCompletionBond { bond_amount },
+        );
+    }
+
+    pub fn post_bond(&mut self, job: &Job, application: Pubkey) {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        self.send(
+            accounts::PostCompletionBond {
+                application,
+                job_post: job.post,
+                freelancer,
+                config: config(),
+                system_program: system_program::ID,
+            },
+            instruction::PostCompletionBond {},
+        );
+    }
+
+    // Marks the hire defaulted once the delivery deadline has passed
+    pub fn reclaim(&mut self, job: &Job, application: Pubkey) -> ProgramResult {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        self.svm.send(instruction(
+            accounts::ReclaimExpiredEscrow {
+                job_post: job.post,
+                application,
+                escrow: job.escrow,
+                client: job.client,
+                freelancer_account: user(freelancer),
+                client_account: user(job.client),
+                config: config(),
+            },
+            instruction::ReclaimExpiredEscrow {},
+        ))
+    }
+
+    pub fn reject(&mut self, job: &Job, application: Pubkey) {
+        self.send(
+            accounts::SetApplicationStage {
+                application,
+                job_post: job.post,
+                signer: job.client,
+                user_account: user(job.client),
+                config: config(),
+            },
+            instruction::RejectApplication {
+                reason: "Not a fit".to_string(),
+            },
+        );
+    }
+
+    fn fee_settlement(
+        &self,
+        job: &Job,
+        application: Pubkey,
+        signer: Pubkey,
+    ) -> accounts::SettleApplicationFee {
+        accounts::SettleApplicationFee {
+            deposit: deposit(application),
+            application,
+            job_post: job.post,
+            applicant: self.svm.account::<Application>(&application).applicant,
+            signer,
+            config: config(),
+        }
+    }
+
+    pub fn refund_application_fee(
+        &mut self,
+        job: &Job,
+        application: Pubkey,
+        signer: Pubkey,
+    ) -> ProgramResult {
+        let accounts = self.fee_settlement(job, application, signer);
+        self.svm
+            .send(instruction(accounts, instruction::RefundApplicationFee {}))
+    }
+
+    pub fn forfeit_application_fee(&mut self, job: &Job, application: Pubkey) -> ProgramResult {
+        let accounts = self.fee_settlement(job, application, job.client);
+        self.svm
+            .send(instruction(accounts, instruction::ForfeitApplicationFee {}))
+    }
+
+    pub fn propose_counter_offer(
+        &mut self,
+        job: &Job,
+        application: Pubkey,
+        proposed_amount: u64,
+    ) -> ProgramResult {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        self.svm.send(instruction(
+            accounts::ProposeCounterOffer {
+                application,
+                job_post: job.post,
+                freelancer,
+                config: config(),
+            },
+            instruction::ProposeCounterOffer { proposed_amount },
+        ))
+    }
+
+    pub fn accept_counter_offer(&mut self, job: &Job, application: Pubkey) -> ProgramResult {
+        let hire = self.hire_accounts(job, application);
+        self.svm.send(instruction(
+            accounts::AcceptCounterOffer {
+                hire,
+                escrow: job.escrow,
+                system_program: system_program::ID,
+            },
+            instruction::AcceptCounterOffer {},
+        ))
+    }
+
+    pub fn create_retainer(
+        &mut self,
+        client: Pubkey,
+        freelancer: Pubkey,
+        period_amount: u64,
+        period_secs: i64,
+        periods: u16,
+    ) -> Pubkey {
+        self.send(
+            accounts::CreateRetainer {
+                retainer: retainer(client, freelancer),
+                client,
+                user_account: user(client),
+                freelancer_account: user(freelancer),
+                config: config(),
+                system_program: system_program::ID,
+            },
+            instruction::CreateRetainer {
+                freelancer,
+                period_amount,
+                period_secs,
+                periods,
+            },
+        );
+        retainer(client, freelancer)
+    }
+
+    pub fn claim_period_payment(&mut self, retainer: Pubkey) -> ProgramResult {
+        let freelancer = self.svm.account::<Retainer>(&retainer).freelancer;
+        self.svm.send(instruction(
+            accounts::ClaimPeriodPayment {
+                retainer,
+                freelancer,
+                config: config(),
+            },
+            instruction::ClaimPeriodPayment {},
+        ))
+    }
+
+    pub fn cancel_retainer(&mut self, retainer: Pubkey) -> ProgramResult {
+        let state = self.svm.account::<Retainer>(&retainer);
+        self.svm.send(instruction(
+            accounts::CancelRetainer {
+                retainer,
+                client: state.client,
+                freelancer: state.freelancer,
+                config: config(),
+            },
+            instruction::CancelRetainer {},
+        ))
+    }
+
+    // Hands the dispute to the registry's next arbitrator, passing every
+    // candidate in turn
+    pub fn open_dispute(&mut self, job: &Job, application: Pubkey, signer: Pubkey) -> Pubkey {
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        let registry_state = self.svm.account::<ArbitratorRegistry>(&registry());
+        let count = registry_state.arbitrators.len();
+        let mut ix = instruction(
+            accounts::OpenDispute {
+                dispute: dispute(application),
+                application,
+                job_post: job.post,
+                signer,
+                user_account: user(signer),
+                arbitrator_registry: registry(),
+                organization: None,
+                config: config(),
+                system_program: system_program::ID,
+                protocol_stats: protocol_stats(),
+            },
+            instruction::OpenDispute {
+                reason: "Work was not delivered".to_string(),
+            },
+        );
+        for offset in 0..count {
+            let candidate =
+                registry_state.arbitrators[(registry_state.next_index as usize + offset) % count];
+            ix.accounts.extend([
+                AccountMeta::new_readonly(user(candidate), false),
+                AccountMeta::new(pda(&[b"arbitrator", candidate.as_ref()]), false),
+                AccountMeta::new_readonly(
+                    WorkRelationship::address(candidate, job.client).0,
+                    false,
+                ),
+                AccountMeta::new_readonly(
+                    WorkRelationship::address(candidate, freelancer).0,
+                    false,
+                ),
+            ]);
+        }
+        self.svm.send(ix).unwrap();
+        dispute(application)
+    }
+
+    pub fn respond(&mut self, dispute: Pubkey, respondent: Pubkey) {
+        self.send(
+            accounts::RespondToDispute {
+                dispute,
+                signer: respondent,
+                config: config(),
+            },
+            instruction::RespondToDispute {
+                evidence_link: "https://example.com/evidence".to_string(),
+            },
+        );
+    }
+
+    pub fn settlement(
+        &self,
+        job: &Job,
+        application: Pubkey,
+        payees: Option<&Payees>,
+    ) -> accounts::SettleDispute {
+        let dispute = dispute(application);
+        let freelancer = self.svm.account::<Application>(&application).applicant;
+        let arbiter = self.svm.account::<Dispute>(&dispute).arbiter;
+        accounts::SettleDispute {
+            dispute,
+            application,
+            job_post: job.post,
+            escrow: job.escrow,
+            freelancer,
+            client: job.client,
+            freelancer_account: user(freelancer),
+            payout: direct_payout(),
+            client_account: user(job.client),
+            client_referrer: None,
+            freelancer_reputation: reputation(freelancer),
+            client_reputation: reputation(job.client),
+            freelancer_work_history: work_history(freelancer),
+            config: config(),
+            activity_feed: activity_feed(),
+            arbiter,
+            arbiter_stake: pda(&[b"arbitrator", arbiter.as_ref()]),
+            protocol_stats: protocol_stats(),
+            mint: job.mint,
+            escrow_vault: job.vault,
+            freelancer_token_account: payees.map(|payees| payees.freelancer),
+            client_token_account: payees.map(|payees| payees.client),
+            arbiter_token_account: payees.map(|payees| payees.arbiter),
+            token_program: job.mint.map(|_| spl_token_2022::ID),
+        }
+    }
+
+    pub fn resolve(
+        &mut self,
+        job: &Job,
+        application: Pubkey,
+        ruling: DisputeRuling,
+        payees: Option<&Payees>,
+    ) -> ProgramResult {
+        let settlement = self.settlement(job, application, payees);
+        let arbiter = settlement.arbiter;
+        self.svm.send(instruction(
+            accounts::ResolveDispute {
+                ruling: pda(&[b"ruling", settlement.dispute.as_ref()]),
+                settlement,
+                signer: arbiter,
+                system_program: system_program::ID,
+            },
+            instruction::ResolveDispute {
+                ruling,
+                reason_code: RulingReason::Other,
+                document_hash: [0; 32],
+            },
+        ))
+    }
+}
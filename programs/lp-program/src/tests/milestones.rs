@@ -0,0 +1,115 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+#[test]
+fn milestones_release_in_parts_and_the_final_approval_pays_the_rest() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let first = market.add_milestone(&job, 4 * SOL / 10);
+
+    // The second milestone overshoots the budget; the client funds the difference
+    let balance = market.svm.lamports(&client);
+    let escrow_balance = market.svm.lamports(&job.escrow);
+    let second = market.add_milestone(&job, 8 * SOL / 10);
+    let shortfall = 2 * SOL / 10;
+    let milestone_rent = market.svm.lamports(&second);
+    assert_eq!(
+        market.svm.lamports(&client),
+        balance - shortfall - milestone_rent
+    );
+    assert_eq!(market.svm.lamports(&job.escrow), escrow_balance + shortfall);
+    let post = market.svm.account::<JobPost>(&job.post);
+    assert_eq!(post.amount, SOL + shortfall);
+    assert_eq!(
+        market.svm.account::<Escrow>(&job.escrow).expected_amount,
+        SOL + shortfall
+    );
+
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    let (balance, escrow_balance) = (
+        market.svm.lamports(&freelancer),
+        market.svm.lamports(&job.escrow),
+    );
+    market.approve_milestone(&job, application, first).unwrap();
+    assert_eq!(market.svm.lamports(&freelancer), balance + 4 * SOL / 10);
+    assert_eq!(
+        market.svm.lamports(&job.escrow),
+        escrow_balance - 4 * SOL / 10
+    );
+    assert_eq!(
+        market.svm.account::<Milestone>(&first).status,
+        MilestoneStatus::Released
+    );
+    assert_eq!(
+        failure(market.approve_milestone(&job, application, first)),
+        u32::from(ErrorCode::InvalidMilestone)
+    );
+
+    market.submit(&job, application);
+    let balance = market.svm.lamports(&freelancer);
+    market.approve(&job, application).unwrap();
+    assert_eq!(
+        market.svm.lamports(&freelancer),
+        balance + 6 * SOL / 10 + shortfall
+    );
+    let paid = market.svm.account::<Application>(&application);
+    assert_eq!(paid.released_amount, SOL + shortfall);
+    assert!(market.svm.get(&job.escrow).is_none());
+}
+
+#[test]
+fn milestones_charge_both_fee_sides_as_they_release() {
+    let mut market = Market::new();
+    market.set_fee(200, 500);
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let milestone = market.add_milestone(&job, 4 * SOL / 10);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+
+    // The milestone earns 40% of the 2% client-side fee
+    let balance = market.svm.lamports(&freelancer);
+    market
+        .approve_milestone(&job, application, milestone)
+        .unwrap();
+    let (fee, client_fee) = (4 * SOL / 10 / 20, 8 * SOL / 1_000);
+    assert_eq!(
+        market.svm.lamports(&freelancer),
+        balance + 4 * SOL / 10 - fee
+    );
+    assert_eq!(
+        market.svm.account::<Config>(&config()).accrued_fees,
+        fee + client_fee
+    );
+    assert_eq!(
+        market.svm.account::<JobPost>(&job.post).client_fee,
+        SOL / 50 - client_fee
+    );
+    assert_eq!(
+        market.svm.account::<Escrow>(&job.escrow).expected_amount,
+        SOL + SOL / 50 - client_fee
+    );
+
+    // The final approval collects the rest of both fees and closes the escrow
+    market.submit(&job, application);
+    let balance = market.svm.lamports(&freelancer);
+    market.approve(&job, application).unwrap();
+    assert_eq!(
+        market.svm.lamports(&freelancer),
+        balance + 6 * SOL / 10 - 6 * SOL / 10 / 20
+    );
+    assert_eq!(
+        market.svm.account::<Config>(&config()).accrued_fees,
+        SOL / 20 + SOL / 50
+    );
+    assert_eq!(
+        market.svm.account::<Application>(&application).fees_paid,
+        SOL / 20
+    );
+    assert!(market.svm.get(&job.escrow).is_none());
+}
@@ -0,0 +1,394 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+#[test]
+fn final_payout_closes_the_escrow_to_the_client() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    market.submit(&job, application);
+
+    let state = market.svm.account::<Escrow>(&job.escrow);
+    assert_eq!(state.funder, client);
+    assert_eq!(state.expected_amount, SOL);
+    assert_eq!(state.status, EscrowStatus::Funded);
+    let escrow = market.svm.lamports(&job.escrow);
+    let rent = Rent::default().minimum_balance(8 + Escrow::INIT_SPACE);
+    assert_eq!(escrow, SOL + rent);
+    let (client_balance, freelancer_balance) = (
+        market.svm.lamports(&client),
+        market.svm.lamports(&freelancer),
+    );
+    market.approve(&job, application).unwrap();
+
+    assert!(market.svm.get(&job.escrow).is_none());
+    assert_eq!(market.svm.lamports(&freelancer), freelancer_balance + SOL);
+    assert_eq!(market.svm.lamports(&client), client_balance + rent);
+    assert_eq!(
+        market.svm.account::<JobPost>(&job.post).status,
+        JobStatus::Completed
+    );
+}
+
+#[test]
+fn cancelled_escrow_stays_refunded_until_the_job_is_closed() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let job = market.post_job(client, SOL);
+    let owed = market.svm.account::<Escrow>(&job.escrow).expected_amount;
+    // Lamports sent straight to the escrow are not part of the budget
+    market.svm.airdrop(job.escrow, 1_000);
+    let balance = market.svm.lamports(&client);
+    market.cancel_job(&job);
+
+    assert_eq!(market.svm.lamports(&client), balance + owed);
+    let state = market.svm.account::<Escrow>(&job.escrow);
+    assert_eq!(state.status, EscrowStatus::Refunded);
+    let rent = Rent::default().minimum_balance(8 + Escrow::INIT_SPACE);
+    assert_eq!(market.svm.lamports(&job.escrow), rent + 1_000);
+
+    let post_rent = market.svm.lamports(&job.post);
+    market.close_job(&job).unwrap();
+    assert!(market.svm.get(&job.escrow).is_none());
+    assert_eq!(
+        market.svm.lamports(&client),
+        balance + owed + rent + 1_000 + post_rent
+    );
+}
+
+#[test]
+fn payouts_only_go_to_the_applicant() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    market.submit(&job, application);
+
+    let mut accounts = market.approval_accounts(&job, application);
+    accounts.freelancer = market.svm.wallet();
+    assert_eq!(
+        failure(market.approve_with(accounts)),
+        u32::from(ErrorCode::PayeeMismatch)
+    );
+    market.approve(&job, application).unwrap();
+}
+
+#[test]
+fn applications_and_user_accounts_are_bound_to_the_job_and_signer() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let other_job = market.post_job(client, SOL);
+    let application = market.apply(&other_job, freelancer);
+
+    let mut accounts = market.hire_accounts(&other_job, application);
+    accounts.job_post = job.post;
+    assert_eq!(
+        failure(
+            market
+                .svm
+                .send(instruction(accounts, instruction::ApproveApplication {}))
+        ),
+        u32::from(ErrorCode::ApplicationJobMismatch)
+    );
+    market.hire(&other_job, application);
+    market.submit(&other_job, application);
+
+    // Someone else cannot approve by presenting the client's user account
+    let intruder = market.register(UserRole::Client);
+    let mut accounts = market.approval_accounts(&other_job, application);
+    accounts.signer = intruder;
+    assert_eq!(
+        failure(market.approve_with(accounts)),
+        anchor_lang::error::ErrorCode::ConstraintSeeds as u32
+    );
+}
+
+#[test]
+fn approver_sets_release_funds_at_the_threshold() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let approvers = [
+        market.register(UserRole::Client),
+        market.register(UserRole::Client),
+        market.register(UserRole::Client),
+    ];
+    let job = market.post_job(client, SOL);
+    market.set_approvers(&job, approvers.to_vec(), 2);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    market.submit(&job, application);
+
+    let approve_as = |market: &mut Market, signer: Pubkey| {
+        let mut accounts = market.approval_accounts(&job, application);
+        accounts.signer = signer;
+        accounts.user_account = user(signer);
+        market.approve_with(accounts)
+    };
+    assert_eq!(
+        failure(market.approve(&job, application)),
+        u32::from(ErrorCode::NotAnApprover)
+    );
+    let balance = market.svm.lamports(&freelancer);
+    approve_as(&mut market, approvers[0]).unwrap();
+    assert_eq!(
+        failure(approve_as(&mut market, approvers[0])),
+        u32::from(ErrorCode::DuplicateApproval)
+    );
+    let pending = market.svm.account::<Application>(&application);
+    assert_eq!(pending.status, ApplicationStatus::Submitted);
+    assert_eq!(pending.approvals, [approvers[0]]);
+    assert_eq!(market.svm.lamports(&freelancer), balance);
+
+    approve_as(&mut market, approvers[2]).unwrap();
+    let paid = market.svm.account::<Application>(&application);
+    assert_eq!(paid.status, ApplicationStatus::Paid);
+    assert!(paid.approvals.is_empty());
+    assert_eq!(market.svm.lamports(&freelancer), balance + SOL);
+}
+
+#[test]
+fn only_the_releasing_approval_records_the_review() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let approvers = [
+        market.register(UserRole::Client),
+        market.register(UserRole::Client),
+    ];
+    let job = market.post_job(client, SOL);
+    market.set_approvers(&job, approvers.to_vec(), 2);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    market.submit(&job, application);
+
+    let review_as = |market: &mut Market, signer: Pubkey, review: &str, rating: u8| {
+        let mut accounts = market.approval_accounts(&job, application);
+        accounts.signer = signer;
+        accounts.user_account = user(signer);
+        market.review(accounts, review, rating)
+    };
+    let len = market.svm.data_len(&application);
+    review_as(&mut market, approvers[0], "Missed half the brief", 1).unwrap();
+    let pending = market.svm.account::<Application>(&application);
+    assert_eq!(pending.freelancer_rating, 0);
+    assert!(pending.client_review.is_empty());
+    assert_eq!(market.svm.data_len(&application), len);
+
+    review_as(&mut market, approvers[1], "Great work", 4).unwrap();
+    let paid = market.svm.account::<Application>(&application);
+    assert_eq!(paid.status, ApplicationStatus::Paid);
+    assert_eq!(paid.freelancer_rating, 4);
+    assert_eq!(paid.client_review, "Great work");
+}
+
+#[test]
+fn split_payouts_give_the_rounding_remainder_to_the_last_recipient() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let amount = SOL + 1;
+    let job = market.post_job(client, amount);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    market.submit(&job, application);
+
+    let recipients = [
+        market.svm.wallet(),
+        market.svm.wallet(),
+        market.svm.wallet(),
+    ];
+    let splits = recipients
+        .iter()
+        .zip([3_333, 3_333, 3_334])
+        .map(|(&recipient, share_bps)| PayoutSplit {
+            recipient,
+            share_bps,
+        })
+        .collect::<Vec<_>>();
+    let mut uneven = splits.clone();
+    uneven[2].share_bps -= 1;
+    assert_eq!(
+        failure(market.set_payout_splits(&job, application, uneven)),
+        u32::from(ErrorCode::InvalidPayoutSplits)
+    );
+    market.set_payout_splits(&job, application, splits).unwrap();
+
+    let approve_paying = |market: &mut Market, paid: &[Pubkey]| {
+        let mut ix = instruction(
+            market.approval_accounts(&job, application),
+            instruction::ApproveSubmission {
+                client_review: String::new(),
+                rating: 5,
+            },
+        );
+        ix.accounts
+            .extend(paid.iter().map(|&wallet| AccountMeta::new(wallet, false)));
+        market.svm.send(ix)
+    };
+    assert_eq!(
+        failure(approve_paying(&mut market, &recipients[..2])),
+        u32::from(ErrorCode::SplitRecipientMissing)
+    );
+
+    let balances = recipients.map(|wallet| market.svm.lamports(&wallet));
+    let freelancer_balance = market.svm.lamports(&freelancer);
+    approve_paying(&mut market, &recipients).unwrap();
+    let received = recipients.map(|wallet| market.svm.lamports(&wallet));
+    assert_eq!(received[0] - balances[0], bps_of(amount, 3_333));
+    assert_eq!(received[1] - balances[1], bps_of(amount, 3_333));
+    // 3334 bps of the amount rounds down; the last recipient also gets the odd lamport
+    assert_eq!(received[2] - balances[2], bps_of(amount, 3_334) + 1);
+    assert_eq!(market.svm.lamports(&freelancer), freelancer_balance);
+}
+
+#[test]
+fn upfront_payments_charge_the_freelancer_fee_and_follow_splits() {
+    let mut market = Market::new();
+    market.set_fee(0, 1_000);
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    market.set_upfront_bps(&job, 3_000);
+    let application = market.apply(&job, freelancer);
+    let recipients = [market.svm.wallet(), market.svm.wallet()];
+    let splits = recipients
+        .iter()
+        .map(|&recipient| PayoutSplit {
+            recipient,
+            share_bps: 5_000,
+        })
+        .collect();
+    market.set_payout_splits(&job, application, splits).unwrap();
+
+    let mut accounts = market.hire_accounts(&job, application);
+    accounts.escrow = Some(job.escrow);
+    accounts.freelancer = Some(freelancer);
+    let mut ix = instruction(accounts, instruction::ApproveApplication {});
+    ix.accounts.extend(
+        recipients
+            .iter()
+            .map(|&wallet| AccountMeta::new(wallet, false)),
+    );
+    let (balances, freelancer_balance) = (
+        recipients.map(|wallet| market.svm.lamports(&wallet)),
+        market.svm.lamports(&freelancer),
+    );
+    market.svm.send(ix).unwrap();
+
+    // 30% upfront, less the 10% fee, divided evenly
+    let fee = 3 * SOL / 100;
+    for (wallet, balance) in recipients.iter().zip(balances) {
+        assert_eq!(
+            market.svm.lamports(wallet),
+            balance + (3 * SOL / 10 - fee) / 2
+        );
+    }
+    assert_eq!(market.svm.lamports(&freelancer), freelancer_balance);
+    assert_eq!(market.svm.account::<Config>(&config()).accrued_fees, fee);
+    let hired = market.svm.account::<Application>(&application);
+    assert_eq!(hired.released_amount, 3 * SOL / 10);
+    assert_eq!(hired.fees_paid, fee);
+}
+
+#[test]
+fn defaulted_hires_return_their_share_and_the_last_one_refunds_the_escrow() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let job = market.post_job(client, 2 * SOL);
+    market.set_max_hires(&job, 2);
+    let applications = [
+        market.register(UserRole::Freelancer),
+        market.register(UserRole::Freelancer),
+    ]
+    .map(|freelancer| market.apply(&job, freelancer));
+    for application in applications {
+        market.hire(&job, application);
+    }
+
+    let end_date = market.svm.account::<JobPost>(&job.post).end_date;
+    market.svm.advance(end_date - market.svm.now() + 1);
+    let (balance, escrow_balance) = (
+        market.svm.lamports(&client),
+        market.svm.lamports(&job.escrow),
+    );
+    market.reclaim(&job, applications[0]).unwrap();
+    assert_eq!(market.svm.lamports(&client), balance + SOL);
+    assert_eq!(market.svm.lamports(&job.escrow), escrow_balance - SOL);
+    assert_eq!(
+        market.svm.account::<Escrow>(&job.escrow).status,
+        EscrowStatus::Funded
+    );
+    assert_eq!(
+        market.svm.account::<JobPost>(&job.post).status,
+        JobStatus::InProgress
+    );
+    assert_eq!(
+        failure(market.reclaim(&job, applications[0])),
+        u32::from(ErrorCode::ApplicationDefaulted)
+    );
+
+    // Lamports sent straight to the escrow go back with the final refund
+    market.svm.airdrop(job.escrow, 1_000);
+    market.reclaim(&job, applications[1]).unwrap();
+    let rent = Rent::default().minimum_balance(8 + Escrow::INIT_SPACE);
+    assert_eq!(market.svm.lamports(&client), balance + 2 * SOL + 1_000);
+    assert_eq!(market.svm.lamports(&job.escrow), rent);
+    assert_eq!(
+        market.svm.account::<Escrow>(&job.escrow).status,
+        EscrowStatus::Refunded
+    );
+    assert_eq!(
+        market.svm.account::<JobPost>(&job.post).status,
+        JobStatus::Cancelled
+    );
+}
+
+#[test]
+fn late_submissions_refund_a_penalty_per_started_day_to_the_client() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    market.set_late_penalty(&job, 500);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+
+    // A day and a half late counts as two days
+    let end_date = market.svm.account::<JobPost>(&job.post).end_date;
+    market
+        .svm
+        .advance(end_date - market.svm.now() + 36 * 60 * 60);
+    market.submit(&job, application);
+    let (client_balance, freelancer_balance) = (
+        market.svm.lamports(&client),
+        market.svm.lamports(&freelancer),
+    );
+    let escrow_rent = Rent::default().minimum_balance(8 + Escrow::INIT_SPACE);
+    market.approve(&job, application).unwrap();
+
+    let penalty = SOL / 10;
+    assert_eq!(
+        market.svm.lamports(&freelancer),
+        freelancer_balance + SOL - penalty
+    );
+    assert_eq!(
+        market.svm.lamports(&client),
+        client_balance + penalty + escrow_rent
+    );
+    assert_eq!(
+        market
+            .svm
+            .account::<Reputation>(&reputation(freelancer))
+            .total_earned,
+        SOL - penalty
+    );
+}
@@ -0,0 +1,55 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+#[test]
+fn unavailable_freelancers_cannot_apply() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+
+    market.set_availability(freelancer, false, 50_000_000);
+    let profile = market
+        .svm
+        .account::<FreelancerProfile>(&freelancer_profile(freelancer));
+    assert!(!profile.available);
+    assert_eq!(profile.hourly_rate, 50_000_000);
+    assert_eq!(
+        failure(market.try_apply(&job, freelancer)),
+        u32::from(ErrorCode::FreelancerUnavailable)
+    );
+
+    market.set_availability(freelancer, true, 50_000_000);
+    let application = market.apply(&job, freelancer);
+    assert_eq!(
+        market.svm.account::<Application>(&application).applicant,
+        freelancer
+    );
+}
+
+#[test]
+fn handles_are_unique_until_released() {
+    let mut market = Market::new();
+    let ada = market.svm.wallet();
+    market.try_register(ada, UserRole::Client, "ada").unwrap();
+    let record = market.svm.account::<UsernameRecord>(&username("ada"));
+    assert_eq!(record.wallet, ada);
+
+    let impostor = market.svm.wallet();
+    assert!(market
+        .try_register(impostor, UserRole::Client, "ada")
+        .is_err());
+    assert_eq!(
+        failure(market.try_register(impostor, UserRole::Client, "Ada")),
+        u32::from(ErrorCode::InvalidHandle)
+    );
+
+    market.close_user(ada).unwrap();
+    assert!(market.svm.get(&username("ada")).is_none());
+    market
+        .try_register(impostor, UserRole::Client, "ada")
+        .unwrap();
+    let record = market.svm.account::<UsernameRecord>(&username("ada"));
+    assert_eq!(record.wallet, impostor);
+}
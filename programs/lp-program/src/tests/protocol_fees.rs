@@ -0,0 +1,90 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+const CLIENT_FEE_BPS: u16 = 200;
+const FREELANCER_FEE_BPS: u16 = 500;
+
+#[test]
+fn approval_charges_both_fee_sides_and_claim_fees_pays_the_treasury() {
+    let mut market = Market::new();
+    let fee_treasury = market.set_fee(CLIENT_FEE_BPS, FREELANCER_FEE_BPS);
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    let client_fee = bps_of(SOL, CLIENT_FEE_BPS);
+    assert_eq!(
+        market.svm.account::<JobPost>(&job.post).client_fee,
+        client_fee
+    );
+    assert_eq!(
+        market.svm.account::<Escrow>(&job.escrow).expected_amount,
+        SOL + client_fee
+    );
+
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+    market.submit(&job, application);
+    let escrow_rent = market.svm.lamports(&job.escrow) - SOL - client_fee;
+    let (freelancer_balance, client_balance, config_balance) = (
+        market.svm.lamports(&freelancer),
+        market.svm.lamports(&client),
+        market.svm.lamports(&config()),
+    );
+    market.approve(&job, application).unwrap();
+
+    // The client only gets the escrow's rent back; both fees accrue on the config
+    let freelancer_fee = bps_of(SOL, FREELANCER_FEE_BPS);
+    assert_eq!(
+        market.svm.lamports(&freelancer),
+        freelancer_balance + SOL - freelancer_fee
+    );
+    assert_eq!(market.svm.lamports(&client), client_balance + escrow_rent);
+    assert_eq!(
+        market.svm.lamports(&config()),
+        config_balance + freelancer_fee + client_fee
+    );
+    assert_eq!(
+        market.svm.account::<Config>(&config()).accrued_fees,
+        freelancer_fee + client_fee
+    );
+    assert_eq!(
+        market.svm.account::<Application>(&application).fees_paid,
+        freelancer_fee
+    );
+
+    let treasury_balance = market.svm.lamports(&fee_treasury);
+    market.claim_fees().unwrap();
+    assert_eq!(
+        market.svm.lamports(&fee_treasury),
+        treasury_balance + freelancer_fee + client_fee
+    );
+    assert_eq!(market.svm.lamports(&config()), config_balance);
+    assert_eq!(market.svm.account::<Config>(&config()).accrued_fees, 0);
+    assert_eq!(
+        failure(market.claim_fees()),
+        u32::from(ErrorCode::NothingToClaim)
+    );
+}
+
+#[test]
+fn cancelling_refunds_the_client_fee_and_accrues_nothing() {
+    let mut market = Market::new();
+    market.set_fee(CLIENT_FEE_BPS, FREELANCER_FEE_BPS);
+    let client = market.register(UserRole::Client);
+    let job = market.post_job(client, SOL);
+
+    let (client_balance, config_balance) =
+        (market.svm.lamports(&client), market.svm.lamports(&config()));
+    market.cancel_job(&job);
+    assert_eq!(
+        market.svm.lamports(&client),
+        client_balance + SOL + bps_of(SOL, CLIENT_FEE_BPS)
+    );
+    assert_eq!(market.svm.lamports(&config()), config_balance);
+    assert_eq!(market.svm.account::<Config>(&config()).accrued_fees, 0);
+    assert_eq!(
+        failure(market.claim_fees()),
+        u32::from(ErrorCode::NothingToClaim)
+    );
+}
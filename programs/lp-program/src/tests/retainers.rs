@@ -0,0 +1,41 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+const DAY: i64 = 24 * 60 * 60;
+const PERIOD: u64 = SOL / 10;
+
+#[test]
+fn retainer_pays_elapsed_periods_and_refunds_the_rest_on_cancel() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let balance = market.svm.lamports(&client);
+    let retainer = market.create_retainer(client, freelancer, PERIOD, DAY, 4);
+    let rent = Rent::default().minimum_balance(8 + Retainer::INIT_SPACE);
+    assert_eq!(market.svm.lamports(&client), balance - 4 * PERIOD - rent);
+    assert_eq!(market.svm.lamports(&retainer), 4 * PERIOD + rent);
+
+    assert_eq!(
+        failure(market.claim_period_payment(retainer)),
+        u32::from(ErrorCode::NothingToClaim)
+    );
+    market.svm.advance(5 * DAY / 2);
+    let freelancer_balance = market.svm.lamports(&freelancer);
+    market.claim_period_payment(retainer).unwrap();
+    assert_eq!(
+        market.svm.lamports(&freelancer),
+        freelancer_balance + 2 * PERIOD
+    );
+    assert_eq!(market.svm.account::<Retainer>(&retainer).periods_claimed, 2);
+
+    // The third period has elapsed but was never claimed; the fourth has not
+    market.svm.advance(DAY);
+    market.cancel_retainer(retainer).unwrap();
+    assert!(market.svm.get(&retainer).is_none());
+    assert_eq!(
+        market.svm.lamports(&freelancer),
+        freelancer_balance + 3 * PERIOD
+    );
+    assert_eq!(market.svm.lamports(&client), balance - 3 * PERIOD);
+}
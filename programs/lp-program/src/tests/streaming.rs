@@ -0,0 +1,78 @@
+use super::harness::*;
+use super::market::*;
+use crate::*;
+
+#[test]
+fn streamed_budget_vests_linearly_and_approval_pays_the_rest() {
+    let mut market = Market::new();
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    market.set_streaming(&job, true);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+
+    // Nothing vests before the start date
+    assert_eq!(
+        failure(market.claim_vested(&job, application)),
+        u32::from(ErrorCode::NothingToClaim)
+    );
+    let post = market.svm.account::<JobPost>(&job.post);
+    let halfway = post.start_date + (post.end_date - post.start_date) / 2;
+    market.svm.advance(halfway - market.svm.now());
+    let (balance, escrow_balance) = (
+        market.svm.lamports(&freelancer),
+        market.svm.lamports(&job.escrow),
+    );
+    market.claim_vested(&job, application).unwrap();
+    assert_eq!(market.svm.lamports(&freelancer), balance + SOL / 2);
+    assert_eq!(market.svm.lamports(&job.escrow), escrow_balance - SOL / 2);
+    assert_eq!(
+        failure(market.claim_vested(&job, application)),
+        u32::from(ErrorCode::NothingToClaim)
+    );
+
+    market.submit(&job, application);
+    let balance = market.svm.lamports(&freelancer);
+    market.approve(&job, application).unwrap();
+    assert_eq!(market.svm.lamports(&freelancer), balance + SOL / 2);
+    assert_eq!(
+        market
+            .svm
+            .account::<Application>(&application)
+            .released_amount,
+        SOL
+    );
+}
+
+#[test]
+fn vested_claims_accrue_the_freelancer_fee() {
+    let mut market = Market::new();
+    let fee_treasury = market.set_fee(0, 500);
+    let client = market.register(UserRole::Client);
+    let freelancer = market.register(UserRole::Freelancer);
+    let job = market.post_job(client, SOL);
+    market.set_streaming(&job, true);
+    let application = market.apply(&job, freelancer);
+    market.hire(&job, application);
+
+    let post = market.svm.account::<JobPost>(&job.post);
+    let halfway = post.start_date + (post.end_date - post.start_date) / 2;
+    market.svm.advance(halfway - market.svm.now());
+    let (balance, config_balance) = (
+        market.svm.lamports(&freelancer),
+        market.svm.lamports(&config()),
+    );
+    market.claim_vested(&job, application).unwrap();
+    let fee = SOL / 2 / 20;
+    assert_eq!(market.svm.lamports(&freelancer), balance + SOL / 2 - fee);
+    assert_eq!(market.svm.lamports(&config()), config_balance + fee);
+    assert_eq!(market.svm.account::<Config>(&config()).accrued_fees, fee);
+    let claimed = market.svm.account::<Application>(&application);
+    assert_eq!(claimed.released_amount, SOL / 2);
+    assert_eq!(claimed.fees_paid, fee);
+
+    let treasury_balance = market.svm.lamports(&fee_treasury);
+    market.claim_fees().unwrap();
+    assert_eq!(market.svm.lamports(&fee_treasury), treasury_balance + fee);
+}